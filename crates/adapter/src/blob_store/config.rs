@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use frog_core::errors::CoreError;
+use frog_core::ports::blob_store::BlobStorePort;
+use serde::Deserialize;
+
+use crate::blob_store::fs::FsBlobStore;
+use crate::blob_store::s3::S3BlobStore;
+
+/// Configuration of the storage holding key material and ciphertexts.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BlobStoreConfig {
+    /// Blobs are stored as files under a local directory.
+    Fs {
+        /// Directory the blobs are stored in.
+        root: String,
+    },
+    /// Blobs are stored as objects of an S3-compatible bucket, e.g. on MinIO.
+    S3 {
+        /// Name of the bucket, created if it does not exist.
+        bucket: String,
+        /// Region of the bucket.
+        region: String,
+        /// Endpoint of the S3 API.
+        endpoint: String,
+        /// Access key of the S3 API.
+        access_key: String,
+        /// Secret key of the S3 API.
+        secret_key: String,
+    },
+}
+
+impl BlobStoreConfig {
+    /// Creates the blob store described by this configuration.
+    pub async fn build(&self) -> Result<Arc<dyn BlobStorePort + Send + Sync>, CoreError> {
+        match self {
+            BlobStoreConfig::Fs { root } => Ok(Arc::new(FsBlobStore::new(root.into()))),
+            BlobStoreConfig::S3 {
+                bucket,
+                region,
+                endpoint,
+                access_key,
+                secret_key,
+            } => Ok(Arc::new(
+                S3BlobStore::new(bucket, region, endpoint, access_key, secret_key).await?,
+            )),
+        }
+    }
+}
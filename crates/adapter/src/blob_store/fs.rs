@@ -0,0 +1,110 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use frog_core::errors::CoreError;
+use frog_core::ports::blob_store::BlobStorePort;
+use tokio::fs;
+use uuid::Uuid;
+
+/// Blob store keeping each blob in a file under a root directory.
+///
+/// The segments of a key map to nested directories. Files are written to a temporary path and
+/// renamed, so readers never see a partially written blob.
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Resolves the path of a key, rejecting keys escaping the root directory.
+    fn path(&self, key: &str) -> Result<PathBuf, CoreError> {
+        if key
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            return Err(CoreError::ValidationFail(format!(
+                "Invalid blob key: {}",
+                key
+            )));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStorePort for FsBlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), CoreError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| CoreError::InternalError(e.into()))?;
+        }
+
+        let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        fs::write(&temp_path, data)
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        fs::rename(&temp_path, &path)
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, CoreError> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(CoreError::NotFound),
+            Err(e) => Err(CoreError::InternalError(e.into())),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, CoreError> {
+        let mut keys = Vec::new();
+        let mut directories = vec![(prefix.to_string(), self.path(prefix)?)];
+        while let Some((key_prefix, directory)) = directories.pop() {
+            let mut entries = match fs::read_dir(&directory).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(CoreError::InternalError(e.into())),
+            };
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| CoreError::InternalError(e.into()))?
+            {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let file_type = entry
+                    .file_type()
+                    .await
+                    .map_err(|e| CoreError::InternalError(e.into()))?;
+                if file_type.is_dir() {
+                    directories.push((format!("{}/{}", key_prefix, name), entry.path()));
+                } else if !name.ends_with(".tmp") {
+                    // Temporary files belong to blobs being written.
+                    keys.push(format!("{}/{}", key_prefix, name));
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CoreError> {
+        match fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(CoreError::InternalError(e.into())),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), CoreError> {
+        match fs::remove_dir_all(self.path(prefix)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(CoreError::InternalError(e.into())),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod config;
+pub mod fs;
+pub mod s3;
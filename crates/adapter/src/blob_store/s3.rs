@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use frog_core::errors::CoreError;
+use frog_core::ports::blob_store::BlobStorePort;
+use s3::creds::Credentials;
+use s3::error::S3Error;
+use s3::{Bucket, BucketConfiguration, Region};
+
+/// Blob store keeping each blob as an object of an S3-compatible bucket.
+///
+/// Path-style addressing is used, so that self-hosted services such as MinIO work without
+/// DNS configuration.
+pub struct S3BlobStore {
+    bucket: Box<Bucket>,
+}
+
+impl S3BlobStore {
+    /// Connects to a bucket, creating it if it does not exist.
+    ///
+    /// # Arguments
+    /// - `bucket_name`: The name of the bucket.
+    /// - `region`: The region of the bucket.
+    /// - `endpoint`: The endpoint of the S3 API.
+    /// - `access_key`: The access key of the S3 API.
+    /// - `secret_key`: The secret key of the S3 API.
+    pub async fn new(
+        bucket_name: &str,
+        region: &str,
+        endpoint: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<Self, CoreError> {
+        let region = Region::Custom {
+            region: region.to_string(),
+            endpoint: endpoint.to_string(),
+        };
+        let credentials = Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        let bucket = Bucket::new(bucket_name, region.clone(), credentials.clone())
+            .map_err(map_s3_error)?
+            .with_path_style();
+        if !bucket.exists().await.map_err(map_s3_error)? {
+            Bucket::create_with_path_style(
+                bucket_name,
+                region,
+                credentials,
+                BucketConfiguration::default(),
+            )
+            .await
+            .map_err(map_s3_error)?;
+        }
+
+        Ok(Self { bucket })
+    }
+}
+
+fn map_s3_error(err: S3Error) -> CoreError {
+    match err {
+        S3Error::HttpFailWithBody(404, _) => CoreError::NotFound,
+        _ => CoreError::InternalError(err.into()),
+    }
+}
+
+#[async_trait]
+impl BlobStorePort for S3BlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), CoreError> {
+        self.bucket
+            .put_object(key, &data)
+            .await
+            .map_err(map_s3_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, CoreError> {
+        let response = self.bucket.get_object(key).await.map_err(map_s3_error)?;
+        Ok(response.bytes().to_vec())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, CoreError> {
+        let pages = self
+            .bucket
+            .list(format!("{}/", prefix), None)
+            .await
+            .map_err(map_s3_error)?;
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .map(|object| object.key)
+            .collect())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CoreError> {
+        match self.bucket.delete_object(key).await.map_err(map_s3_error) {
+            Ok(_) | Err(CoreError::NotFound) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), CoreError> {
+        for key in self.list(prefix).await? {
+            self.delete(&key).await?;
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,25 @@
+use frog_common::blob::is_octet_stream;
+use frog_core::errors::CoreError;
+use reqwest::Response;
+
 pub mod peer;
 pub mod session;
+
+/// Reads a binary response body.
+///
+/// The body is read as raw bytes when sent as `application/octet-stream`, and as a JSON array
+/// of bytes otherwise, for older servers and peers.
+pub async fn read_blob(response: Response) -> Result<Vec<u8>, CoreError> {
+    if is_octet_stream(response.headers()) {
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        Ok(bytes.to_vec())
+    } else {
+        response
+            .json()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))
+    }
+}
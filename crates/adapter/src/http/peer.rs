@@ -1,21 +1,43 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use frog_common::blob::OCTET_STREAM;
+use frog_core::entities::client::ClientId;
+use frog_core::entities::session::SessionId;
 use frog_core::errors::CoreError;
 use frog_core::ports::peer::PeerPort;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::Client;
 
+use crate::http::read_blob;
+
+/// Exchanges decryption shares directly with the HTTP servers of the peers.
+///
+/// The client's own share is served by its HTTP server, so publishing it is a no-op.
 pub struct PeerClient {
     client: Client,
+    peer_endpoints: HashMap<ClientId, String>,
 }
 
 impl PeerClient {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, peer_endpoints: HashMap<ClientId, String>) -> Self {
+        Self {
+            client,
+            peer_endpoints,
+        }
     }
 }
 
 #[async_trait]
 impl PeerPort for PeerClient {
-    async fn get_dec_share(&self, peer_endpoint: &str) -> Result<Vec<u8>, CoreError> {
+    async fn publish_dec_share(&self, _dec_share: Vec<u8>) -> Result<(), CoreError> {
+        Ok(())
+    }
+
+    async fn get_dec_share(&self, peer_id: &ClientId) -> Result<Vec<u8>, CoreError> {
+        let peer_endpoint = self.peer_endpoints.get(peer_id).ok_or_else(|| {
+            CoreError::ValidationFail(format!("No endpoint configured for peer {}", peer_id))
+        })?;
         let response = self
             .client
             .get(format!("{}/decrypt_share", peer_endpoint))
@@ -31,9 +53,78 @@ impl PeerPort for PeerClient {
             )));
         }
 
-        response
-            .json()
+        read_blob(response).await
+    }
+}
+
+/// Exchanges decryption shares through the server, so peers do not need to reach each other.
+pub struct RelayPeerClient {
+    client: Client,
+    server_endpoint: String,
+    session_id: SessionId,
+    client_id: ClientId,
+}
+
+impl RelayPeerClient {
+    pub fn new(
+        client: Client,
+        server_endpoint: String,
+        session_id: SessionId,
+        client_id: ClientId,
+    ) -> Self {
+        Self {
+            client,
+            server_endpoint,
+            session_id,
+            client_id,
+        }
+    }
+
+    /// Builds the URL of the decryption share of a client on the server.
+    fn dec_share_url(&self, client_id: &ClientId) -> String {
+        format!(
+            "{}/v1/sessions/{}/clients/{}/dec_share",
+            &self.server_endpoint, self.session_id, client_id
+        )
+    }
+}
+
+#[async_trait]
+impl PeerPort for RelayPeerClient {
+    async fn publish_dec_share(&self, dec_share: Vec<u8>) -> Result<(), CoreError> {
+        let response = self
+            .client
+            .put(self.dec_share_url(&self.client_id))
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(dec_share)
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        if !response.status().is_success() {
+            return Err(CoreError::UnexpectedResponse(format!(
+                "Received non-success status: {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get_dec_share(&self, peer_id: &ClientId) -> Result<Vec<u8>, CoreError> {
+        let response = self
+            .client
+            .get(self.dec_share_url(peer_id))
+            .send()
             .await
-            .map_err(|e| CoreError::InternalError(e.into()))
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        if !response.status().is_success() {
+            return Err(CoreError::UnexpectedResponse(format!(
+                "Received non-success status: {}",
+                response.status()
+            )));
+        }
+
+        read_blob(response).await
     }
 }
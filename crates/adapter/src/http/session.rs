@@ -1,12 +1,26 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
+use frog_common::blob::OCTET_STREAM;
 use frog_core::entities::client::{ClientEntity, ClientId};
-use frog_core::entities::session::{SessionEntity, SessionId};
+use frog_core::entities::computation::{ComputationId, ComputationSummary};
+use frog_core::entities::params::ParamsFingerprint;
+use frog_core::entities::session::{SessionId, SessionStatus};
+use frog_core::entities::session_event::SessionEvent;
+use frog_core::entities::session_summary::SessionSummary;
 use frog_core::errors::CoreError;
 use frog_core::errors::CoreError::UnexpectedResponse;
 use frog_core::ports::session_client::SessionClientPort;
-use log::error;
+use futures::StreamExt;
+use log::{error, warn};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
 use reqwest::{Client, StatusCode};
 
+use crate::http::read_blob;
+
+/// Delay before following the events of a session again after the stream was interrupted.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
 pub struct SessionClient {
     server_endpoint: String,
     client: Client,
@@ -34,22 +48,121 @@ impl SessionClient {
         }
         Ok(body)
     }
+
+    /// Downloads a binary resource of a session, e.g. its aggregated public key.
+    async fn get_resource(
+        &self,
+        session_id: &SessionId,
+        resource: &str,
+    ) -> Result<Vec<u8>, CoreError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/v1/sessions/{}/{}",
+                &self.server_endpoint, session_id, resource
+            ))
+            .header(ACCEPT, OCTET_STREAM)
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        if response.status() != StatusCode::OK {
+            let body = self.handle_response(response).await?;
+            return Err(UnexpectedResponse(body));
+        }
+        read_blob(response).await
+    }
+
+    /// Follows the event stream of a session until an event matches `is_awaited`.
+    ///
+    /// # Returns
+    /// - `Ok(Some(SessionEvent))` with the awaited event.
+    /// - `Ok(None)` if the stream ended before the awaited event was received.
+    async fn follow_events(
+        &self,
+        session_id: &SessionId,
+        is_awaited: impl Fn(&SessionEvent) -> bool,
+    ) -> Result<Option<SessionEvent>, CoreError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/v1/sessions/{}/events",
+                &self.server_endpoint, session_id
+            ))
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        if response.status() != StatusCode::OK {
+            let body = self.handle_response(response).await?;
+            return Err(UnexpectedResponse(body));
+        }
+
+        // Server-sent events are separated by a blank line, and chunks may split them anywhere.
+        let mut body = response.bytes_stream();
+        let mut buffer = Vec::new();
+        while let Some(chunk) = body.next().await {
+            buffer.extend_from_slice(&chunk.map_err(|e| CoreError::InternalError(e.into()))?);
+            while let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+                let message = buffer.drain(..end + 2).collect::<Vec<_>>();
+                if let Some(event) = parse_event(&message)? {
+                    if is_awaited(&event) {
+                        return Ok(Some(event));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Parses the session event carried by a server-sent event message.
+///
+/// # Returns
+/// - `Ok(None)` if the message carries no data, e.g. a keep-alive comment.
+fn parse_event(message: &[u8]) -> Result<Option<SessionEvent>, CoreError> {
+    let data = String::from_utf8_lossy(message)
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if data.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| CoreError::InternalError(e.into()))
 }
 
 #[async_trait]
 impl SessionClientPort for SessionClient {
+    async fn get_params(&self) -> Result<ParamsFingerprint, CoreError> {
+        let response = self
+            .client
+            .get(format!("{}/v1/params", &self.server_endpoint))
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let body = self.handle_response(response).await?;
+        serde_json::from_str(&body).map_err(|e| CoreError::InternalError(e.into()))
+    }
+
     async fn join_session(
         &self,
         session_id: SessionId,
         client_entity: ClientEntity,
     ) -> Result<(), CoreError> {
+        // The key shares are sent as a bincode body, which is far smaller than JSON arrays.
+        let body =
+            bincode::serialize(&client_entity).map_err(|e| CoreError::InternalError(e.into()))?;
         let response = self
             .client
             .put(format!(
                 "{}/v1/sessions/{}",
                 &self.server_endpoint, session_id
             ))
-            .json(&client_entity)
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(body)
             .send()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
@@ -58,7 +171,7 @@ impl SessionClientPort for SessionClient {
         Ok(())
     }
 
-    async fn get_session(&self, session_id: SessionId) -> Result<SessionEntity, CoreError> {
+    async fn get_session(&self, session_id: SessionId) -> Result<SessionSummary, CoreError> {
         let response = self
             .client
             .get(format!(
@@ -69,11 +182,41 @@ impl SessionClientPort for SessionClient {
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
         response
-            .json::<SessionEntity>()
+            .json::<SessionSummary>()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))
     }
 
+    async fn get_pk(&self, session_id: SessionId) -> Result<Vec<u8>, CoreError> {
+        self.get_resource(&session_id, "pk").await
+    }
+
+    async fn get_encrypted_result(&self, session_id: SessionId) -> Result<Vec<Vec<u8>>, CoreError> {
+        let encrypted_result = self.get_resource(&session_id, "result").await?;
+        bincode::deserialize(&encrypted_result).map_err(|e| CoreError::InternalError(e.into()))
+    }
+
+    async fn wait_for_status(
+        &self,
+        session_id: SessionId,
+        status: SessionStatus,
+    ) -> Result<SessionEvent, CoreError> {
+        loop {
+            let awaited = self
+                .follow_events(&session_id, |event| {
+                    event.status == status || event.status.is_terminal()
+                })
+                .await?;
+            if let Some(event) = awaited {
+                return Ok(event);
+            }
+
+            // The stream was interrupted, e.g. by a server restart, so follow it again.
+            warn!("Events of session {} interrupted, reconnecting", session_id);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
     async fn bootstrap(
         &self,
         session_id: SessionId,
@@ -86,7 +229,8 @@ impl SessionClientPort for SessionClient {
                 "{}/v1/sessions/{}/clients/{}/bootstrap",
                 &self.server_endpoint, session_id, client_id
             ))
-            .json(&bs_key)
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(bs_key)
             .send()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
@@ -107,7 +251,8 @@ impl SessionClientPort for SessionClient {
                 "{}/v1/sessions/{}/clients/{}/data",
                 &self.server_endpoint, session_id, client_id
             ))
-            .json(&data)
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(data)
             .send()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
@@ -115,4 +260,112 @@ impl SessionClientPort for SessionClient {
         self.handle_response(response).await?;
         Ok(())
     }
+
+    async fn create_computation(&self, session_id: SessionId) -> Result<ComputationId, CoreError> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/v1/sessions/{}/computations",
+                &self.server_endpoint, session_id
+            ))
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let body = self.handle_response(response).await?;
+        serde_json::from_str(&body).map_err(|e| CoreError::InternalError(e.into()))
+    }
+
+    async fn get_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<ComputationSummary, CoreError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/v1/sessions/{}/computations/{}",
+                &self.server_endpoint, session_id, computation_id
+            ))
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let body = self.handle_response(response).await?;
+        serde_json::from_str(&body).map_err(|e| CoreError::InternalError(e.into()))
+    }
+
+    async fn send_computation_data(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        data: Vec<u8>,
+    ) -> Result<(), CoreError> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/v1/sessions/{}/computations/{}/clients/{}/data",
+                &self.server_endpoint, session_id, computation_id, client_id
+            ))
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        self.handle_response(response).await?;
+        Ok(())
+    }
+
+    async fn get_computation_result(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<Vec<Vec<u8>>, CoreError> {
+        let encrypted_result = self
+            .get_resource(
+                &session_id,
+                &format!("computations/{}/result", computation_id),
+            )
+            .await?;
+        bincode::deserialize(&encrypted_result).map_err(|e| CoreError::InternalError(e.into()))
+    }
+
+    async fn send_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        dec_share: Vec<u8>,
+    ) -> Result<(), CoreError> {
+        let response = self
+            .client
+            .put(format!(
+                "{}/v1/sessions/{}/computations/{}/clients/{}/dec_share",
+                &self.server_endpoint, session_id, computation_id, client_id
+            ))
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(dec_share)
+            .send()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        self.handle_response(response).await?;
+        Ok(())
+    }
+
+    async fn get_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+    ) -> Result<Vec<u8>, CoreError> {
+        self.get_resource(
+            &session_id,
+            &format!(
+                "computations/{}/clients/{}/dec_share",
+                computation_id, client_id
+            ),
+        )
+        .await
+    }
 }
@@ -1,2 +1,3 @@
 pub mod session;
+pub mod session_event;
 pub mod state;
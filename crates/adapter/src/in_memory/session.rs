@@ -1,9 +1,18 @@
 use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
 
 use async_trait::async_trait;
+use frog_core::entities::blob::{content_hash, BlobRef};
+use frog_core::entities::client::{ClientArtifact, ClientEntity, ClientId};
+use frog_core::entities::computation::ComputationEntity;
 use frog_core::entities::session::{SessionEntity, SessionId};
+use frog_core::entities::session_event::SessionEvent;
+use frog_core::entities::session_query::{SessionCursor, SessionPage, SessionQuery};
+use frog_core::entities::session_summary::SessionSummary;
 use frog_core::errors::CoreError;
 use frog_core::ports::session::SessionPort;
+use phantom::native_ops::NativeOps;
+use phantom::server::PhantomServer;
 
 use crate::in_memory::state::InMemoryState;
 
@@ -40,19 +49,281 @@ impl SessionPort for SessionInMemoryRepository {
         Ok(result)
     }
 
+    async fn get_without_server(&self, session_id: SessionId) -> Result<SessionEntity, CoreError> {
+        let mut result = self.get(session_id).await?;
+        result.phantom_server = None;
+        Ok(result)
+    }
+
+    async fn get_summary(&self, session_id: SessionId) -> Result<SessionSummary, CoreError> {
+        let session_entity = self.get_without_server(session_id).await?;
+        Ok(SessionSummary::from(&session_entity))
+    }
+
+    async fn get_pk_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError> {
+        let inner_state = self.inner_state.read().unwrap();
+        let session_entity = inner_state
+            .sessions
+            .get(&session_id)
+            .ok_or(CoreError::NotFound)?;
+        if session_entity.pk.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(BlobRef::new(
+            &blob_namespace(&session_id),
+            &session_entity.pk,
+        )))
+    }
+
+    async fn get_result_refs(&self, session_id: SessionId) -> Result<Vec<BlobRef>, CoreError> {
+        let inner_state = self.inner_state.read().unwrap();
+        let session_entity = inner_state
+            .sessions
+            .get(&session_id)
+            .ok_or(CoreError::NotFound)?;
+        let namespace = blob_namespace(&session_id);
+        Ok(session_entity
+            .encrypted_result
+            .iter()
+            .map(|encrypted_result| BlobRef::new(&namespace, encrypted_result))
+            .collect())
+    }
+
+    async fn get_blob(&self, blob_ref: &BlobRef) -> Result<Vec<u8>, CoreError> {
+        let session_id = blob_session_id(blob_ref)?;
+        let inner_state = self.inner_state.read().unwrap();
+        let session_entity = inner_state
+            .sessions
+            .get(&session_id)
+            .ok_or(CoreError::NotFound)?;
+        let data = std::iter::once(&session_entity.pk)
+            .chain(&session_entity.encrypted_result)
+            .find(|data| content_hash(data) == blob_ref.hash)
+            .ok_or(CoreError::NotFound)?;
+        Ok(data.clone())
+    }
+
+    async fn get_server_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError> {
+        let inner_state = self.inner_state.read().unwrap();
+        let session_entity = inner_state
+            .sessions
+            .get(&session_id)
+            .ok_or(CoreError::NotFound)?;
+        session_entity
+            .phantom_server
+            .as_ref()
+            .map(|phantom_server| phantom_server_ref(&session_id, phantom_server))
+            .transpose()
+    }
+
+    async fn get_server(
+        &self,
+        server_ref: &BlobRef,
+    ) -> Result<PhantomServer<NativeOps>, CoreError> {
+        let session_id = blob_session_id(server_ref)?;
+        let inner_state = self.inner_state.read().unwrap();
+        let phantom_server = inner_state
+            .sessions
+            .get(&session_id)
+            .and_then(|session_entity| session_entity.phantom_server.as_ref())
+            .ok_or(CoreError::NotFound)?;
+
+        // The server may have been replaced since its reference was read.
+        if phantom_server_ref(&session_id, phantom_server)? != *server_ref {
+            return Err(CoreError::NotFound);
+        }
+        Ok(phantom_server.clone())
+    }
+
+    async fn get_client(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+    ) -> Result<ClientEntity, CoreError> {
+        let result = self
+            .inner_state
+            .read()
+            .unwrap()
+            .sessions
+            .get(&session_id)
+            .and_then(|session_entity| session_entity.client_info.get(&client_id))
+            .ok_or(CoreError::NotFound)?
+            .clone();
+        Ok(result)
+    }
+
+    async fn list(&self, query: SessionQuery) -> Result<SessionPage<SessionSummary>, CoreError> {
+        let mut sessions = self
+            .inner_state
+            .read()
+            .unwrap()
+            .sessions
+            .values()
+            .filter(|session_entity| query.matches(session_entity))
+            .filter(|session_entity| {
+                query.cursor.as_ref().is_none_or(|cursor| {
+                    (&session_entity.created_at, &session_entity.id.0)
+                        > (&cursor.created_at, &cursor.id.0)
+                })
+            })
+            .map(SessionSummary::from)
+            .collect::<Vec<_>>();
+        sessions.sort_by(|a, b| (a.created_at, a.id.0).cmp(&(b.created_at, b.id.0)));
+
+        // Only hand out a cursor when more sessions are left after this page.
+        let next_cursor = if sessions.len() > query.limit {
+            sessions.truncate(query.limit);
+            sessions.last().map(SessionCursor::after)
+        } else {
+            None
+        };
+
+        Ok(SessionPage {
+            sessions,
+            next_cursor,
+        })
+    }
+
+    async fn list_overdue(
+        &self,
+        now: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<SessionId>, CoreError> {
+        let result = self
+            .inner_state
+            .read()
+            .unwrap()
+            .sessions
+            .values()
+            .filter(|session_entity| session_entity.is_overdue(now))
+            .map(|session_entity| session_entity.id.clone())
+            .take(limit)
+            .collect();
+        Ok(result)
+    }
+
     async fn update(
         &self,
         session_id: SessionId,
         mut session_entity: SessionEntity,
     ) -> Result<SessionId, CoreError> {
         session_entity.id = session_id.clone();
-        *(self
-            .inner_state
-            .write()
-            .unwrap()
+        let mut inner_state = self.inner_state.write().unwrap();
+        let stored_entity = inner_state
             .sessions
             .get_mut(&session_id)
-            .ok_or(CoreError::NotFound)?) = session_entity.clone();
+            .ok_or(CoreError::NotFound)?;
+
+        // Reject the update if the session changed since it was read.
+        if stored_entity.version != session_entity.version {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+
+        session_entity.version += 1;
+        let status_changed = stored_entity.status != session_entity.status;
+        *stored_entity = session_entity;
+
+        // Notify the subscribers of the session, like the database trigger does.
+        if status_changed {
+            let event = SessionEvent::from(&*stored_entity);
+            inner_state.events.publish(event);
+        }
+        Ok(session_id)
+    }
+
+    async fn set_client_artifact(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+        artifact: ClientArtifact,
+        data: Vec<u8>,
+    ) -> Result<bool, CoreError> {
+        let mut inner_state = self.inner_state.write().unwrap();
+        let stored_entity = inner_state
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(CoreError::NotFound)?;
+
+        if stored_entity.status != artifact.phase() {
+            return Err(CoreError::ValidationFail(format!(
+                "Session {} is in status {}, expected {}",
+                session_id,
+                stored_entity.status,
+                artifact.phase()
+            )));
+        }
+
+        let was_complete = is_complete(stored_entity, artifact);
+        let client_entity = stored_entity
+            .client_info
+            .get_mut(&client_id)
+            .ok_or(CoreError::NotFound)?;
+        *artifact.of_mut(client_entity) = data;
+        stored_entity.version += 1;
+        Ok(!was_complete && is_complete(stored_entity, artifact))
+    }
+
+    async fn set_result(
+        &self,
+        session_id: SessionId,
+        session_entity: SessionEntity,
+    ) -> Result<SessionId, CoreError> {
+        let mut inner_state = self.inner_state.write().unwrap();
+        let stored_entity = inner_state
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(CoreError::NotFound)?;
+
+        // Reject the update if the session changed since it was read.
+        if stored_entity.version != session_entity.version {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+
+        let status_changed = stored_entity.status != session_entity.status;
+        stored_entity.status = session_entity.status;
+        stored_entity.phase_deadline = session_entity.phase_deadline;
+        stored_entity.result_format = session_entity.result_format;
+        stored_entity.encrypted_result = session_entity.encrypted_result;
+        stored_entity.version += 1;
+
+        // Notify the subscribers of the session, like the database trigger does.
+        if status_changed {
+            let event = SessionEvent::from(&*stored_entity);
+            inner_state.events.publish(event);
+        }
+        Ok(session_id)
+    }
+
+    async fn set_computation(
+        &self,
+        session_id: SessionId,
+        version: u64,
+        computation_entity: ComputationEntity,
+    ) -> Result<SessionId, CoreError> {
+        let mut inner_state = self.inner_state.write().unwrap();
+        let stored_entity = inner_state
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(CoreError::NotFound)?;
+
+        // Reject the update if the session changed since it was read.
+        if stored_entity.version != version {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+
+        stored_entity
+            .computations
+            .insert(computation_entity.id, computation_entity);
+        stored_entity.version += 1;
         Ok(session_id)
     }
 
@@ -66,3 +337,38 @@ impl SessionPort for SessionInMemoryRepository {
         Ok(())
     }
 }
+
+/// Prefix of the keys of all the blobs of a session, as the persistent repository names them.
+fn blob_namespace(session_id: &SessionId) -> String {
+    format!("sessions/{}", session_id)
+}
+
+/// Finds the session a blob belongs to from the namespace of its key.
+fn blob_session_id(blob_ref: &BlobRef) -> Result<SessionId, CoreError> {
+    blob_ref
+        .key
+        .strip_prefix("sessions/")
+        .and_then(|key| key.split_once('/'))
+        .ok_or(CoreError::NotFound)
+        .and_then(|(session_id, _)| SessionId::try_from(session_id))
+}
+
+/// Builds the reference the Phantom server of a session would be stored under.
+fn phantom_server_ref(
+    session_id: &SessionId,
+    phantom_server: &PhantomServer<NativeOps>,
+) -> Result<BlobRef, CoreError> {
+    let data =
+        bincode::serialize(phantom_server).map_err(|err| CoreError::InternalError(err.into()))?;
+    Ok(BlobRef::new(&blob_namespace(session_id), &data))
+}
+
+/// Checks whether every participant of a session provided a non-empty artifact.
+fn is_complete(session_entity: &SessionEntity, artifact: ClientArtifact) -> bool {
+    session_entity
+        .client_info
+        .values()
+        .filter(|client_entity| !artifact.of(client_entity).is_empty())
+        .count()
+        == session_entity.settings.participant_number
+}
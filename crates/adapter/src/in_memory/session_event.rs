@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use frog_core::entities::session::SessionId;
+use frog_core::entities::session_event::SessionEvent;
+use frog_core::errors::CoreError;
+use frog_core::ports::session_event::{SessionEventPort, SessionEventStream};
+use futures::stream::{self, StreamExt};
+use log::warn;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Number of events buffered for each subscriber before it is considered lagging.
+const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// Message carried by the bus.
+#[derive(Clone)]
+enum BusMessage {
+    /// A session changed.
+    Event(SessionEvent),
+    /// Events may have been lost, so every subscription must end.
+    Reset,
+}
+
+/// In-process bus delivering session events to subscribers.
+///
+/// Events are published by the in-memory repository, or by a listener relaying the events
+/// of another storage.
+#[derive(Clone)]
+pub struct SessionEventInMemoryBus {
+    sender: broadcast::Sender<BusMessage>,
+}
+
+impl SessionEventInMemoryBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+
+    /// Delivers an event to the current subscribers, if any.
+    pub fn publish(&self, event: SessionEvent) {
+        // Sending only fails when nobody is subscribed.
+        let _ = self.sender.send(BusMessage::Event(event));
+    }
+
+    /// Ends every current subscription.
+    ///
+    /// Used when events may have been lost, e.g. because the connection to the storage
+    /// notifying them was interrupted, so that subscribers read their sessions again.
+    pub fn reset(&self) {
+        let _ = self.sender.send(BusMessage::Reset);
+    }
+}
+
+impl Default for SessionEventInMemoryBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SessionEventPort for SessionEventInMemoryBus {
+    async fn subscribe(&self, session_id: SessionId) -> Result<SessionEventStream, CoreError> {
+        let receiver = self.sender.subscribe();
+        let events = stream::unfold(receiver, move |mut receiver| {
+            let session_id = session_id.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(BusMessage::Event(event)) if event.session_id == session_id => {
+                            return Some((event, receiver))
+                        }
+                        Ok(BusMessage::Event(_)) => continue,
+                        Ok(BusMessage::Reset) => return None,
+                        Err(RecvError::Lagged(skipped)) => {
+                            // End the stream so that the subscriber reads the session again.
+                            warn!(
+                                "Subscriber of session {} skipped {} events",
+                                session_id, skipped
+                            );
+                            return None;
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        Ok(events.boxed())
+    }
+}
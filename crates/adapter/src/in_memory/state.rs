@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use frog_core::entities::session::{SessionEntity, SessionId};
 
+use crate::in_memory::session_event::SessionEventInMemoryBus;
+
 #[derive(Default)]
 pub struct InMemoryState {
     pub sessions: HashMap<SessionId, SessionEntity>,
+    pub events: SessionEventInMemoryBus,
 }
@@ -1,3 +1,4 @@
+pub mod blob_store;
 pub mod http;
 pub mod in_memory;
 pub mod postgres;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE sessions
+    DROP COLUMN participant_number,
+    DROP COLUMN computation,
+    DROP COLUMN labels;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN participant_number INTEGER NOT NULL DEFAULT 2,
+    ADD COLUMN computation        TEXT    NOT NULL DEFAULT 'sum',
+    ADD COLUMN labels             JSONB   NOT NULL DEFAULT '{}';
+
+CREATE INDEX sessions_labels_idx ON sessions USING GIN (labels);
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE sessions
+    DROP COLUMN ttl_secs,
+    DROP COLUMN phase_timeout_secs,
+    DROP COLUMN expires_at,
+    DROP COLUMN phase_deadline;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN ttl_secs           BIGINT    NOT NULL DEFAULT 86400,
+    ADD COLUMN phase_timeout_secs BIGINT    NOT NULL DEFAULT 3600,
+    ADD COLUMN expires_at         TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP + INTERVAL '1 day',
+    ADD COLUMN phase_deadline     TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP + INTERVAL '1 hour';
+
+CREATE INDEX sessions_expires_at_idx ON sessions (expires_at);
+CREATE INDEX sessions_phase_deadline_idx ON sessions (phase_deadline);
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
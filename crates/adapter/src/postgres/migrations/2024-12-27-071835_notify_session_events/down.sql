@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER sessions_notify_event ON sessions;
+DROP FUNCTION notify_session_event();
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+CREATE FUNCTION notify_session_event() RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('session_events', json_build_object(
+            'id', NEW.id,
+            'status', NEW.status,
+            'failure_reason', NEW.failure_reason,
+            'version', NEW.version
+        )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sessions_notify_event
+    AFTER UPDATE OF status
+    ON sessions
+    FOR EACH ROW
+    WHEN (OLD.status IS DISTINCT FROM NEW.status)
+EXECUTE FUNCTION notify_session_event();
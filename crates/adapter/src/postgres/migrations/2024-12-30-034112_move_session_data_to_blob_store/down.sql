@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE sessions
+    DROP COLUMN pk_key,
+    DROP COLUMN pk_hash,
+    DROP COLUMN phantom_server_key,
+    DROP COLUMN phantom_server_hash,
+    DROP COLUMN encrypted_result_key,
+    DROP COLUMN encrypted_result_hash,
+    DROP COLUMN client_info_key,
+    DROP COLUMN client_info_hash,
+    ADD COLUMN pk               BYTEA NOT NULL DEFAULT '',
+    ADD COLUMN phantom_server   BYTEA NOT NULL DEFAULT '',
+    ADD COLUMN encrypted_result BYTEA NOT NULL DEFAULT '',
+    ADD COLUMN client_info      BYTEA NOT NULL DEFAULT '';
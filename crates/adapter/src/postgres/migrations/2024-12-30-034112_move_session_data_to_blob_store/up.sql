@@ -0,0 +1,24 @@
+-- Your SQL goes here
+-- Key material and ciphertexts now live in the blob store, which cannot be filled from SQL:
+-- the data of existing sessions is dropped, so the sessions that had not ended yet fail
+-- explicitly rather than waiting for data they lost.
+UPDATE sessions
+SET status         = 'Failed',
+    failure_reason = 'Key material and ciphertexts were dropped by the upgrade to the blob store',
+    version        = version + 1,
+    updated_at     = CURRENT_TIMESTAMP
+WHERE status NOT IN ('Expired', 'Failed', 'Aborted');
+
+ALTER TABLE sessions
+    DROP COLUMN pk,
+    DROP COLUMN phantom_server,
+    DROP COLUMN encrypted_result,
+    DROP COLUMN client_info,
+    ADD COLUMN pk_key                TEXT,
+    ADD COLUMN pk_hash               TEXT,
+    ADD COLUMN phantom_server_key    TEXT,
+    ADD COLUMN phantom_server_hash   TEXT,
+    ADD COLUMN encrypted_result_key  TEXT,
+    ADD COLUMN encrypted_result_hash TEXT,
+    ADD COLUMN client_info_key       TEXT,
+    ADD COLUMN client_info_hash      TEXT;
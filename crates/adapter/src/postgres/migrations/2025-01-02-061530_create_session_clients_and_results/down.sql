@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE session_results;
+DROP TABLE session_clients;
+
+ALTER TABLE sessions
+    ADD COLUMN encrypted_result_key  TEXT,
+    ADD COLUMN encrypted_result_hash TEXT,
+    ADD COLUMN client_info_key       TEXT,
+    ADD COLUMN client_info_hash      TEXT;
@@ -0,0 +1,49 @@
+-- Your SQL goes here
+-- Clients and results now have their own rows, so that a single client can be updated alone.
+-- Their blobs cannot be split from SQL: the clients and results of existing sessions are dropped,
+-- so the sessions that had not ended yet fail explicitly rather than waiting for data they lost.
+-- Their keys cannot tell which ones had any, since the previous migration left them unset for
+-- every session created before it.
+UPDATE sessions
+SET status         = 'Failed',
+    failure_reason = 'Clients and results were dropped by the upgrade to per-client storage',
+    version        = version + 1,
+    updated_at     = CURRENT_TIMESTAMP
+WHERE status NOT IN ('Expired', 'Failed', 'Aborted');
+
+ALTER TABLE sessions
+    DROP COLUMN encrypted_result_key,
+    DROP COLUMN encrypted_result_hash,
+    DROP COLUMN client_info_key,
+    DROP COLUMN client_info_hash;
+
+CREATE TABLE session_clients
+(
+    session_id          UUID      NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    client_id           BIGINT    NOT NULL,
+    pk_share_key        TEXT,
+    pk_share_hash       TEXT,
+    rp_key_share_key    TEXT,
+    rp_key_share_hash   TEXT,
+    bs_key_share_key    TEXT,
+    bs_key_share_hash   TEXT,
+    encrypted_data_key  TEXT,
+    encrypted_data_hash TEXT,
+    dec_share_key       TEXT,
+    dec_share_hash      TEXT,
+
+    created_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (session_id, client_id)
+);
+
+CREATE TABLE session_results
+(
+    session_id  UUID    NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    result_key  TEXT    NOT NULL,
+    result_hash TEXT    NOT NULL,
+
+    PRIMARY KEY (session_id, position)
+);
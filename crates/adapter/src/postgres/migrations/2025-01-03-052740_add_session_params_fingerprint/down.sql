@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE sessions
+    DROP COLUMN param_preset,
+    DROP COLUMN param_hash,
+    DROP COLUMN crs_hash;
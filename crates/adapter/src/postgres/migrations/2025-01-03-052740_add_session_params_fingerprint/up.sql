@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN param_preset TEXT NOT NULL DEFAULT '',
+    ADD COLUMN param_hash TEXT NOT NULL DEFAULT '',
+    ADD COLUMN crs_hash TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN crs BYTEA;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE sessions
+    ADD COLUMN input_len INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Computations run after the first one reuse the keys of their session. The inputs, decryption
+-- shares and result of a computation are kept together in a single blob.
+CREATE TABLE session_computations
+(
+    session_id     UUID      NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    computation_id INTEGER   NOT NULL,
+    status         TEXT      NOT NULL,
+    failure_reason TEXT,
+    data_key       TEXT,
+    data_hash      TEXT,
+
+    created_at     TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at     TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (session_id, computation_id)
+);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE session_computations
+    DROP COLUMN result_format;
+
+ALTER TABLE sessions
+    DROP COLUMN result_format;
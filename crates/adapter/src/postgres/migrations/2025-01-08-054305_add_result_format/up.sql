@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Results computed so far hold one ciphertext per bit, later ones are ring-packed.
+ALTER TABLE sessions
+    ADD COLUMN result_format TEXT NOT NULL DEFAULT 'Unpacked';
+
+ALTER TABLE session_computations
+    ADD COLUMN result_format TEXT NOT NULL DEFAULT 'Unpacked';
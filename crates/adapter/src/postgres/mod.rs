@@ -2,3 +2,4 @@ pub mod config;
 pub mod models;
 pub mod schema;
 pub mod session_db;
+pub mod session_event_listener;
@@ -1 +1,22 @@
+use frog_core::entities::blob::BlobRef;
+
 pub mod session;
+pub mod session_client;
+pub mod session_computation;
+pub mod session_result;
+
+/// Splits a blob reference into its key and hash columns.
+pub fn split_blob_ref(blob_ref: Option<BlobRef>) -> (Option<String>, Option<String>) {
+    match blob_ref {
+        Some(blob_ref) => (Some(blob_ref.key), Some(blob_ref.hash)),
+        None => (None, None),
+    }
+}
+
+/// Joins the key and hash columns of a blob reference, `None` if the blob is empty.
+pub fn join_blob_ref(key: &Option<String>, hash: &Option<String>) -> Option<BlobRef> {
+    Some(BlobRef {
+        key: key.clone()?,
+        hash: hash.clone()?,
+    })
+}
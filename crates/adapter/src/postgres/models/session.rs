@@ -1,52 +1,247 @@
+use std::collections::{BTreeMap, HashMap};
 use std::io::Error;
 use std::str::FromStr;
 use std::time::SystemTime;
 
 use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
-use frog_core::entities::session::{SessionEntity, SessionId, SessionStatus};
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::client::{ClientEntity, ClientId};
+use frog_core::entities::computation::{ComputationEntity, ComputationId};
+use frog_core::entities::params::ParamsFingerprint;
+use frog_core::entities::session::{
+    ResultFormat, SessionEntity, SessionId, SessionSettings, SessionStatus,
+};
+use frog_core::entities::session_summary::{ClientProgress, SessionSummary};
+use phantom::crs::Crs;
+use phantom::function::ValueType;
 use uuid::Uuid;
 
+use crate::postgres::models::{join_blob_ref, split_blob_ref};
+
 #[derive(Debug, Queryable, Insertable, Selectable, AsChangeset, Identifiable, Clone)]
 #[diesel(table_name = super::super::schema::sessions)]
+#[diesel(treat_none_as_null = true)]
 pub struct SessionModel {
     pub id: Uuid,
     pub status: String,
-    pub pk: Vec<u8>,
-    pub phantom_server: Vec<u8>,
-    pub encrypted_result: Vec<u8>,
-    pub client_info: Vec<u8>,
 
     pub created_at: SystemTime,
     pub updated_at: SystemTime,
+
+    pub participant_number: i32,
+    pub computation: String,
+    pub labels: serde_json::Value,
+
+    pub ttl_secs: i64,
+    pub phase_timeout_secs: i64,
+    pub expires_at: SystemTime,
+    pub phase_deadline: SystemTime,
+
+    pub failure_reason: Option<String>,
+
+    pub version: i64,
+
+    pub pk_key: Option<String>,
+    pub pk_hash: Option<String>,
+    pub phantom_server_key: Option<String>,
+    pub phantom_server_hash: Option<String>,
+
+    pub param_preset: String,
+    pub param_hash: String,
+    pub crs_hash: String,
+    pub crs: Option<Vec<u8>>,
+
+    pub input_type: String,
+    pub input_len: i32,
+    pub result_format: String,
+}
+
+/// Serialized fields of a session kept in the blob store rather than in the `sessions` table.
+///
+/// An empty field has no blob. The clients, results and computations of the session have their
+/// own tables.
+pub struct SessionBlobs {
+    pub pk: Vec<u8>,
+    pub phantom_server: Vec<u8>,
+}
+
+/// References to the blobs of a session, `None` for the empty fields.
+pub struct SessionBlobRefs {
+    pub pk: Option<BlobRef>,
+    pub phantom_server: Option<BlobRef>,
+}
+
+impl TryFrom<&SessionEntity> for SessionBlobs {
+    type Error = bincode::Error;
+
+    fn try_from(entity: &SessionEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pk: entity.pk.clone(),
+            phantom_server: match &entity.phantom_server {
+                Some(phantom_server) => bincode::serialize(phantom_server)?,
+                None => Vec::new(),
+            },
+        })
+    }
 }
 
-impl TryFrom<SessionEntity> for SessionModel {
-    type Error = Error;
+impl SessionModel {
+    /// Builds the row of a session whose large fields were stored as blobs.
+    pub fn new(entity: &SessionEntity, blob_refs: SessionBlobRefs) -> Result<Self, Error> {
+        let failure_reason = match &entity.status {
+            SessionStatus::Failed { reason } => Some(reason.clone()),
+            _ => None,
+        };
+        let (pk_key, pk_hash) = split_blob_ref(blob_refs.pk);
+        let (phantom_server_key, phantom_server_hash) = split_blob_ref(blob_refs.phantom_server);
 
-    fn try_from(entity: SessionEntity) -> Result<Self, Self::Error> {
         Ok(Self {
             id: entity.id.0,
             status: entity.status.to_string(),
-            client_info: bincode::serialize(&entity.client_info).unwrap(),
-            encrypted_result: bincode::serialize(&entity.encrypted_result).unwrap(),
-            pk: entity.pk,
-            phantom_server: bincode::serialize(&entity.phantom_server).unwrap(),
 
-            created_at: SystemTime::now(),
+            created_at: entity.created_at,
             updated_at: SystemTime::now(),
+
+            participant_number: entity.settings.participant_number as i32,
+            computation: entity.settings.computation.clone(),
+            labels: serde_json::to_value(&entity.settings.labels)?,
+
+            ttl_secs: entity.settings.ttl_secs as i64,
+            phase_timeout_secs: entity.settings.phase_timeout_secs as i64,
+            expires_at: entity.expires_at,
+            phase_deadline: entity.phase_deadline,
+
+            failure_reason,
+
+            version: entity.version as i64,
+
+            pk_key,
+            pk_hash,
+            phantom_server_key,
+            phantom_server_hash,
+
+            param_preset: entity.params.preset.clone(),
+            param_hash: entity.params.param_hash.clone(),
+            crs_hash: entity.params.crs_hash.clone(),
+            crs: entity
+                .crs
+                .as_ref()
+                .map(bincode::serialize)
+                .transpose()
+                .map_err(Error::other)?,
+
+            input_type: entity.settings.input_type.to_string(),
+            input_len: entity.settings.input_len as i32,
+            result_format: entity.result_format.to_string(),
         })
     }
-}
 
-impl From<SessionModel> for SessionEntity {
-    fn from(val: SessionModel) -> Self {
-        Self {
-            id: SessionId(val.id),
-            status: SessionStatus::from_str(&val.status).unwrap(),
-            client_info: bincode::deserialize(&val.client_info).unwrap(),
-            pk: val.pk,
-            encrypted_result: bincode::deserialize(&val.encrypted_result).unwrap(),
-            phantom_server: bincode::deserialize(&val.phantom_server).unwrap(),
+    /// Returns the references to the blobs of the session.
+    pub fn blob_refs(&self) -> SessionBlobRefs {
+        SessionBlobRefs {
+            pk: join_blob_ref(&self.pk_key, &self.pk_hash),
+            phantom_server: join_blob_ref(&self.phantom_server_key, &self.phantom_server_hash),
+        }
+    }
+
+    /// Returns the settings the session was created with.
+    fn settings(&self) -> SessionSettings {
+        SessionSettings {
+            participant_number: self.participant_number as usize,
+            computation: self.computation.clone(),
+            input_type: ValueType::from_str(&self.input_type).unwrap(),
+            input_len: self.input_len as usize,
+            labels: serde_json::from_value(self.labels.clone()).unwrap(),
+            ttl_secs: self.ttl_secs as u64,
+            phase_timeout_secs: self.phase_timeout_secs as u64,
         }
     }
+
+    /// Returns the CRS of the session, kept in the row.
+    fn crs(&self) -> Result<Option<Crs>, bincode::Error> {
+        self.crs
+            .as_ref()
+            .map(|crs| bincode::deserialize(crs))
+            .transpose()
+    }
+
+    /// Summarizes the session from its row and the rows of its clients, without its blobs.
+    ///
+    /// # Arguments
+    /// - `participants`: The progress of each client that joined the session.
+    /// - `has_result`: Whether the session has result rows.
+    pub fn into_summary(
+        self,
+        mut participants: Vec<ClientProgress>,
+        has_result: bool,
+    ) -> Result<SessionSummary, bincode::Error> {
+        participants.sort_by_key(|client| client.id.0);
+        let settings = self.settings();
+        Ok(SessionSummary {
+            id: SessionId(self.id),
+            crs: self.crs()?,
+            outputs: SessionSummary::output_layout(&settings),
+            settings,
+            params: ParamsFingerprint {
+                preset: self.param_preset,
+                param_hash: self.param_hash,
+                crs_hash: self.crs_hash,
+            },
+            participants,
+            has_pk: self.pk_key.is_some(),
+            has_result,
+            result_format: ResultFormat::from_str(&self.result_format).unwrap(),
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            phase_deadline: self.phase_deadline,
+            version: self.version as u64,
+            status: parse_status(&self.status, self.failure_reason),
+        })
+    }
+
+    /// Rebuilds the session from its row, the content of its blobs, its clients, its results and
+    /// its computations.
+    pub fn into_entity(
+        self,
+        blobs: SessionBlobs,
+        client_info: HashMap<ClientId, ClientEntity>,
+        encrypted_result: Vec<Vec<u8>>,
+        computations: BTreeMap<ComputationId, ComputationEntity>,
+    ) -> Result<SessionEntity, bincode::Error> {
+        Ok(SessionEntity {
+            id: SessionId(self.id),
+            settings: self.settings(),
+            crs: self.crs()?,
+            status: parse_status(&self.status, self.failure_reason),
+            params: ParamsFingerprint {
+                preset: self.param_preset,
+                param_hash: self.param_hash,
+                crs_hash: self.crs_hash,
+            },
+            client_info,
+            pk: blobs.pk,
+            encrypted_result,
+            result_format: ResultFormat::from_str(&self.result_format).unwrap(),
+            computations,
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            phase_deadline: self.phase_deadline,
+            version: self.version as u64,
+            phantom_server: if blobs.phantom_server.is_empty() {
+                None
+            } else {
+                Some(bincode::deserialize(&blobs.phantom_server)?)
+            },
+        })
+    }
+}
+
+/// Parses a stored session status, restoring the reason of a failure kept in its own column.
+pub fn parse_status(status: &str, failure_reason: Option<String>) -> SessionStatus {
+    match SessionStatus::from_str(status).unwrap() {
+        SessionStatus::Failed { .. } => SessionStatus::Failed {
+            reason: failure_reason.unwrap_or_default(),
+        },
+        session_status => session_status,
+    }
 }
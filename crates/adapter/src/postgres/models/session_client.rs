@@ -0,0 +1,112 @@
+use std::time::SystemTime;
+
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::client::{ClientArtifact, ClientId};
+use frog_core::entities::session::SessionId;
+use frog_core::entities::session_summary::ClientProgress;
+use uuid::Uuid;
+
+use crate::postgres::models::{join_blob_ref, split_blob_ref};
+
+/// Row of a client of a session, with one pair of blob columns per artifact of the client.
+#[derive(Debug, Queryable, Insertable, Selectable, AsChangeset, Identifiable, Clone)]
+#[diesel(table_name = super::super::schema::session_clients)]
+#[diesel(primary_key(session_id, client_id))]
+#[diesel(treat_none_as_null = true)]
+pub struct SessionClientModel {
+    pub session_id: Uuid,
+    pub client_id: i64,
+
+    pub pk_share_key: Option<String>,
+    pub pk_share_hash: Option<String>,
+    pub rp_key_share_key: Option<String>,
+    pub rp_key_share_hash: Option<String>,
+    pub bs_key_share_key: Option<String>,
+    pub bs_key_share_hash: Option<String>,
+    pub encrypted_data_key: Option<String>,
+    pub encrypted_data_hash: Option<String>,
+    pub dec_share_key: Option<String>,
+    pub dec_share_hash: Option<String>,
+
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+/// References to the blobs of a client, `None` for the artifacts it has not provided yet.
+#[derive(Debug, PartialEq)]
+pub struct ClientBlobRefs {
+    pub pk_share: Option<BlobRef>,
+    pub rp_key_share: Option<BlobRef>,
+    pub bs_key_share: Option<BlobRef>,
+    pub encrypted_data: Option<BlobRef>,
+    pub dec_share: Option<BlobRef>,
+}
+
+impl ClientBlobRefs {
+    /// Returns the reference to the blob of an artifact, `None` if it was not provided yet.
+    pub fn artifact(self, artifact: ClientArtifact) -> Option<BlobRef> {
+        match artifact {
+            ClientArtifact::BsKeyShare => self.bs_key_share,
+            ClientArtifact::EncryptedData => self.encrypted_data,
+            ClientArtifact::DecShare => self.dec_share,
+        }
+    }
+}
+
+impl SessionClientModel {
+    /// Builds the row of a client whose artifacts were stored as blobs.
+    pub fn new(session_id: &SessionId, client_id: &ClientId, blob_refs: ClientBlobRefs) -> Self {
+        let (pk_share_key, pk_share_hash) = split_blob_ref(blob_refs.pk_share);
+        let (rp_key_share_key, rp_key_share_hash) = split_blob_ref(blob_refs.rp_key_share);
+        let (bs_key_share_key, bs_key_share_hash) = split_blob_ref(blob_refs.bs_key_share);
+        let (encrypted_data_key, encrypted_data_hash) = split_blob_ref(blob_refs.encrypted_data);
+        let (dec_share_key, dec_share_hash) = split_blob_ref(blob_refs.dec_share);
+        let now = SystemTime::now();
+
+        Self {
+            session_id: session_id.0,
+            client_id: client_id.0 as i64,
+
+            pk_share_key,
+            pk_share_hash,
+            rp_key_share_key,
+            rp_key_share_hash,
+            bs_key_share_key,
+            bs_key_share_hash,
+            encrypted_data_key,
+            encrypted_data_hash,
+            dec_share_key,
+            dec_share_hash,
+
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Returns the ID of the client.
+    pub fn client_id(&self) -> ClientId {
+        ClientId(self.client_id as usize)
+    }
+
+    /// Returns the artifacts the client has provided so far.
+    pub fn progress(&self) -> ClientProgress {
+        ClientProgress {
+            id: self.client_id(),
+            has_bs_key_share: self.bs_key_share_key.is_some(),
+            has_encrypted_data: self.encrypted_data_key.is_some(),
+            has_dec_share: self.dec_share_key.is_some(),
+        }
+    }
+
+    /// Returns the references to the blobs of the client.
+    pub fn blob_refs(&self) -> ClientBlobRefs {
+        ClientBlobRefs {
+            pk_share: join_blob_ref(&self.pk_share_key, &self.pk_share_hash),
+            rp_key_share: join_blob_ref(&self.rp_key_share_key, &self.rp_key_share_hash),
+            bs_key_share: join_blob_ref(&self.bs_key_share_key, &self.bs_key_share_hash),
+            encrypted_data: join_blob_ref(&self.encrypted_data_key, &self.encrypted_data_hash),
+            dec_share: join_blob_ref(&self.dec_share_key, &self.dec_share_hash),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::client::ClientId;
+use frog_core::entities::computation::{ComputationEntity, ComputationId, ComputationStatus};
+use frog_core::entities::session::{ResultFormat, SessionId};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::postgres::models::{join_blob_ref, split_blob_ref};
+
+/// Row of a computation of a session, whose ciphertexts are kept in a single blob.
+#[derive(Debug, Queryable, Insertable, Selectable, AsChangeset, Identifiable, Clone)]
+#[diesel(table_name = super::super::schema::session_computations)]
+#[diesel(primary_key(session_id, computation_id))]
+#[diesel(treat_none_as_null = true)]
+pub struct SessionComputationModel {
+    pub session_id: Uuid,
+    pub computation_id: i32,
+    pub status: String,
+    pub failure_reason: Option<String>,
+
+    pub data_key: Option<String>,
+    pub data_hash: Option<String>,
+
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+
+    pub result_format: String,
+}
+
+/// Ciphertexts of a computation, serialized together into its blob.
+#[derive(Serialize, Deserialize)]
+pub struct ComputationData {
+    pub inputs: BTreeMap<ClientId, Vec<u8>>,
+    pub dec_shares: BTreeMap<ClientId, Vec<u8>>,
+    pub encrypted_result: Vec<Vec<u8>>,
+}
+
+impl From<&ComputationEntity> for ComputationData {
+    fn from(entity: &ComputationEntity) -> Self {
+        Self {
+            inputs: entity.inputs.clone(),
+            dec_shares: entity.dec_shares.clone(),
+            encrypted_result: entity.encrypted_result.clone(),
+        }
+    }
+}
+
+impl SessionComputationModel {
+    /// Builds the row of a computation whose ciphertexts were stored as a blob.
+    pub fn new(
+        session_id: &SessionId,
+        entity: &ComputationEntity,
+        data_ref: Option<BlobRef>,
+    ) -> Self {
+        let failure_reason = match &entity.status {
+            ComputationStatus::Failed { reason } => Some(reason.clone()),
+            _ => None,
+        };
+        let (data_key, data_hash) = split_blob_ref(data_ref);
+
+        Self {
+            session_id: session_id.0,
+            computation_id: entity.id.0 as i32,
+            status: entity.status.to_string(),
+            failure_reason,
+
+            data_key,
+            data_hash,
+
+            created_at: entity.created_at,
+            updated_at: SystemTime::now(),
+
+            result_format: entity.result_format.to_string(),
+        }
+    }
+
+    /// Returns the reference to the blob of the computation.
+    pub fn data_ref(&self) -> Option<BlobRef> {
+        join_blob_ref(&self.data_key, &self.data_hash)
+    }
+
+    /// Rebuilds the computation from its row and the content of its blob.
+    pub fn into_entity(self, data: ComputationData) -> ComputationEntity {
+        ComputationEntity {
+            id: ComputationId(self.computation_id as usize),
+            status: match ComputationStatus::from_str(&self.status).unwrap() {
+                ComputationStatus::Failed { .. } => ComputationStatus::Failed {
+                    reason: self.failure_reason.unwrap_or_default(),
+                },
+                computation_status => computation_status,
+            },
+            inputs: data.inputs,
+            dec_shares: data.dec_shares,
+            encrypted_result: data.encrypted_result,
+            result_format: ResultFormat::from_str(&self.result_format).unwrap(),
+            created_at: self.created_at,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::session::SessionId;
+use uuid::Uuid;
+
+/// Row of an encrypted result of a session, at its position among the results.
+#[derive(Debug, Queryable, Insertable, Selectable, Identifiable, Clone, PartialEq)]
+#[diesel(table_name = super::super::schema::session_results)]
+#[diesel(primary_key(session_id, position))]
+pub struct SessionResultModel {
+    pub session_id: Uuid,
+    pub position: i32,
+    pub result_key: String,
+    pub result_hash: String,
+}
+
+impl SessionResultModel {
+    /// Builds the row of an encrypted result stored as a blob.
+    pub fn new(session_id: &SessionId, position: usize, blob_ref: BlobRef) -> Self {
+        Self {
+            session_id: session_id.0,
+            position: position as i32,
+            result_key: blob_ref.key,
+            result_hash: blob_ref.hash,
+        }
+    }
+
+    /// Returns the reference to the blob of the result.
+    pub fn blob_ref(&self) -> BlobRef {
+        BlobRef {
+            key: self.result_key.clone(),
+            hash: self.result_hash.clone(),
+        }
+    }
+}
@@ -1,14 +1,83 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    session_clients (session_id, client_id) {
+        session_id -> Uuid,
+        client_id -> Int8,
+        pk_share_key -> Nullable<Text>,
+        pk_share_hash -> Nullable<Text>,
+        rp_key_share_key -> Nullable<Text>,
+        rp_key_share_hash -> Nullable<Text>,
+        bs_key_share_key -> Nullable<Text>,
+        bs_key_share_hash -> Nullable<Text>,
+        encrypted_data_key -> Nullable<Text>,
+        encrypted_data_hash -> Nullable<Text>,
+        dec_share_key -> Nullable<Text>,
+        dec_share_hash -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    session_computations (session_id, computation_id) {
+        session_id -> Uuid,
+        computation_id -> Int4,
+        status -> Text,
+        failure_reason -> Nullable<Text>,
+        data_key -> Nullable<Text>,
+        data_hash -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        result_format -> Text,
+    }
+}
+
+diesel::table! {
+    session_results (session_id, position) {
+        session_id -> Uuid,
+        position -> Int4,
+        result_key -> Text,
+        result_hash -> Text,
+    }
+}
+
 diesel::table! {
     sessions (id) {
         id -> Uuid,
         status -> Text,
-        pk -> Bytea,
-        phantom_server -> Bytea,
-        encrypted_result -> Bytea,
-        client_info -> Bytea,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        participant_number -> Int4,
+        computation -> Text,
+        labels -> Jsonb,
+        ttl_secs -> Int8,
+        phase_timeout_secs -> Int8,
+        expires_at -> Timestamp,
+        phase_deadline -> Timestamp,
+        failure_reason -> Nullable<Text>,
+        version -> Int8,
+        pk_key -> Nullable<Text>,
+        pk_hash -> Nullable<Text>,
+        phantom_server_key -> Nullable<Text>,
+        phantom_server_hash -> Nullable<Text>,
+        param_preset -> Text,
+        param_hash -> Text,
+        crs_hash -> Text,
+        crs -> Nullable<Bytea>,
+        input_type -> Text,
+        input_len -> Int4,
+        result_format -> Text,
     }
 }
+
+diesel::joinable!(session_clients -> sessions (session_id));
+diesel::joinable!(session_computations -> sessions (session_id));
+diesel::joinable!(session_results -> sessions (session_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    session_clients,
+    session_computations,
+    session_results,
+    sessions,
+);
@@ -1,29 +1,989 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use anyhow::Error;
 use async_trait::async_trait;
-use deadpool_diesel::postgres::Pool;
+use deadpool_diesel::postgres::{Object, Pool};
+use diesel::sql_types::{BigInt, Bool};
 use diesel::{
-    delete, insert_into, update, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper,
+    delete, insert_into, sql_query, update, BoolExpressionMethods, Connection, ExpressionMethods,
+    OptionalExtension, PgConnection, PgJsonbExpressionMethods, QueryDsl, QueryableByName,
+    RunQueryDsl, SelectableHelper,
 };
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
-use frog_core::entities::session::{SessionEntity, SessionId};
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::client::{ClientArtifact, ClientEntity, ClientId};
+use frog_core::entities::computation::ComputationEntity;
+use frog_core::entities::session::{SessionEntity, SessionId, SessionStatus};
+use frog_core::entities::session_query::{SessionCursor, SessionPage, SessionQuery};
+use frog_core::entities::session_summary::SessionSummary;
 use frog_core::errors::CoreError;
+use frog_core::ports::blob_store::BlobStorePort;
 use frog_core::ports::session::SessionPort;
+use log::warn;
+use phantom::native_ops::NativeOps;
+use phantom::server::PhantomServer;
+use uuid::Uuid;
 
-use crate::postgres::models::session::SessionModel;
+use crate::postgres::models::session::{parse_status, SessionBlobRefs, SessionBlobs, SessionModel};
+use crate::postgres::models::session_client::{ClientBlobRefs, SessionClientModel};
+use crate::postgres::models::session_computation::{ComputationData, SessionComputationModel};
+use crate::postgres::models::session_result::SessionResultModel;
+use crate::postgres::models::{join_blob_ref, split_blob_ref};
+use crate::postgres::schema::session_clients;
+use crate::postgres::schema::session_computations;
+use crate::postgres::schema::session_results;
 use crate::postgres::schema::sessions::dsl::sessions;
-use crate::postgres::schema::sessions::id;
+use crate::postgres::schema::sessions::{
+    created_at, expires_at, failure_reason, id, labels, participant_number, phantom_server_hash,
+    phantom_server_key, phase_deadline, pk_hash, pk_key, result_format, status, updated_at,
+    version,
+};
 
 // NOTE: path relative to Cargo.toml
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./src/postgres/migrations");
 
+/// Session repository storing sessions in PostgreSQL.
+///
+/// Each session is split across the `sessions`, `session_clients`, `session_results` and
+/// `session_computations` tables, so that an operation on a single client only reads and writes
+/// the rows of that client.
+/// Key material and ciphertexts are kept in a blob store, and the tables only keep references to
+/// them along with their content hashes.
+///
+/// Blobs are written before the rows referencing them are committed. Writers hold a shared
+/// advisory lock on the session meanwhile, and the blobs left unreferenced by a failed write or
+/// superseded by a successful one are collected under the exclusive lock, so that a blob is never
+/// collected while a writer is about to reference it.
 pub struct SessionDBRepository {
     pub db: Pool,
+    pub blob_store: Arc<dyn BlobStorePort + Send + Sync>,
+}
+
+/// Outcome of writing the rows and blobs of a session.
+struct WriteOutcome {
+    /// Whether the rows were written, i.e. nobody else updated the session in the meantime.
+    updated: bool,
+    /// Whether some blobs of the session may no longer be referenced.
+    orphaned_blobs: bool,
+}
+
+/// Outcome of storing an artifact of a client.
+enum ArtifactWrite {
+    /// The session is not in the phase of the artifact.
+    WrongStatus(SessionStatus),
+    /// The client did not join the session.
+    UnknownClient,
+    /// The artifact was stored.
+    Stored {
+        /// Whether it was the last one missing.
+        completed: bool,
+        /// Whether it replaced a different blob.
+        replaced: bool,
+    },
+}
+
+/// Result of trying to take an advisory lock.
+#[derive(QueryableByName)]
+struct AdvisoryLock {
+    #[diesel(sql_type = Bool)]
+    acquired: bool,
+}
+
+/// Pooled connection holding the blob lock of a session.
+///
+/// The lock is a session-level advisory lock, which Postgres only releases on unlock or when the
+/// connection closes. A guard dropped without being unlocked, e.g. because its future was
+/// cancelled or a query failed, detaches its connection from the pool so that closing it releases
+/// the lock, instead of handing a locked connection to the next caller.
+struct BlobLock {
+    conn: Option<Object>,
+    session_id: Uuid,
+    shared: bool,
+}
+
+impl BlobLock {
+    /// Takes the blob lock of a session in shared mode, held while writing its blobs and rows.
+    async fn shared(conn: Object, session_id: Uuid) -> Result<Self, CoreError> {
+        // Guard the connection before locking, so that a cancelled lock is not leaked either.
+        let lock = BlobLock {
+            conn: Some(conn),
+            session_id,
+            shared: true,
+        };
+        interact(lock.conn(), move |conn| lock_blobs_shared(conn, session_id)).await?;
+        Ok(lock)
+    }
+
+    /// Tries to take the blob lock of a session in exclusive mode, held while collecting its
+    /// blobs.
+    ///
+    /// # Returns
+    /// - `Ok(None)` if a writer holds the lock.
+    async fn try_exclusive(conn: Object, session_id: Uuid) -> Result<Option<Self>, CoreError> {
+        let mut lock = BlobLock {
+            conn: Some(conn),
+            session_id,
+            shared: false,
+        };
+        if interact(lock.conn(), move |conn| try_lock_blobs(conn, session_id)).await? {
+            Ok(Some(lock))
+        } else {
+            // The lock was not taken, so the connection can go back to the pool.
+            lock.conn.take();
+            Ok(None)
+        }
+    }
+
+    fn conn(&self) -> &Object {
+        self.conn
+            .as_ref()
+            .expect("the connection is only taken when the guard is released")
+    }
+
+    /// Releases the lock and hands back the connection.
+    async fn unlock(mut self) -> Result<Object, CoreError> {
+        let session_id = self.session_id;
+        if self.shared {
+            interact(self.conn(), move |conn| {
+                unlock_blobs_shared(conn, session_id)
+            })
+            .await?;
+        } else {
+            interact(self.conn(), move |conn| unlock_blobs(conn, session_id)).await?;
+        }
+        Ok(self
+            .conn
+            .take()
+            .expect("the connection is only taken when the guard is released"))
+    }
+}
+
+impl Drop for BlobLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            warn!(
+                "Closing a connection holding the blob lock of session {}",
+                self.session_id
+            );
+            drop(Object::take(conn));
+        }
+    }
+}
+
+/// Rows of a session across the `sessions`, `session_clients`, `session_results` and
+/// `session_computations` tables.
+struct SessionRows {
+    session: SessionModel,
+    clients: Vec<SessionClientModel>,
+    results: Vec<SessionResultModel>,
+    computations: Vec<SessionComputationModel>,
+}
+
+impl SessionRows {
+    /// Reads the rows of a session.
+    fn read(conn: &mut PgConnection, session_id: Uuid) -> Result<Self, diesel::result::Error> {
+        let session = sessions
+            .filter(id.eq(session_id))
+            .select(SessionModel::as_select())
+            .first::<SessionModel>(conn)?;
+        let clients = session_clients::table
+            .filter(session_clients::session_id.eq(session_id))
+            .select(SessionClientModel::as_select())
+            .load::<SessionClientModel>(conn)?;
+        let results = session_results::table
+            .filter(session_results::session_id.eq(session_id))
+            .order(session_results::position.asc())
+            .select(SessionResultModel::as_select())
+            .load::<SessionResultModel>(conn)?;
+        let computations = session_computations::table
+            .filter(session_computations::session_id.eq(session_id))
+            .order(session_computations::computation_id.asc())
+            .select(SessionComputationModel::as_select())
+            .load::<SessionComputationModel>(conn)?;
+        Ok(Self {
+            session,
+            clients,
+            results,
+            computations,
+        })
+    }
+
+    /// Returns the keys of all the blobs the rows reference.
+    fn blob_keys(&self) -> HashSet<String> {
+        let session_refs = self.session.blob_refs();
+        let client_refs = self.clients.iter().flat_map(|client| {
+            let blob_refs = client.blob_refs();
+            [
+                blob_refs.pk_share,
+                blob_refs.rp_key_share,
+                blob_refs.bs_key_share,
+                blob_refs.encrypted_data,
+                blob_refs.dec_share,
+            ]
+        });
+        let computation_refs = self
+            .computations
+            .iter()
+            .map(|computation| computation.data_ref());
+        [session_refs.pk, session_refs.phantom_server]
+            .into_iter()
+            .chain(client_refs)
+            .chain(computation_refs)
+            .flatten()
+            .map(|blob_ref| blob_ref.key)
+            .chain(self.results.iter().map(|result| result.result_key.clone()))
+            .collect()
+    }
 }
 
 impl SessionDBRepository {
-    pub fn new(db: Pool) -> Self {
-        SessionDBRepository { db }
+    pub fn new(db: Pool, blob_store: Arc<dyn BlobStorePort + Send + Sync>) -> Self {
+        SessionDBRepository { db, blob_store }
+    }
+
+    /// Stores the large fields of a session as blobs and builds its rows.
+    ///
+    /// Blobs whose key is in `stored_keys` are already in the blob store and are not written
+    /// again.
+    async fn store(
+        &self,
+        session_entity: &SessionEntity,
+        stored_keys: &HashSet<String>,
+    ) -> Result<SessionRows, CoreError> {
+        let blobs = SessionBlobs::try_from(session_entity)
+            .map_err(|err| CoreError::InternalError(err.into()))?;
+        let namespace = blob_namespace(&session_entity.id);
+        let blob_refs = SessionBlobRefs {
+            pk: self.put_blob(&namespace, &blobs.pk, stored_keys).await?,
+            phantom_server: self
+                .put_blob(&namespace, &blobs.phantom_server, stored_keys)
+                .await?,
+        };
+        let session = SessionModel::new(session_entity, blob_refs)
+            .map_err(|err| CoreError::InternalError(err.into()))?;
+
+        let mut clients = Vec::with_capacity(session_entity.client_info.len());
+        for client_entity in session_entity.client_info.values() {
+            let blob_refs = ClientBlobRefs {
+                pk_share: self
+                    .put_blob(&namespace, &client_entity.pk_share, stored_keys)
+                    .await?,
+                rp_key_share: self
+                    .put_blob(&namespace, &client_entity.rp_key_share, stored_keys)
+                    .await?,
+                bs_key_share: self
+                    .put_blob(&namespace, &client_entity.bs_key_share, stored_keys)
+                    .await?,
+                encrypted_data: self
+                    .put_blob(&namespace, &client_entity.encrypted_data, stored_keys)
+                    .await?,
+                dec_share: self
+                    .put_blob(&namespace, &client_entity.dec_share, stored_keys)
+                    .await?,
+            };
+            clients.push(SessionClientModel::new(
+                &session_entity.id,
+                &client_entity.id,
+                blob_refs,
+            ));
+        }
+
+        let results = self.store_results(session_entity, stored_keys).await?;
+
+        let mut computations = Vec::with_capacity(session_entity.computations.len());
+        for computation_entity in session_entity.computations.values() {
+            let data = bincode::serialize(&ComputationData::from(computation_entity))
+                .map_err(|err| CoreError::InternalError(err.into()))?;
+            let data_ref = self.put_blob(&namespace, &data, stored_keys).await?;
+            computations.push(SessionComputationModel::new(
+                &session_entity.id,
+                computation_entity,
+                data_ref,
+            ));
+        }
+
+        Ok(SessionRows {
+            session,
+            clients,
+            results,
+            computations,
+        })
+    }
+
+    /// Stores the encrypted result of a session as blobs and builds its rows.
+    ///
+    /// Blobs whose key is in `stored_keys` are already in the blob store and are not written
+    /// again.
+    async fn store_results(
+        &self,
+        session_entity: &SessionEntity,
+        stored_keys: &HashSet<String>,
+    ) -> Result<Vec<SessionResultModel>, CoreError> {
+        let namespace = blob_namespace(&session_entity.id);
+        let mut results = Vec::with_capacity(session_entity.encrypted_result.len());
+        for (position, encrypted_result) in session_entity.encrypted_result.iter().enumerate() {
+            let blob_ref = BlobRef::new(&namespace, encrypted_result);
+            if !stored_keys.contains(&blob_ref.key) {
+                self.blob_store
+                    .put(&blob_ref.key, encrypted_result.clone())
+                    .await?;
+            }
+            results.push(SessionResultModel::new(
+                &session_entity.id,
+                position,
+                blob_ref,
+            ));
+        }
+        Ok(results)
+    }
+
+    /// Loads the blobs of a session and rebuilds it from its rows.
+    ///
+    /// The Phantom server is left out unless `with_server` is set.
+    async fn load(&self, rows: SessionRows, with_server: bool) -> Result<SessionEntity, CoreError> {
+        let blob_refs = rows.session.blob_refs();
+        let blobs = SessionBlobs {
+            pk: self.load_blob(blob_refs.pk).await?,
+            phantom_server: if with_server {
+                self.load_blob(blob_refs.phantom_server).await?
+            } else {
+                Vec::new()
+            },
+        };
+
+        let mut client_info = HashMap::with_capacity(rows.clients.len());
+        for client in rows.clients {
+            let client_entity = self.load_client(client).await?;
+            client_info.insert(client_entity.id.clone(), client_entity);
+        }
+
+        let mut encrypted_result = Vec::with_capacity(rows.results.len());
+        for result in rows.results {
+            encrypted_result.push(self.load_blob(Some(result.blob_ref())).await?);
+        }
+
+        let mut computations = BTreeMap::new();
+        for computation in rows.computations {
+            let computation_entity = self.load_computation(computation).await?;
+            computations.insert(computation_entity.id, computation_entity);
+        }
+
+        rows.session
+            .into_entity(blobs, client_info, encrypted_result, computations)
+            .map_err(|err| CoreError::InternalError(err.into()))
+    }
+
+    /// Loads the blob of a computation and rebuilds it from its row.
+    async fn load_computation(
+        &self,
+        computation: SessionComputationModel,
+    ) -> Result<ComputationEntity, CoreError> {
+        let data = self.load_blob(computation.data_ref()).await?;
+        let data = bincode::deserialize::<ComputationData>(&data)
+            .map_err(|err| CoreError::InternalError(err.into()))?;
+        Ok(computation.into_entity(data))
+    }
+
+    /// Loads the blobs of a client and rebuilds it from its row.
+    async fn load_client(&self, client: SessionClientModel) -> Result<ClientEntity, CoreError> {
+        let blob_refs = client.blob_refs();
+        Ok(ClientEntity {
+            id: client.client_id(),
+            pk_share: self.load_blob(blob_refs.pk_share).await?,
+            rp_key_share: self.load_blob(blob_refs.rp_key_share).await?,
+            bs_key_share: self.load_blob(blob_refs.bs_key_share).await?,
+            encrypted_data: self.load_blob(blob_refs.encrypted_data).await?,
+            dec_share: self.load_blob(blob_refs.dec_share).await?,
+        })
+    }
+
+    async fn put_blob(
+        &self,
+        namespace: &str,
+        data: &[u8],
+        stored_keys: &HashSet<String>,
+    ) -> Result<Option<BlobRef>, CoreError> {
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let blob_ref = BlobRef::new(namespace, data);
+        if !stored_keys.contains(&blob_ref.key) {
+            self.blob_store.put(&blob_ref.key, data.to_vec()).await?;
+        }
+        Ok(Some(blob_ref))
+    }
+
+    /// Writes the rows and blobs of a session if nobody else updated it since it was read.
+    ///
+    /// Only the rows and blobs that differ from the stored ones are written. The stored rows
+    /// are read outside of the transaction: if they changed in the meantime, the version check
+    /// rejects the update anyway.
+    async fn write(
+        &self,
+        conn: &Object,
+        session_entity: &SessionEntity,
+    ) -> Result<WriteOutcome, CoreError> {
+        let session_id = session_entity.id.0;
+        let stored = interact(conn, move |conn| SessionRows::read(conn, session_id)).await?;
+        let stored_keys = stored.blob_keys();
+        let mut rows = self.store(session_entity, &stored_keys).await?;
+        let keys = rows.blob_keys();
+
+        let updated = interact(conn, move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let expected_version = rows.session.version;
+                rows.session.version += 1;
+
+                // Only update the session if nobody else updated it since it was read.
+                let updated_rows = update(
+                    sessions
+                        .filter(id.eq(rows.session.id))
+                        .filter(version.eq(expected_version)),
+                )
+                .set(&rows.session)
+                .execute(conn)?;
+                if updated_rows == 0 {
+                    return Ok(false);
+                }
+
+                write_clients(conn, rows.session.id, &stored.clients, rows.clients)?;
+                write_results(conn, rows.session.id, &stored.results, rows.results)?;
+                write_computations(
+                    conn,
+                    rows.session.id,
+                    &stored.computations,
+                    rows.computations,
+                )?;
+                Ok(true)
+            })
+        })
+        .await?;
+
+        // A failed write orphans the blobs it added, a successful one those it replaced.
+        let orphaned_blobs = if updated {
+            !stored_keys.is_subset(&keys)
+        } else {
+            !keys.is_subset(&stored_keys)
+        };
+        Ok(WriteOutcome {
+            updated,
+            orphaned_blobs,
+        })
+    }
+
+    /// Writes the blob of an artifact of a client and points the row of the client at it.
+    ///
+    /// The session row is locked meanwhile, so that concurrent artifacts are counted one after
+    /// the other and only the last one missing completes them.
+    async fn write_artifact(
+        &self,
+        conn: &Object,
+        session_id: &SessionId,
+        client_id: &ClientId,
+        artifact: ClientArtifact,
+        data: &[u8],
+    ) -> Result<ArtifactWrite, CoreError> {
+        let blob_ref = self
+            .put_blob(&blob_namespace(session_id), data, &HashSet::new())
+            .await?;
+        let uuid = session_id.0;
+        let stored_client_id = client_id.0 as i64;
+
+        interact(conn, move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let (stored_status, stored_failure_reason, expected_clients) = sessions
+                    .filter(id.eq(uuid))
+                    .select((status, failure_reason, participant_number))
+                    .for_update()
+                    .first::<(String, Option<String>, i32)>(conn)?;
+                let stored_status = parse_status(&stored_status, stored_failure_reason);
+                if stored_status != artifact.phase() {
+                    return Ok(ArtifactWrite::WrongStatus(stored_status));
+                }
+
+                let clients = session_clients::table
+                    .filter(session_clients::session_id.eq(uuid))
+                    .select(SessionClientModel::as_select())
+                    .load::<SessionClientModel>(conn)?;
+                let Some(client) = clients
+                    .iter()
+                    .find(|client| client.client_id == stored_client_id)
+                else {
+                    return Ok(ArtifactWrite::UnknownClient);
+                };
+
+                // Count the clients that provided the artifact, before and after this one.
+                let previous_ref = client.blob_refs().artifact(artifact);
+                let provided = clients
+                    .iter()
+                    .filter(|client| client.blob_refs().artifact(artifact).is_some())
+                    .count();
+                let was_complete = provided == expected_clients as usize;
+                let provided = provided - usize::from(previous_ref.is_some())
+                    + usize::from(blob_ref.is_some());
+                let is_complete = provided == expected_clients as usize;
+                let replaced = previous_ref.is_some() && previous_ref != blob_ref;
+
+                write_artifact_ref(conn, uuid, stored_client_id, artifact, blob_ref)?;
+                update(sessions.filter(id.eq(uuid)))
+                    .set((version.eq(version + 1), updated_at.eq(SystemTime::now())))
+                    .execute(conn)?;
+                Ok(ArtifactWrite::Stored {
+                    completed: !was_complete && is_complete,
+                    replaced,
+                })
+            })
+        })
+        .await
+    }
+
+    /// Writes the result rows and blobs of a session along with its new status, if nobody else
+    /// updated it since it was read.
+    async fn write_result(
+        &self,
+        conn: &Object,
+        session_entity: &SessionEntity,
+    ) -> Result<WriteOutcome, CoreError> {
+        let uuid = session_entity.id.0;
+        let results = self.store_results(session_entity, &HashSet::new()).await?;
+        let keys = results
+            .iter()
+            .map(|result| result.result_key.clone())
+            .collect::<HashSet<_>>();
+
+        let expected_version = session_entity.version as i64;
+        let new_status = session_entity.status.to_string();
+        let new_failure_reason = match &session_entity.status {
+            SessionStatus::Failed { reason } => Some(reason.clone()),
+            _ => None,
+        };
+        let new_phase_deadline = session_entity.phase_deadline;
+        let new_result_format = session_entity.result_format.to_string();
+
+        let (updated, stored_keys) = interact(conn, move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                // Only update the session if nobody else updated it since it was read.
+                let updated_rows = update(
+                    sessions
+                        .filter(id.eq(uuid))
+                        .filter(version.eq(expected_version)),
+                )
+                .set((
+                    status.eq(new_status),
+                    failure_reason.eq(new_failure_reason),
+                    phase_deadline.eq(new_phase_deadline),
+                    result_format.eq(new_result_format),
+                    version.eq(version + 1),
+                    updated_at.eq(SystemTime::now()),
+                ))
+                .execute(conn)?;
+                if updated_rows == 0 {
+                    return Ok((false, HashSet::new()));
+                }
+
+                let stored = session_results::table
+                    .filter(session_results::session_id.eq(uuid))
+                    .order(session_results::position.asc())
+                    .select(SessionResultModel::as_select())
+                    .load::<SessionResultModel>(conn)?;
+                let stored_keys = stored
+                    .iter()
+                    .map(|result| result.result_key.clone())
+                    .collect::<HashSet<_>>();
+                write_results(conn, uuid, &stored, results)?;
+                Ok((true, stored_keys))
+            })
+        })
+        .await?;
+
+        // A failed write orphans the blobs it added, a successful one those it replaced.
+        let orphaned_blobs = if updated {
+            !stored_keys.is_subset(&keys)
+        } else {
+            !keys.is_empty()
+        };
+        Ok(WriteOutcome {
+            updated,
+            orphaned_blobs,
+        })
+    }
+
+    /// Writes the blob of a computation of a session and upserts its row, if nobody else updated
+    /// the session since it was read.
+    async fn write_computation(
+        &self,
+        conn: &Object,
+        session_id: &SessionId,
+        expected_version: u64,
+        computation_entity: &ComputationEntity,
+    ) -> Result<WriteOutcome, CoreError> {
+        let data = bincode::serialize(&ComputationData::from(computation_entity))
+            .map_err(|err| CoreError::InternalError(err.into()))?;
+        let data_ref = self
+            .put_blob(&blob_namespace(session_id), &data, &HashSet::new())
+            .await?;
+        let computation = SessionComputationModel::new(session_id, computation_entity, data_ref);
+        let uuid = session_id.0;
+        let expected_version = expected_version as i64;
+
+        let (updated, replaced) = interact(conn, move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                // Only update the session if nobody else updated it since it was read.
+                let updated_rows = update(
+                    sessions
+                        .filter(id.eq(uuid))
+                        .filter(version.eq(expected_version)),
+                )
+                .set((version.eq(version + 1), updated_at.eq(SystemTime::now())))
+                .execute(conn)?;
+                if updated_rows == 0 {
+                    return Ok((false, false));
+                }
+
+                let stored_ref = session_computations::table
+                    .find((uuid, computation.computation_id))
+                    .select(SessionComputationModel::as_select())
+                    .first::<SessionComputationModel>(conn)
+                    .optional()?
+                    .and_then(|stored| stored.data_ref());
+                let replaced = stored_ref.is_some() && stored_ref != computation.data_ref();
+
+                insert_into(session_computations::table)
+                    .values(&computation)
+                    .on_conflict((
+                        session_computations::session_id,
+                        session_computations::computation_id,
+                    ))
+                    .do_update()
+                    .set(&computation)
+                    .execute(conn)?;
+                Ok((true, replaced))
+            })
+        })
+        .await?;
+
+        // A failed write orphans the blob it added, a successful one the blob it replaced.
+        Ok(WriteOutcome {
+            updated,
+            orphaned_blobs: !updated || replaced,
+        })
+    }
+
+    /// Writes the blobs of a new session and inserts its rows.
+    async fn insert(
+        &self,
+        conn: &Object,
+        session_entity: &SessionEntity,
+    ) -> Result<SessionId, CoreError> {
+        let rows = self.store(session_entity, &HashSet::new()).await?;
+        interact(conn, move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let response = insert_into(sessions)
+                    .values(&rows.session)
+                    .get_result::<SessionModel>(conn)?;
+                if !rows.clients.is_empty() {
+                    insert_into(session_clients::table)
+                        .values(&rows.clients)
+                        .execute(conn)?;
+                }
+                if !rows.results.is_empty() {
+                    insert_into(session_results::table)
+                        .values(&rows.results)
+                        .execute(conn)?;
+                }
+                if !rows.computations.is_empty() {
+                    insert_into(session_computations::table)
+                        .values(&rows.computations)
+                        .execute(conn)?;
+                }
+                Ok(SessionId(response.id))
+            })
+        })
+        .await
+    }
+
+    /// Deletes the blobs of a session its rows no longer reference.
+    ///
+    /// Skipped if a writer holds the blob lock of the session, since it may be about to
+    /// reference some of them; the blobs are then collected after a later write.
+    ///
+    /// # Arguments
+    /// - `conn`: A connection not holding the blob lock of the session.
+    /// - `session_id`: The session whose blobs are collected. All its blobs are deleted if the
+    ///   session does not exist.
+    async fn collect_blobs(&self, conn: Object, session_id: &SessionId) -> Result<(), CoreError> {
+        let Some(lock) = BlobLock::try_exclusive(conn, session_id.0).await? else {
+            return Ok(());
+        };
+        let collected = self
+            .delete_unreferenced_blobs(lock.conn(), session_id)
+            .await;
+        lock.unlock().await?;
+        collected
+    }
+
+    async fn delete_unreferenced_blobs(
+        &self,
+        conn: &Object,
+        session_id: &SessionId,
+    ) -> Result<(), CoreError> {
+        let uuid = session_id.0;
+        let referenced = match interact(conn, move |conn| SessionRows::read(conn, uuid)).await {
+            Ok(rows) => rows.blob_keys(),
+            Err(CoreError::NotFound) => HashSet::new(),
+            Err(e) => return Err(e),
+        };
+        for key in self.blob_store.list(&blob_namespace(session_id)).await? {
+            if !referenced.contains(&key) {
+                self.blob_store.delete(&key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects the blobs of a session after a write that may have orphaned some.
+    ///
+    /// Failing to collect them only wastes space, so the error is logged rather than returned.
+    async fn collect_blobs_after_write(&self, conn: Object, session_id: &SessionId) {
+        if let Err(e) = self.collect_blobs(conn, session_id).await {
+            warn!(
+                "Failed to collect the blobs of session {}: {}",
+                session_id, e
+            );
+        }
+    }
+
+    async fn load_blob(&self, blob_ref: Option<BlobRef>) -> Result<Vec<u8>, CoreError> {
+        let Some(blob_ref) = blob_ref else {
+            return Ok(Vec::new());
+        };
+        let data = self.blob_store.get(&blob_ref.key).await?;
+        blob_ref.verify(&data)?;
+        Ok(data)
+    }
+}
+
+/// Prefix of the keys of all the blobs of a session.
+fn blob_namespace(session_id: &SessionId) -> String {
+    format!("sessions/{}", session_id)
+}
+
+/// Runs a query on a pooled connection.
+async fn interact<T, F>(conn: &Object, query: F) -> Result<T, CoreError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut PgConnection) -> Result<T, diesel::result::Error> + Send + 'static,
+{
+    conn.interact(move |conn| query(conn).map_err(map_diesel_error))
+        .await
+        .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))?
+}
+
+/// Key of the advisory lock fencing the blobs of a session.
+fn blob_lock_key(session_id: Uuid) -> i64 {
+    session_id.as_u64_pair().0 as i64
+}
+
+/// Takes the blob lock of a session in shared mode, held while writing its blobs and rows.
+fn lock_blobs_shared(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+) -> Result<(), diesel::result::Error> {
+    sql_query("SELECT pg_advisory_lock_shared($1)")
+        .bind::<BigInt, _>(blob_lock_key(session_id))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Releases the shared blob lock of a session.
+fn unlock_blobs_shared(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+) -> Result<(), diesel::result::Error> {
+    sql_query("SELECT pg_advisory_unlock_shared($1)")
+        .bind::<BigInt, _>(blob_lock_key(session_id))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Tries to take the blob lock of a session in exclusive mode, held while collecting its blobs.
+///
+/// # Returns
+/// - `Ok(false)` if a writer holds the lock.
+fn try_lock_blobs(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+) -> Result<bool, diesel::result::Error> {
+    sql_query("SELECT pg_try_advisory_lock($1) AS acquired")
+        .bind::<BigInt, _>(blob_lock_key(session_id))
+        .get_result::<AdvisoryLock>(conn)
+        .map(|lock| lock.acquired)
+}
+
+/// Releases the exclusive blob lock of a session.
+fn unlock_blobs(conn: &mut PgConnection, session_id: Uuid) -> Result<(), diesel::result::Error> {
+    sql_query("SELECT pg_advisory_unlock($1)")
+        .bind::<BigInt, _>(blob_lock_key(session_id))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Writes the client rows that changed since the stored ones were read.
+fn write_clients(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    stored: &[SessionClientModel],
+    clients: Vec<SessionClientModel>,
+) -> Result<(), diesel::result::Error> {
+    let stored = stored
+        .iter()
+        .map(|client| (client.client_id, client))
+        .collect::<HashMap<_, _>>();
+    let client_ids = clients
+        .iter()
+        .map(|client| client.client_id)
+        .collect::<Vec<_>>();
+
+    for mut client in clients {
+        if let Some(stored_client) = stored.get(&client.client_id) {
+            if stored_client.blob_refs() == client.blob_refs() {
+                continue;
+            }
+            client.created_at = stored_client.created_at;
+        }
+        insert_into(session_clients::table)
+            .values(&client)
+            .on_conflict((session_clients::session_id, session_clients::client_id))
+            .do_update()
+            .set(&client)
+            .execute(conn)?;
+    }
+
+    if stored
+        .keys()
+        .any(|client_id| !client_ids.contains(client_id))
+    {
+        delete(
+            session_clients::table
+                .filter(session_clients::session_id.eq(session_id))
+                .filter(session_clients::client_id.ne_all(client_ids)),
+        )
+        .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// Points the columns of an artifact in the row of a client at its blob.
+fn write_artifact_ref(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    client_id: i64,
+    artifact: ClientArtifact,
+    blob_ref: Option<BlobRef>,
+) -> Result<(), diesel::result::Error> {
+    let (key, hash) = split_blob_ref(blob_ref);
+    let client = session_clients::table.find((session_id, client_id));
+    let now = SystemTime::now();
+    match artifact {
+        ClientArtifact::BsKeyShare => update(client)
+            .set((
+                session_clients::bs_key_share_key.eq(key),
+                session_clients::bs_key_share_hash.eq(hash),
+                session_clients::updated_at.eq(now),
+            ))
+            .execute(conn)?,
+        ClientArtifact::EncryptedData => update(client)
+            .set((
+                session_clients::encrypted_data_key.eq(key),
+                session_clients::encrypted_data_hash.eq(hash),
+                session_clients::updated_at.eq(now),
+            ))
+            .execute(conn)?,
+        ClientArtifact::DecShare => update(client)
+            .set((
+                session_clients::dec_share_key.eq(key),
+                session_clients::dec_share_hash.eq(hash),
+                session_clients::updated_at.eq(now),
+            ))
+            .execute(conn)?,
+    };
+    Ok(())
+}
+
+/// Replaces the result rows if they changed since the stored ones were read.
+fn write_results(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    stored: &[SessionResultModel],
+    results: Vec<SessionResultModel>,
+) -> Result<(), diesel::result::Error> {
+    if stored == results.as_slice() {
+        return Ok(());
+    }
+    if !stored.is_empty() {
+        delete(session_results::table.filter(session_results::session_id.eq(session_id)))
+            .execute(conn)?;
+    }
+    if !results.is_empty() {
+        insert_into(session_results::table)
+            .values(&results)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// Writes the computation rows that changed since the stored ones were read.
+fn write_computations(
+    conn: &mut PgConnection,
+    session_id: Uuid,
+    stored: &[SessionComputationModel],
+    computations: Vec<SessionComputationModel>,
+) -> Result<(), diesel::result::Error> {
+    let stored = stored
+        .iter()
+        .map(|computation| (computation.computation_id, computation))
+        .collect::<HashMap<_, _>>();
+    let computation_ids = computations
+        .iter()
+        .map(|computation| computation.computation_id)
+        .collect::<Vec<_>>();
+
+    for mut computation in computations {
+        if let Some(stored_computation) = stored.get(&computation.computation_id) {
+            if stored_computation.status == computation.status
+                && stored_computation.failure_reason == computation.failure_reason
+                && stored_computation.result_format == computation.result_format
+                && stored_computation.data_ref() == computation.data_ref()
+            {
+                continue;
+            }
+            computation.created_at = stored_computation.created_at;
+        }
+        insert_into(session_computations::table)
+            .values(&computation)
+            .on_conflict((
+                session_computations::session_id,
+                session_computations::computation_id,
+            ))
+            .do_update()
+            .set(&computation)
+            .execute(conn)?;
+    }
+
+    if stored
+        .keys()
+        .any(|computation_id| !computation_ids.contains(computation_id))
+    {
+        delete(
+            session_computations::table
+                .filter(session_computations::session_id.eq(session_id))
+                .filter(session_computations::computation_id.ne_all(computation_ids)),
+        )
+        .execute(conn)?;
     }
+    Ok(())
 }
 
 fn map_diesel_error(err: diesel::result::Error) -> CoreError {
@@ -41,17 +1001,15 @@ impl SessionPort for SessionDBRepository {
             .get()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
-        conn.interact(move |conn| {
-            let session = SessionModel::try_from(session_entity)
-                .map_err(|err| CoreError::InternalError(err.into()))?;
-            let response = insert_into(sessions)
-                .values(&session)
-                .get_result::<SessionModel>(conn)
-                .map_err(map_diesel_error)?;
-            Ok(SessionId(response.id))
-        })
-        .await
-        .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))?
+        let session_id = session_entity.id.clone();
+        let lock = BlobLock::shared(conn, session_id.0).await?;
+        let created = self.insert(lock.conn(), &session_entity).await;
+        let conn = lock.unlock().await?;
+
+        if created.is_err() {
+            self.collect_blobs_after_write(conn, &session_id).await;
+        }
+        created
     }
 
     async fn get(&self, session_id: SessionId) -> Result<SessionEntity, CoreError> {
@@ -60,13 +1018,274 @@ impl SessionPort for SessionDBRepository {
             .get()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
-        conn.interact(move |conn| {
-            let response = sessions
+        let rows = conn
+            .interact(move |conn| SessionRows::read(conn, session_id.0).map_err(map_diesel_error))
+            .await
+            .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))??;
+        self.load(rows, true).await
+    }
+
+    async fn get_without_server(&self, session_id: SessionId) -> Result<SessionEntity, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let rows = conn
+            .interact(move |conn| SessionRows::read(conn, session_id.0).map_err(map_diesel_error))
+            .await
+            .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))??;
+        self.load(rows, false).await
+    }
+
+    async fn get_summary(&self, session_id: SessionId) -> Result<SessionSummary, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let (session, clients, has_result) = interact(&conn, move |conn| {
+            let session = sessions
                 .filter(id.eq(session_id.0))
                 .select(SessionModel::as_select())
-                .first::<SessionModel>(conn)
+                .first::<SessionModel>(conn)?;
+            let clients = session_clients::table
+                .filter(session_clients::session_id.eq(session_id.0))
+                .select(SessionClientModel::as_select())
+                .load::<SessionClientModel>(conn)?;
+            let has_result = diesel::select(diesel::dsl::exists(
+                session_results::table.filter(session_results::session_id.eq(session_id.0)),
+            ))
+            .get_result::<bool>(conn)?;
+            Ok((session, clients, has_result))
+        })
+        .await?;
+
+        let participants = clients.iter().map(SessionClientModel::progress).collect();
+        session
+            .into_summary(participants, has_result)
+            .map_err(|err| CoreError::InternalError(err.into()))
+    }
+
+    async fn get_pk_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let (stored_key, stored_hash) = interact(&conn, move |conn| {
+            sessions
+                .filter(id.eq(session_id.0))
+                .select((pk_key, pk_hash))
+                .first::<(Option<String>, Option<String>)>(conn)
+        })
+        .await?;
+        Ok(join_blob_ref(&stored_key, &stored_hash))
+    }
+
+    async fn get_result_refs(&self, session_id: SessionId) -> Result<Vec<BlobRef>, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let results = interact(&conn, move |conn| {
+            // Tell a missing session apart from a session without a result.
+            sessions
+                .filter(id.eq(session_id.0))
+                .select(id)
+                .first::<Uuid>(conn)?;
+            session_results::table
+                .filter(session_results::session_id.eq(session_id.0))
+                .order(session_results::position.asc())
+                .select(SessionResultModel::as_select())
+                .load::<SessionResultModel>(conn)
+        })
+        .await?;
+        Ok(results.iter().map(SessionResultModel::blob_ref).collect())
+    }
+
+    async fn get_blob(&self, blob_ref: &BlobRef) -> Result<Vec<u8>, CoreError> {
+        self.load_blob(Some(blob_ref.clone())).await
+    }
+
+    async fn get_server_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let (stored_key, stored_hash) = interact(&conn, move |conn| {
+            sessions
+                .filter(id.eq(session_id.0))
+                .select((phantom_server_key, phantom_server_hash))
+                .first::<(Option<String>, Option<String>)>(conn)
+        })
+        .await?;
+        Ok(join_blob_ref(&stored_key, &stored_hash))
+    }
+
+    async fn get_server(
+        &self,
+        server_ref: &BlobRef,
+    ) -> Result<PhantomServer<NativeOps>, CoreError> {
+        let data = self.load_blob(Some(server_ref.clone())).await?;
+        bincode::deserialize(&data).map_err(|err| CoreError::InternalError(err.into()))
+    }
+
+    async fn get_client(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+    ) -> Result<ClientEntity, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let client = conn
+            .interact(move |conn| {
+                session_clients::table
+                    .find((session_id.0, client_id.0 as i64))
+                    .select(SessionClientModel::as_select())
+                    .first::<SessionClientModel>(conn)
+                    .map_err(map_diesel_error)
+            })
+            .await
+            .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))??;
+        self.load_client(client).await
+    }
+
+    async fn list(&self, query: SessionQuery) -> Result<SessionPage<SessionSummary>, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let (response, has_next_page) = conn
+            .interact(move |conn| {
+                let mut statement = sessions.select(SessionModel::as_select()).into_boxed();
+
+                if let Some(session_status) = &query.status {
+                    statement = statement.filter(status.eq(session_status.to_string()));
+                }
+                if let Some(after) = query.created_after {
+                    statement = statement.filter(created_at.ge(after));
+                }
+                if let Some(before) = query.created_before {
+                    statement = statement.filter(created_at.lt(before));
+                }
+                if !query.labels.is_empty() {
+                    let expected_labels = serde_json::to_value(&query.labels)
+                        .map_err(|err| CoreError::InternalError(err.into()))?;
+                    statement = statement.filter(labels.contains(expected_labels));
+                }
+                if let Some(cursor) = &query.cursor {
+                    statement = statement.filter(
+                        created_at
+                            .gt(cursor.created_at)
+                            .or(created_at.eq(cursor.created_at).and(id.gt(cursor.id.0))),
+                    );
+                }
+
+                // Fetch one extra row to find out whether another page exists.
+                let mut response = statement
+                    .order((created_at.asc(), id.asc()))
+                    .limit(query.limit as i64 + 1)
+                    .load::<SessionModel>(conn)
+                    .map_err(map_diesel_error)?;
+                let has_next_page = response.len() > query.limit;
+                response.truncate(query.limit);
+
+                // Fetch the clients of the whole page at once, and which sessions have a result.
+                let session_ids = response
+                    .iter()
+                    .map(|session| session.id)
+                    .collect::<Vec<_>>();
+                let mut clients = HashMap::<Uuid, Vec<SessionClientModel>>::new();
+                for client in session_clients::table
+                    .filter(session_clients::session_id.eq_any(session_ids.clone()))
+                    .select(SessionClientModel::as_select())
+                    .load::<SessionClientModel>(conn)
+                    .map_err(map_diesel_error)?
+                {
+                    clients.entry(client.session_id).or_default().push(client);
+                }
+                let with_result = session_results::table
+                    .filter(session_results::session_id.eq_any(session_ids))
+                    .filter(session_results::position.eq(0))
+                    .select(session_results::session_id)
+                    .load::<Uuid>(conn)
+                    .map_err(map_diesel_error)?
+                    .into_iter()
+                    .collect::<HashSet<_>>();
+
+                let response = response
+                    .into_iter()
+                    .map(|session| {
+                        let participants = clients
+                            .remove(&session.id)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(SessionClientModel::progress)
+                            .collect();
+                        let has_result = with_result.contains(&session.id);
+                        session
+                            .into_summary(participants, has_result)
+                            .map_err(|err| CoreError::InternalError(err.into()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok::<_, CoreError>((response, has_next_page))
+            })
+            .await
+            .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))??;
+
+        let next_cursor = if has_next_page {
+            response.last().map(SessionCursor::after)
+        } else {
+            None
+        };
+
+        Ok(SessionPage {
+            sessions: response,
+            next_cursor,
+        })
+    }
+
+    async fn list_overdue(
+        &self,
+        now: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<SessionId>, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        conn.interact(move |conn| {
+            let waiting_statuses = SessionStatus::WAITING
+                .iter()
+                .map(|session_status| session_status.to_string())
+                .collect::<Vec<_>>();
+            let failure_statuses = SessionStatus::FAILURES
+                .iter()
+                .map(|session_status| session_status.to_string())
+                .collect::<Vec<_>>();
+            let response = sessions
+                .select(id)
+                .filter(status.ne_all(failure_statuses))
+                .filter(
+                    expires_at
+                        .le(now)
+                        .or(status.eq_any(waiting_statuses).and(phase_deadline.le(now))),
+                )
+                .order(expires_at.asc())
+                .limit(limit as i64)
+                .load::<Uuid>(conn)
                 .map_err(map_diesel_error)?
-                .into();
+                .into_iter()
+                .map(SessionId)
+                .collect();
             Ok(response)
         })
         .await
@@ -82,22 +1301,161 @@ impl SessionPort for SessionDBRepository {
             return Err(CoreError::ValidationFail("Session ID mismatch".to_string()));
         }
 
+        let is_failure = session_entity.status.is_failure();
         let conn = self
             .db
             .get()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
-        conn.interact(move |conn| {
-            let session = SessionModel::try_from(session_entity)
-                .map_err(|err| CoreError::InternalError(err.into()))?;
-            let response = update(sessions.filter(id.eq(session.id)))
-                .set(&session)
-                .get_result::<SessionModel>(conn)
-                .map_err(map_diesel_error)?;
-            Ok(SessionId(response.id))
-        })
-        .await
-        .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))?
+        let lock = BlobLock::shared(conn, session_id.0).await?;
+        let written = self.write(lock.conn(), &session_entity).await;
+        let conn = lock.unlock().await?;
+
+        let outcome = match written {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                return Err(e);
+            }
+        };
+        if outcome.orphaned_blobs {
+            self.collect_blobs_after_write(conn, &session_id).await;
+        }
+        if !outcome.updated {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+
+        // A failed session no longer references any blob, so drop them all.
+        if is_failure {
+            self.blob_store
+                .delete_prefix(&blob_namespace(&session_id))
+                .await?;
+        }
+        Ok(session_id)
+    }
+
+    async fn set_client_artifact(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+        artifact: ClientArtifact,
+        data: Vec<u8>,
+    ) -> Result<bool, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let lock = BlobLock::shared(conn, session_id.0).await?;
+        let written = self
+            .write_artifact(lock.conn(), &session_id, &client_id, artifact, &data)
+            .await;
+        let conn = lock.unlock().await?;
+
+        match written {
+            Ok(ArtifactWrite::Stored {
+                completed,
+                replaced,
+            }) => {
+                if replaced {
+                    self.collect_blobs_after_write(conn, &session_id).await;
+                }
+                Ok(completed)
+            }
+            Ok(ArtifactWrite::WrongStatus(stored_status)) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                Err(CoreError::ValidationFail(format!(
+                    "Session {} is in status {}, expected {}",
+                    session_id,
+                    stored_status,
+                    artifact.phase()
+                )))
+            }
+            Ok(ArtifactWrite::UnknownClient) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                Err(CoreError::NotFound)
+            }
+            Err(e) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn set_result(
+        &self,
+        session_id: SessionId,
+        session_entity: SessionEntity,
+    ) -> Result<SessionId, CoreError> {
+        if session_id != session_entity.id {
+            return Err(CoreError::ValidationFail("Session ID mismatch".to_string()));
+        }
+
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let lock = BlobLock::shared(conn, session_id.0).await?;
+        let written = self.write_result(lock.conn(), &session_entity).await;
+        let conn = lock.unlock().await?;
+
+        let outcome = match written {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                return Err(e);
+            }
+        };
+        if outcome.orphaned_blobs {
+            self.collect_blobs_after_write(conn, &session_id).await;
+        }
+        if !outcome.updated {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+        Ok(session_id)
+    }
+
+    async fn set_computation(
+        &self,
+        session_id: SessionId,
+        version: u64,
+        computation_entity: ComputationEntity,
+    ) -> Result<SessionId, CoreError> {
+        let conn = self
+            .db
+            .get()
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        let lock = BlobLock::shared(conn, session_id.0).await?;
+        let written = self
+            .write_computation(lock.conn(), &session_id, version, &computation_entity)
+            .await;
+        let conn = lock.unlock().await?;
+
+        let outcome = match written {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.collect_blobs_after_write(conn, &session_id).await;
+                return Err(e);
+            }
+        };
+        if outcome.orphaned_blobs {
+            self.collect_blobs_after_write(conn, &session_id).await;
+        }
+        if !outcome.updated {
+            return Err(CoreError::Conflict(format!(
+                "Session {} was updated concurrently",
+                session_id
+            )));
+        }
+        Ok(session_id)
     }
 
     async fn delete(&self, session_id: SessionId) -> Result<(), CoreError> {
@@ -106,13 +1464,15 @@ impl SessionPort for SessionDBRepository {
             .get()
             .await
             .map_err(|e| CoreError::InternalError(e.into()))?;
+        let namespace = blob_namespace(&session_id);
         conn.interact(move |conn| {
             delete(sessions.filter(id.eq(session_id.0)))
                 .execute(conn)
-                .map_err(map_diesel_error)?;
-            Ok(())
+                .map_err(map_diesel_error)
         })
         .await
-        .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))?
+        .map_err(|e| CoreError::InternalError(Error::msg(e.to_string())))??;
+
+        self.blob_store.delete_prefix(&namespace).await
     }
 }
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use frog_core::entities::session::SessionId;
+use frog_core::entities::session_event::SessionEvent;
+use frog_core::errors::CoreError;
+use log::error;
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use uuid::Uuid;
+
+use crate::in_memory::session_event::SessionEventInMemoryBus;
+use crate::postgres::models::session::parse_status;
+
+/// Channel the `sessions` table trigger notifies on every status change.
+pub const SESSION_EVENTS_CHANNEL: &str = "session_events";
+
+/// Delay before listening again after the connection to the database was lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Payload of a notification sent by the `sessions` table trigger.
+#[derive(Deserialize)]
+struct SessionEventPayload {
+    id: Uuid,
+    status: String,
+    failure_reason: Option<String>,
+    version: i64,
+}
+
+impl From<SessionEventPayload> for SessionEvent {
+    fn from(payload: SessionEventPayload) -> Self {
+        Self {
+            session_id: SessionId(payload.id),
+            status: parse_status(&payload.status, payload.failure_reason),
+            version: payload.version as u64,
+        }
+    }
+}
+
+/// Relays the session events notified by PostgreSQL to an in-process bus.
+///
+/// The events are notified by a trigger on the `sessions` table, so the changes made by every
+/// server replica and worker are relayed.
+pub struct SessionEventListener;
+
+impl SessionEventListener {
+    /// Starts listening for session events in the background.
+    ///
+    /// # Arguments
+    /// - `url`: The PostgreSQL connection URL.
+    /// - `bus`: The bus the events are published to.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the listener is subscribed to the events channel.
+    /// - `Err(CoreError)` if the listener cannot connect to the database.
+    pub async fn spawn(url: &str, bus: SessionEventInMemoryBus) -> Result<(), CoreError> {
+        let mut listener = PgListener::connect(url)
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+        listener
+            .listen(SESSION_EVENTS_CHANNEL)
+            .await
+            .map_err(|e| CoreError::InternalError(e.into()))?;
+
+        tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        match serde_json::from_str::<SessionEventPayload>(notification.payload()) {
+                            Ok(payload) => bus.publish(payload.into()),
+                            Err(e) => error!("Invalid session event: {}", e),
+                        }
+                    }
+                    Err(e) => {
+                        // Notifications sent while disconnected are lost, so end the
+                        // subscriptions for the subscribers to read their sessions again.
+                        // The listener reconnects on the next call.
+                        error!("Failed to receive session events: {}", e);
+                        bus.reset();
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
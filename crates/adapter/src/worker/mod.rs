@@ -3,13 +3,15 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 use frog_common::workers::{
-    WorkerPayload, BS_KEY_SHARES_AGGREGATOR_WORKER_IDENTIFIER, COMPUTE_FUNCTION_WORKER_IDENTIFIER,
+    WorkerPayload, BS_KEY_SHARES_AGGREGATOR_WORKER_IDENTIFIER,
+    COMPUTE_COMPUTATION_WORKER_IDENTIFIER, COMPUTE_FUNCTION_WORKER_IDENTIFIER, MAX_JOB_ATTEMPTS,
 };
+use frog_core::entities::computation::ComputationId;
 use frog_core::entities::session::SessionId;
 use frog_core::errors::CoreError;
 use frog_core::errors::CoreError::WorkerError;
 use frog_core::ports::worker::WorkerPort;
-use graphile_worker::WorkerUtils;
+use graphile_worker::{JobSpec, WorkerUtils};
 use sqlx::postgres::PgConnectOptions;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
@@ -82,7 +84,10 @@ impl WorkerPort for WorkerAdapter {
             .add_raw_job(
                 BS_KEY_SHARES_AGGREGATOR_WORKER_IDENTIFIER,
                 payload,
-                Default::default(),
+                JobSpec {
+                    max_attempts: Some(MAX_JOB_ATTEMPTS),
+                    ..Default::default()
+                },
             )
             .await
             .map_err(|e| WorkerError(e.into()))?;
@@ -120,7 +125,56 @@ impl WorkerPort for WorkerAdapter {
             .add_raw_job(
                 COMPUTE_FUNCTION_WORKER_IDENTIFIER,
                 payload,
-                Default::default(),
+                JobSpec {
+                    max_attempts: Some(MAX_JOB_ATTEMPTS),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| WorkerError(e.into()))?;
+
+        Ok(())
+    }
+
+    /// Enqueues a task to compute an additional computation of the specified session.
+    ///
+    /// # Arguments
+    /// - `session_id`: The unique identifier of the session the computation belongs to.
+    /// - `computation_id`: The identifier of the computation within the session.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(CoreError)` if an error occurs while enqueuing the task.
+    async fn compute_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<(), CoreError> {
+        // Retrieve the current tracing span and context.
+        let span = tracing::Span::current();
+        let cx = span.context();
+
+        // Inject the tracing context into a HashMap to propagate it.
+        let mut tracing_info = HashMap::new();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut tracing_info)
+        });
+
+        // Create the payload containing the session and computation IDs and tracing information.
+        let payload = WorkerPayload {
+            data: (session_id, computation_id),
+            tracing: tracing_info,
+        };
+
+        // Enqueue the job with the worker system.
+        self.worker_utils
+            .add_raw_job(
+                COMPUTE_COMPUTATION_WORKER_IDENTIFIER,
+                payload,
+                JobSpec {
+                    max_attempts: Some(MAX_JOB_ATTEMPTS),
+                    ..Default::default()
+                },
             )
             .await
             .map_err(|e| WorkerError(e.into()))?;
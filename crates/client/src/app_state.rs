@@ -5,10 +5,15 @@ use crate::services::session::SessionService;
 #[derive(Clone)]
 pub struct AppState {
     pub session_service: Arc<SessionService>,
+    /// Bearer token required to provide the input, which cannot be provided when it is not set.
+    pub input_token: Option<Arc<str>>,
 }
 
 impl AppState {
-    pub fn new(session_service: Arc<SessionService>) -> Self {
-        Self { session_service }
+    pub fn new(session_service: Arc<SessionService>, input_token: Option<String>) -> Self {
+        Self {
+            session_service,
+            input_token: input_token.map(Arc::from),
+        }
     }
 }
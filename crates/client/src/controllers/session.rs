@@ -1,4 +1,10 @@
 use axum::extract::State;
+use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderMap;
+use axum::Json;
+use frog_common::blob::Blob;
+use frog_sdk::SessionResult;
+use serde::Deserialize;
 use tracing::instrument;
 
 use crate::app_state::AppState;
@@ -6,17 +12,54 @@ use crate::errors::AppError;
 use crate::json_response::JsonResponse;
 
 #[instrument(level = "info", skip(app_state))]
-pub async fn get_decryption_share(
-    State(app_state): State<AppState>,
-) -> Result<JsonResponse<Vec<u8>>, AppError> {
+pub async fn get_decryption_share(State(app_state): State<AppState>) -> Result<Blob, AppError> {
     let result = app_state.session_service.get_share().await?;
-    Ok(JsonResponse(result))
+    Ok(Blob(result))
 }
 
 #[instrument(level = "info", skip(app_state))]
 pub async fn get_result(
     State(app_state): State<AppState>,
-) -> Result<JsonResponse<Option<u64>>, AppError> {
+) -> Result<JsonResponse<Option<SessionResult>>, AppError> {
     let result = app_state.session_service.get_result().await?;
     Ok(JsonResponse(result))
 }
+
+/// Values the application on the same host contributes to the computation.
+#[derive(Debug, Deserialize)]
+pub struct InputRequest {
+    /// Values of the input, one per value of the `input_len` of the session.
+    pub values: Vec<i128>,
+}
+
+#[instrument(level = "info", skip(app_state, headers, input))]
+pub async fn set_input(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<InputRequest>,
+) -> Result<JsonResponse<()>, AppError> {
+    ensure_bearer_token(&headers, app_state.input_token.as_deref())?;
+    app_state.session_service.set_input(input.values).await?;
+    Ok(JsonResponse(()))
+}
+
+/// Fails unless the `Authorization` header holds the bearer `token`, which must be configured.
+fn ensure_bearer_token(headers: &HeaderMap, token: Option<&str>) -> Result<(), AppError> {
+    let provided = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match (provided, token) {
+        (Some(provided), Some(token))
+            if constant_time_eq(provided.as_bytes(), token.as_bytes()) =>
+        {
+            Ok(())
+        }
+        _ => Err(AppError::Unauthorized),
+    }
+}
+
+/// Compares two byte strings in a time that does not depend on where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
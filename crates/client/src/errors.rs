@@ -4,6 +4,7 @@ use axum::extract::rejection::JsonRejection;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use frog_core::errors::CoreError;
+use frog_sdk::SdkError;
 use thiserror::Error;
 
 use crate::json_response::JsonResponse;
@@ -26,6 +27,14 @@ pub enum AppError {
     /// Error during serialization or deserialization with `bincode`.
     #[error("Bincode error: {0}")]
     BincodeError(#[from] bincode::Error),
+
+    /// Error of the participant running the protocol.
+    #[error("SDK error: {0}")]
+    SdkError(#[from] SdkError),
+
+    /// The request lacks the token that authorizes it.
+    #[error("Unauthorized")]
+    Unauthorized,
 }
 
 /// Implements `IntoResponse` to convert `AppError` into an HTTP response.
@@ -66,6 +75,16 @@ impl IntoResponse for AppError {
                 }
             },
 
+            // The input posted to the client does not fit the session.
+            AppError::SdkError(SdkError::InvalidInput(message)) => {
+                (StatusCode::BAD_REQUEST, message)
+            }
+
+            // The caller is not allowed to use the endpoint, e.g. to provide the input.
+            AppError::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, AppError::Unauthorized.to_string())
+            }
+
             // Handle all other error types generically.
             error => {
                 // Log unexpected errors for debugging and monitoring purposes.
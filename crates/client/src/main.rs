@@ -1,37 +1,54 @@
-use std::collections::HashMap;
+use std::process::exit;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use clap::{Parser, Subcommand};
-use frog_adapter::http::peer::PeerClient;
+use frog_adapter::http::peer::{PeerClient, RelayPeerClient};
 use frog_adapter::http::session::SessionClient;
 use frog_client::app_state::AppState;
-use frog_client::options::{Options, Server};
+use frog_client::errors::AppError;
+use frog_client::options::{Options, PeerMode, Server};
 use frog_client::routes::routes;
 use frog_client::services::session::SessionService;
-use frog_common::cli_args::CliArgs;
 use frog_common::kill_signals;
 use frog_common::loggers::telemetry::init_telemetry;
-use frog_core::entities::session::SessionStatus;
+use frog_common::options::parse_options;
+use frog_core::entities::client::ClientId;
+use frog_core::errors::CoreError;
 use frog_core::ports::peer::PeerPort;
 use frog_core::ports::session_client::SessionClientPort;
+use frog_sdk::{Participant, ParticipantConfig, Phase};
 use opentelemetry::global;
-use phantom::client::Client;
 use phantom::crs::Crs;
-use phantom::native_ops::NativeOps;
-use phantom::param::Param;
-use phantom::utils::{pad_seed_to_32_bytes, I_2P_60};
-use phantom_zone_evaluator::boolean::fhew::prelude::{DecompositionParam, Modulus};
-use tokio::sync::RwLock;
-use tokio::time::sleep;
+use phantom::param::ParamPreset;
+use phantom::utils::pad_seed_to_32_bytes;
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
     // Parse CLI arguments and load options.
-    let options: Options = CliArgs::default_run_or_get_options(env!("APP_VERSION"));
+    let args = Args::parse();
+    if args.version {
+        println!("{}", env!("APP_VERSION"));
+        exit(0);
+    }
+    let options: Options = match parse_options(args.config_path) {
+        Ok(options) => options,
+        Err(err) => {
+            println!("Failed to load config: {}", err);
+            exit(1);
+        }
+    };
+    let param_preset = match ParamPreset::from_config(&options.client.param_preset) {
+        Ok(param_preset) => param_preset,
+        Err(err) => {
+            println!("Failed to load config: {}", err);
+            exit(1);
+        }
+    };
 
     // Initialize telemetry for distributed tracing and logging.
     init_telemetry(
@@ -40,15 +57,22 @@ async fn main() {
         options.log.level.as_str(),
     );
 
-    // Start the server and application flow.
-    let server_task = tokio::spawn(serve(options));
-
-    // Wait for the server and other tasks to finish gracefully.
-    tokio::try_join!(server_task).expect("Failed to run server");
+    // Run a single step of the protocol, or start the server and application flow.
+    let result = match args.command {
+        Some(command) => run_step(options, param_preset, command).await,
+        None => serve(options, param_preset).await,
+    };
+    let mut exit_code = 0;
+    if let Err(error) = result {
+        error!(%error, "Client failed");
+        eprintln!("{}", error);
+        exit_code = 1;
+    }
 
     // Shutdown the tracing provider before exiting.
     global::shutdown_tracer_provider();
     info!("Shutdown successfully!");
+    exit(exit_code);
 }
 
 /// Command-line arguments for the Frog Client application.
@@ -66,77 +90,188 @@ struct Args {
 }
 
 /// Subcommands supported by the Frog Client application.
+///
+/// Without a subcommand, the client runs every step of the protocol and serves its HTTP API.
+/// Otherwise, it runs a single step and exits, keeping what the next steps need in its state
+/// directory.
 #[derive(Subcommand, Clone, Debug)]
 enum Commands {
     /// Print the current configuration.
     Config,
+    /// Join the session with the key shares of this client.
+    Join,
+    /// Download the public key and send the bootstrapping key share of this client.
+    Bootstrap,
+    /// Encrypt and send the input of this client.
+    Submit {
+        /// Values of the input, one per value of the `input_len` of the session.
+        #[arg(
+            long = "value",
+            required = true,
+            value_delimiter = ',',
+            allow_negative_numbers = true
+        )]
+        values: Vec<i128>,
+    },
+    /// Print the status of the session.
+    Status,
+    /// Download the encrypted result and publish the decryption share of this client.
+    FetchResult,
+    /// Decrypt the result with the decryption shares of every client and print it.
+    Decrypt,
 }
 
-/// Starts the server and initializes application services.
+/// Runs a single step of the protocol.
 ///
 /// # Arguments
 /// - `options`: Configuration options loaded from CLI or configuration files.
-pub async fn serve(options: Options) {
-    if options.client.peer_endpoints.len() != 1 {
-        panic!("Invalid configuration: exactly one peer endpoint is required.");
+/// - `param_preset`: Phantom parameters of the configured preset.
+/// - `command`: Step to run.
+///
+/// # Returns
+/// - A validation error for the `fetch-result` and `decrypt` steps in `direct` peer mode.
+async fn run_step(
+    options: Options,
+    param_preset: &'static ParamPreset,
+    command: Commands,
+) -> Result<(), AppError> {
+    // In direct peer mode, peers download the decryption share of this client from its HTTP API,
+    // which is not served between single steps.
+    if options.client.peer_mode == PeerMode::Direct
+        && matches!(command, Commands::FetchResult | Commands::Decrypt)
+    {
+        return Err(CoreError::ValidationFail(
+            "The fetch-result and decrypt steps need the relay peer mode: in direct peer mode, \
+             peers download decryption shares from the HTTP API of each client, which is only \
+             served when running every step"
+                .to_string(),
+        )
+        .into());
     }
 
+    let participant = build_participant(&options, param_preset)?;
+    match command {
+        Commands::Config => println!("{:#?}", options),
+        Commands::Join => {
+            participant.check_params().await?;
+            participant.join().await?;
+        }
+        Commands::Bootstrap => participant.bootstrap().await?,
+        Commands::Submit { values } => participant.submit(&values).await?,
+        Commands::Status => {
+            println!("{:#?}", participant.summary().await?);
+            println!("Local phase: {:?}", participant.resume().await?);
+        }
+        Commands::FetchResult => participant.await_result().await?,
+        Commands::Decrypt => println!("{:?}", participant.try_decrypt().await?),
+    }
+    Ok(())
+}
+
+/// Builds the participant running the protocol of the client from the configuration.
+///
+/// # Arguments
+/// - `options`: Configuration options loaded from CLI or configuration files.
+/// - `param_preset`: Phantom parameters of the configured preset.
+///
+/// # Returns
+/// - A validation error if a peer endpoint is not keyed by a client ID in `direct` peer mode.
+fn build_participant(
+    options: &Options,
+    param_preset: &'static ParamPreset,
+) -> Result<Participant, AppError> {
     // Initialize HTTP clients for interacting with other services.
     let request_client = reqwest::Client::new();
     let session_client: Arc<dyn SessionClientPort + Sync + Send> = Arc::new(SessionClient::new(
         options.client.server_endpoint.clone(),
         request_client.clone(),
     ));
-    let peer_client: Arc<dyn PeerPort + Sync + Send> = Arc::new(PeerClient::new(request_client));
-
-    // Parse CRS seed from configuration and ensure it's 32 bytes long.
-    let crs_seed = pad_seed_to_32_bytes(options.client.crs_seed.as_bytes());
+    let peer_client: Arc<dyn PeerPort + Sync + Send> = match options.client.peer_mode {
+        PeerMode::Relay => Arc::new(RelayPeerClient::new(
+            request_client,
+            options.client.server_endpoint.clone(),
+            options.client.session_id.clone(),
+            options.client.client_id.clone(),
+        )),
+        PeerMode::Direct => {
+            let peer_endpoints = options
+                .client
+                .peer_endpoints
+                .iter()
+                .map(|(client_id, endpoint)| {
+                    let client_id = ClientId::from_str(client_id).map_err(|e| {
+                        CoreError::ValidationFail(format!(
+                            "Invalid configuration: peer endpoints must be keyed by client ID, \
+                             got '{}': {}",
+                            client_id, e
+                        ))
+                    })?;
+                    Ok((client_id, endpoint.clone()))
+                })
+                .collect::<Result<_, CoreError>>()?;
+            Arc::new(PeerClient::new(request_client, peer_endpoints))
+        }
+    };
 
-    // Initialize the CRS and Phantom client parameters.
-    let crs = Crs::new(crs_seed);
-    let phantom_param = create_phantom_param();
+    // Use a fixed CRS only if a seed is configured, e.g. in tests. Otherwise the CRS of the
+    // session is downloaded when joining it.
+    let crs = options
+        .client
+        .crs_seed
+        .as_ref()
+        .map(|crs_seed| Crs::new(pad_seed_to_32_bytes(crs_seed.as_bytes())));
 
     // Parse client seed from configuration and ensure it's 32 bytes long.
     let client_seed = pad_seed_to_32_bytes(options.client.client_seed.as_bytes());
 
-    // Create the Phantom client with the provided parameters.
-    let phantom_client = Arc::new(RwLock::new(
-        Client::<NativeOps>::new(
-            phantom_param,
-            crs,
-            options.client.client_id.0,
-            client_seed,
-            None,
-        )
-        .unwrap(),
-    ));
+    // Initialize the participant, which creates the Phantom client on first use.
+    let config = ParticipantConfig {
+        client_id: options.client.client_id.clone(),
+        session_id: options.client.session_id.clone(),
+        param_preset,
+        crs,
+        client_seed,
+        state_dir: Some(options.client.state_dir.clone().into()),
+    };
+    Ok(Participant::new(config, session_client, peer_client)
+        .with_progress(|progress| info!("Progress: {:?}", progress)))
+}
 
-    // Initialize the session service.
-    let session_service = Arc::new(SessionService::new(
-        options.client.client_id.clone(),
-        options.client.session_id.clone(),
-        session_client,
-        phantom_client.clone(),
-    ));
+/// Starts the server and initializes application services.
+///
+/// The HTTP server keeps serving the result once the protocol completed. The client stops if
+/// a step fails, and resumes from the last completed step when it is started again.
+///
+/// # Arguments
+/// - `options`: Configuration options loaded from CLI or configuration files.
+/// - `param_preset`: Phantom parameters of the configured preset.
+pub async fn serve(options: Options, param_preset: &'static ParamPreset) -> Result<(), AppError> {
+    let session_service = Arc::new(SessionService::new(build_participant(
+        &options,
+        param_preset,
+    )?));
+
+    // Fail early rather than generating keys the server could not aggregate.
+    session_service.participant().check_params().await?;
 
     // Start the server and main application flow.
-    let server_task = tokio::spawn(listen(options.server.clone(), session_service.clone()));
-    let main_flow_task = tokio::spawn(main_flow(
-        session_service.clone(),
-        peer_client.clone(),
-        options.client.peer_endpoints.clone(),
+    let server_task = tokio::spawn(listen(
+        options.server.clone(),
+        AppState::new(session_service.clone(), options.client.input_token.clone()),
     ));
-    tokio::try_join!(main_flow_task, server_task).expect("Failed to run server");
+    main_flow(session_service, options.client.input.clone()).await?;
+    server_task.await.expect("Failed to run server");
+    Ok(())
 }
 
 /// Starts the HTTP server and listens for incoming requests.
 ///
 /// # Arguments
 /// - `server`: Server configuration.
-/// - `session_service`: Shared session service instance.
-async fn listen(server: Server, session_service: Arc<SessionService>) {
+/// - `app_state`: State shared by the request handlers.
+async fn listen(server: Server, app_state: AppState) {
     // Define the application's routes with tracing and request timeout layers.
-    let routes = routes(AppState::new(session_service)).layer((
+    let routes = routes(app_state).layer((
         TraceLayer::new_for_http(),
         TimeoutLayer::new(Duration::from_secs(10)), // Ensure requests don't hang indefinitely.
     ));
@@ -158,57 +293,32 @@ async fn listen(server: Server, session_service: Arc<SessionService>) {
         .unwrap();
 }
 
-/// Main application flow.
+/// Main application flow, resumed from the last step the client completed.
+///
+/// # Arguments
+/// - `session_service`: Shared session service instance.
+/// - `input`: Configured input of this client, otherwise it waits for it to be posted.
 async fn main_flow(
     session_service: Arc<SessionService>,
-    peer_client: Arc<dyn PeerPort + Sync + Send>,
-    peer_endpoints: HashMap<String, String>,
-) {
-    session_service.join().await.unwrap();
-    session_service
-        .wait(SessionStatus::WaitingForBootstrap)
-        .await
-        .unwrap();
-    session_service.update_pk().await.unwrap();
-    session_service.bootstrap().await.unwrap();
-    session_service
-        .wait(SessionStatus::WaitingForArgument)
-        .await
-        .unwrap();
-    session_service.send_secret_data().await.unwrap();
-    session_service.wait(SessionStatus::Done).await.unwrap();
-    session_service.fetch_encrypted_result().await.unwrap();
-
-    // Collect decryption shares from all peers.
-    let mut dec_shares = vec![];
-    for endpoint in peer_endpoints.values() {
-        loop {
-            if let Ok(dec_share) = peer_client.get_dec_share(endpoint).await {
-                if !dec_share.is_empty() {
-                    dec_shares.push(dec_share);
-                    break;
-                }
-            }
-            sleep(Duration::from_secs(1)).await; // Retry until successful.
-        }
+    input: Option<Vec<i128>>,
+) -> Result<(), AppError> {
+    let participant = session_service.participant();
+    let phase = participant.resume().await?;
+    if phase != Phase::New {
+        info!("Resuming after phase {:?}", phase);
     }
 
-    // Decrypt and log the final result.
-    let result = session_service.decrypt_result(dec_shares).await.unwrap();
-    info!("RESULT: {}", result);
-}
-
-/// Creates Phantom client parameters.
-///
-/// # Returns
-/// A configured `Param` instance.
-fn create_phantom_param() -> Param {
-    Param {
-        param: I_2P_60,
-        ring_packing_modulus: Some(Modulus::Prime(2305843009213554689)),
-        ring_packing_auto_decomposition_param: DecompositionParam {
-            log_base: 20,
-            level: 1,
-        },
+    if let Some(input) = input.filter(|_| phase < Phase::Submitted) {
+        session_service.set_input(input).await?;
     }
+    participant.join().await?;
+    participant.bootstrap().await?;
+    // Waits for the input when it was neither configured nor posted yet.
+    session_service.submit().await?;
+
+    // Share the decryption share of this client with its peers, and decrypt with theirs.
+    participant.await_result().await?;
+    let result = participant.decrypt().await?;
+    info!("RESULT: {:?}", result);
+    Ok(())
 }
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
 
 use frog_common::options::{default_log, Log};
 use frog_core::entities::client::ClientId;
 use frog_core::entities::session::SessionId;
+use phantom::param::DEFAULT_PRESET;
 use serde::Deserialize;
 
 /// Configuration options for the application.
@@ -26,20 +28,67 @@ pub struct Options {
 }
 
 /// Represents server configuration.
-#[derive(Debug, Deserialize, Clone)]
+///
+/// Its `Debug` output redacts the client seed, the input and the input token.
+#[derive(Deserialize, Clone)]
 pub struct Client {
     /// The endpoint of the server.
     pub server_endpoint: String,
     /// Client ID
     pub client_id: ClientId,
-    /// Crs Seed
-    pub crs_seed: String,
+    /// Seed of the CRS, for deterministic tests only. The CRS of the session is downloaded when
+    /// joining it when it is not set.
+    pub crs_seed: Option<String>,
+    /// Name of the Phantom parameter preset, see `phantom::param::PRESETS`.
+    #[serde(default = "default_param_preset")]
+    pub param_preset: String,
     /// Client Seed
     pub client_seed: String,
-    /// The endpoints of other clients (client, end_point).
+    /// How decryption shares are exchanged with the other clients.
+    #[serde(default)]
+    pub peer_mode: PeerMode,
+    /// The endpoints of other clients (client, end_point), used in `direct` peer mode.
+    #[serde(default)]
     pub peer_endpoints: HashMap<String, String>,
     /// Session ID
     pub session_id: SessionId,
+    /// Values this client contributes, one per value of the `input_len` of the session. When it
+    /// is not set, the client waits for them to be posted to `/input`.
+    pub input: Option<Vec<i128>>,
+    /// Bearer token the application must send to post the input to `/input`, which is disabled
+    /// when it is not set.
+    pub input_token: Option<String>,
+    /// Directory keeping what the client needs between the steps of the protocol, by session
+    /// and client ID.
+    #[serde(default = "default_state_dir")]
+    pub state_dir: String,
+}
+
+impl Debug for Client {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("server_endpoint", &self.server_endpoint)
+            .field("client_id", &self.client_id)
+            .field("crs_seed", &self.crs_seed)
+            .field("param_preset", &self.param_preset)
+            .field("client_seed", &Redacted)
+            .field("peer_mode", &self.peer_mode)
+            .field("peer_endpoints", &self.peer_endpoints)
+            .field("session_id", &self.session_id)
+            .field("input", &self.input.as_ref().map(|_| Redacted))
+            .field("input_token", &self.input_token.as_ref().map(|_| Redacted))
+            .field("state_dir", &self.state_dir)
+            .finish()
+    }
+}
+
+/// Placeholder printed instead of a secret or the input of the client.
+struct Redacted;
+
+impl Debug for Redacted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
 }
 
 /// Represents server configuration.
@@ -50,3 +99,24 @@ pub struct Server {
     /// URL for the server.
     pub url: String,
 }
+
+/// Default Phantom parameter preset.
+fn default_param_preset() -> String {
+    DEFAULT_PRESET.to_string()
+}
+
+/// Default directory of the state of the client.
+fn default_state_dir() -> String {
+    "state".to_string()
+}
+
+/// Represents how decryption shares are exchanged with the other clients.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerMode {
+    /// Shares are uploaded to and downloaded from the server.
+    #[default]
+    Relay,
+    /// Shares are requested from the HTTP server of each peer.
+    Direct,
+}
@@ -1,9 +1,10 @@
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use axum::routing::post;
 use axum::{routing::get, Router};
 
 use crate::app_state::AppState;
-use crate::controllers::session::{get_decryption_share, get_result};
+use crate::controllers::session::{get_decryption_share, get_result, set_input};
 
 pub fn routes(app_state: AppState) -> Router {
     Router::new()
@@ -11,6 +12,7 @@ pub fn routes(app_state: AppState) -> Router {
         .route("/health", get(root))
         .route("/decrypt_share", get(get_decryption_share))
         .route("/result", get(get_result))
+        .route("/input", post(set_input))
         .with_state(app_state)
         .fallback(handler_404)
 }
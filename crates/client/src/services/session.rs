@@ -1,177 +1,92 @@
-use std::sync::Arc;
-use std::time::Duration;
-
-use frog_core::entities::client::{ClientEntity, ClientId};
-use frog_core::entities::session::{SessionId, SessionStatus};
-use frog_core::ports::session_client::SessionClientPort;
-use phantom::client::Client;
-use phantom::native_ops::NativeOps;
-use phantom::ops::Ops;
-use phantom::utils::{binary_to_u64, u64_to_binary};
-use tokio::sync::RwLock;
-use tokio::time;
-use tracing::debug;
+use frog_core::entities::session::SessionStatus;
+use frog_core::errors::CoreError;
+use frog_sdk::{Participant, Phase, SessionResult};
+use tokio::sync::watch;
+use tracing::info;
 
 use crate::errors::AppError;
 
+/// Runs the protocol of the client, with the input the configuration or the HTTP API provides.
 pub struct SessionService {
-    client_id: ClientId,
-    session_id: SessionId,
-    dec_share: Arc<RwLock<Vec<u8>>>,
-    encrypted_result: Arc<RwLock<Vec<Vec<u8>>>>,
-    result: Arc<RwLock<Option<u64>>>,
-
-    session_client: Arc<dyn SessionClientPort + Sync + Send>,
-    phantom_client: Arc<RwLock<Client<NativeOps>>>,
+    participant: Participant,
+    /// Values this client contributes, set once from the configuration or the HTTP API.
+    input: watch::Sender<Option<Vec<i128>>>,
 }
 
 impl SessionService {
-    pub fn new(
-        client_id: ClientId,
-        session_id: SessionId,
-        session_client: Arc<dyn SessionClientPort + Sync + Send>,
-        phantom_client: Arc<RwLock<Client<NativeOps>>>,
-    ) -> Self {
+    pub fn new(participant: Participant) -> Self {
         Self {
-            client_id,
-            session_id,
-            dec_share: Default::default(),
-            encrypted_result: Default::default(),
-            result: Default::default(),
-            session_client,
-            phantom_client,
+            participant,
+            input: watch::Sender::new(None),
         }
     }
 
-    pub async fn create(&self) -> Result<(), AppError> {
-        Ok(())
-    }
-
-    pub async fn join(&self) -> Result<(), AppError> {
-        let client = self.phantom_client.read().await;
-
-        let pk_share = client.serialize_pk_share(&client.pk_share_gen())?;
-
-        let rp_key_share = client.serialize_rp_key_share(&client.rp_key_share_gen())?;
-
-        self.session_client
-            .join_session(
-                self.session_id.clone(),
-                ClientEntity::new(self.client_id.clone(), pk_share, rp_key_share),
-            )
-            .await?;
-        Ok(())
-    }
-
-    pub async fn bootstrap(&self) -> Result<(), AppError> {
-        let client = self.phantom_client.read().await;
-
-        let bs_key = client.serialize_bs_key_share(&client.bs_key_share_gen())?;
-        self.session_client
-            .bootstrap(self.session_id.clone(), self.client_id.clone(), bs_key)
-            .await?;
-        Ok(())
+    /// Returns the participant running the protocol of the client.
+    pub fn participant(&self) -> &Participant {
+        &self.participant
     }
 
-    pub async fn wait(&self, session_status: SessionStatus) -> Result<(), AppError> {
-        loop {
-            let session_entity = self
-                .session_client
-                .get_session(self.session_id.clone())
-                .await?;
-            debug!("Session status: {:?}", session_entity.status);
-            if session_entity.status == session_status {
-                break;
+    /// Sets the values this client contributes to the computation of the session.
+    ///
+    /// The input can only be set once, and must hold one value of the input type of the session
+    /// for each of its `input_len` values.
+    ///
+    /// # Arguments
+    /// - `values`: Values to contribute, in order.
+    pub async fn set_input(&self, values: Vec<i128>) -> Result<(), AppError> {
+        self.participant.validate_input(&values).await?;
+
+        let is_set = self.input.send_if_modified(|input| {
+            if input.is_some() {
+                return false;
             }
-            time::sleep(Duration::from_secs(1)).await;
+            *input = Some(values);
+            true
+        });
+        if !is_set {
+            return Err(
+                CoreError::ValidationFail("The input was already provided".to_string()).into(),
+            );
         }
-
         Ok(())
     }
 
-    pub async fn update_pk(&self) -> Result<(), AppError> {
-        let session_entity = self
-            .session_client
-            .get_session(self.session_id.clone())
-            .await?;
-        if session_entity.status != SessionStatus::WaitingForBootstrap {
-            panic!("TODO:");
+    /// Waits until the input of this client is set, and returns it.
+    async fn wait_for_input(&self) -> Result<Vec<i128>, AppError> {
+        let mut input = self.input.subscribe();
+        if input.borrow().is_none() {
+            info!(
+                "Waiting for the input of client {}",
+                self.participant.client_id()
+            );
         }
-
-        let mut client = self.phantom_client.write().await;
-        let pk = client.deserialize_pk(&session_entity.pk)?;
-        client.with_pk(pk);
-        Ok(())
-    }
-
-    pub async fn send_secret_data(&self) -> Result<(), AppError> {
-        let data = 6_u64;
-        let client = self.phantom_client.read().await;
-        let input = u64_to_binary::<64>(data);
-        let encrypted_data =
-            client.serialize_batched_ct(&client.batched_pk_encrypt(input.into_iter()))?;
-        self.session_client
-            .send_data(
-                self.session_id.clone(),
-                self.client_id.clone(),
-                encrypted_data,
-            )
-            .await?;
-        Ok(())
+        let values = input
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| CoreError::UnexpectedResponse("Input channel closed".to_string()))?
+            .clone()
+            .unwrap_or_default();
+        Ok(values)
     }
 
-    pub async fn fetch_encrypted_result(&self) -> Result<(), AppError> {
-        let session_entity = self
-            .session_client
-            .get_session(self.session_id.clone())
+    /// Waits for the session to need the input, then for the input to be set, and submits it.
+    pub async fn submit(&self) -> Result<(), AppError> {
+        if self.participant.phase().await? >= Phase::Submitted {
+            return Ok(());
+        }
+        self.participant
+            .wait(SessionStatus::WaitingForArgument)
             .await?;
-        let client = self.phantom_client.read().await;
-        let dec_shares = session_entity
-            .encrypted_result
-            .iter()
-            .map(|ct| client.decrypt_share(&client.deserialize_ct(ct).unwrap()))
-            .collect::<Vec<_>>();
-        *self.dec_share.write().await = client.serialize_dec_shares(&dec_shares)?;
-        *self.encrypted_result.write().await = session_entity.encrypted_result;
+        let values = self.wait_for_input().await?;
+        self.participant.submit(&values).await?;
         Ok(())
     }
 
     pub async fn get_share(&self) -> Result<Vec<u8>, AppError> {
-        Ok(self.dec_share.read().await.clone())
-    }
-
-    pub async fn get_result(&self) -> Result<Option<u64>, AppError> {
-        Ok(*self.result.read().await)
+        Ok(self.participant.dec_share().await)
     }
 
-    pub async fn decrypt_result(&self, mut dec_shares: Vec<Vec<u8>>) -> Result<u64, AppError> {
-        let client = self.phantom_client.read().await;
-
-        let ct_out = &self
-            .encrypted_result
-            .read()
-            .await
-            .iter()
-            .map(|ct| client.deserialize_ct(ct).unwrap())
-            .collect::<Vec<_>>();
-
-        dec_shares.push(self.dec_share.read().await.clone());
-
-        let ct_out_dec_shares = dec_shares
-            .iter()
-            .map(|bytes| client.deserialize_dec_shares(bytes).unwrap())
-            .collect::<Vec<_>>();
-
-        let result = (0..ct_out.len())
-            .map(|idx| {
-                client.aggregate_decryption_shares(
-                    &ct_out[idx],
-                    ct_out_dec_shares.iter().map(|dec_shares| &dec_shares[idx]),
-                )
-            })
-            .collect::<Vec<_>>();
-        let result = binary_to_u64(result);
-        *self.result.write().await = Some(result);
-        Ok(result)
+    pub async fn get_result(&self) -> Result<Option<SessionResult>, AppError> {
+        Ok(self.participant.result().await)
     }
 }
@@ -0,0 +1,137 @@
+use axum::body::Bytes;
+use axum::extract::rejection::{BytesRejection, JsonRejection};
+use axum::extract::{FromRequest, Request};
+use axum::http::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+/// Content type of raw binary bodies.
+pub const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Checks whether a body is sent as `application/octet-stream`.
+///
+/// # Arguments
+/// - `headers`: The headers of the request or response carrying the body.
+pub fn is_octet_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with(OCTET_STREAM))
+}
+
+/// A binary payload, such as a key share or a ciphertext.
+///
+/// As an extractor, the body is read as raw bytes when sent as `application/octet-stream`,
+/// and as a JSON array of bytes otherwise, for older clients.
+/// As a response, the bytes are always sent as `application/octet-stream`.
+pub struct Blob(pub Vec<u8>);
+
+/// Rejection of a binary request body that could not be read.
+#[derive(Debug)]
+pub enum BlobRejection {
+    /// The raw body could not be read.
+    Bytes(BytesRejection),
+    /// The body was not a JSON array of bytes.
+    Json(JsonRejection),
+}
+
+impl BlobRejection {
+    /// Status code of the rejection.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            BlobRejection::Bytes(rejection) => rejection.status(),
+            BlobRejection::Json(rejection) => rejection.status(),
+        }
+    }
+
+    /// Human readable reason of the rejection.
+    pub fn body_text(&self) -> String {
+        match self {
+            BlobRejection::Bytes(rejection) => rejection.body_text(),
+            BlobRejection::Json(rejection) => rejection.body_text(),
+        }
+    }
+}
+
+impl IntoResponse for BlobRejection {
+    /// Answers with the same JSON error body as the other errors of the services.
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct ErrorResponse {
+            message: String,
+        }
+
+        let message = self.body_text();
+        (self.status(), axum::Json(ErrorResponse { message })).into_response()
+    }
+}
+
+impl<S> FromRequest<S> for Blob
+where
+    S: Send + Sync,
+{
+    type Rejection = BlobRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if is_octet_stream(req.headers()) {
+            let bytes = Bytes::from_request(req, state)
+                .await
+                .map_err(BlobRejection::Bytes)?;
+            Ok(Blob(bytes.to_vec()))
+        } else {
+            let axum::Json(bytes) = axum::Json::<Vec<u8>>::from_request(req, state)
+                .await
+                .map_err(BlobRejection::Json)?;
+            Ok(Blob(bytes))
+        }
+    }
+}
+
+impl Blob {
+    /// Serves the bytes with the given `ETag`.
+    ///
+    /// If the `If-None-Match` header of the request already names this tag, the response is
+    /// `304 Not Modified` without a body, so heavy artifacts are only downloaded once.
+    ///
+    /// # Arguments
+    /// - `etag`: The quoted entity tag identifying the content, see [`not_modified`].
+    /// - `request_headers`: The headers of the request being answered.
+    pub fn with_etag(self, etag: String, request_headers: &HeaderMap) -> Response {
+        if let Some(response) = not_modified(&etag, request_headers) {
+            return response;
+        }
+        ([(ETAG, etag)], self).into_response()
+    }
+}
+
+/// Answers `304 Not Modified` if the `If-None-Match` header of the request names the tag.
+///
+/// Lets a handler skip loading the content when the caller already holds it.
+///
+/// # Arguments
+/// - `etag`: The quoted entity tag identifying the current content, e.g. `"<hash>"`.
+/// - `request_headers`: The headers of the request being answered.
+///
+/// # Returns
+/// - `Some(Response)` with the `304 Not Modified` response if the content is unchanged.
+/// - `None` if the content must be sent.
+pub fn not_modified(etag: &str, request_headers: &HeaderMap) -> Option<Response> {
+    let is_cached = request_headers
+        .get(IF_NONE_MATCH)
+        .and_then(|if_none_match| if_none_match.to_str().ok())
+        .is_some_and(|if_none_match| {
+            if_none_match
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag)
+        });
+
+    is_cached.then(|| (StatusCode::NOT_MODIFIED, [(ETAG, etag.to_string())]).into_response())
+}
+
+impl IntoResponse for Blob {
+    fn into_response(self) -> Response {
+        ([(CONTENT_TYPE, OCTET_STREAM)], self.0).into_response()
+    }
+}
@@ -1,3 +1,4 @@
+pub mod blob;
 pub mod cli_args;
 pub mod kill_signals;
 pub mod loggers;
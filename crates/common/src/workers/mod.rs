@@ -33,3 +33,18 @@ pub const BS_KEY_SHARES_AGGREGATOR_WORKER_IDENTIFIER: &str = "bs_key_shares";
 /// Workers with this identifier are responsible for executing cryptographic calculations
 /// as part of a session.
 pub const COMPUTE_FUNCTION_WORKER_IDENTIFIER: &str = "compute_function";
+
+/// Identifier for the worker responsible for performing an additional computation of a session.
+///
+/// Workers with this identifier compute the function of a session over the inputs of one of its
+/// later computations, reusing the keys aggregated for the session.
+pub const COMPUTE_COMPUTATION_WORKER_IDENTIFIER: &str = "compute_computation";
+
+/// Identifier for the periodic worker responsible for expiring abandoned sessions.
+///
+/// Workers with this identifier move sessions that outlived their deadlines to the `Expired`
+/// status and purge their key material.
+pub const EXPIRE_SESSIONS_WORKER_IDENTIFIER: &str = "expire_sessions";
+
+/// Maximum number of attempts for a session job before the session is marked as failed.
+pub const MAX_JOB_ATTEMPTS: i16 = 5;
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::CoreError;
+
+/// Reference to a blob kept in a blob store, along with the hash of its content.
+///
+/// Blobs are content-addressed: the key of a blob ends with the hash of its content, so
+/// storing the same content twice yields the same reference.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlobRef {
+    /// Key of the blob in the blob store.
+    pub key: String,
+    /// Hex-encoded SHA-256 hash of the content of the blob.
+    pub hash: String,
+}
+
+impl BlobRef {
+    /// Builds the reference of some content stored under a namespace.
+    ///
+    /// # Parameters
+    /// - `namespace`: The prefix grouping related blobs, e.g. all the blobs of a session.
+    /// - `data`: The content of the blob.
+    pub fn new(namespace: &str, data: &[u8]) -> Self {
+        let hash = content_hash(data);
+        Self {
+            key: format!("{}/{}", namespace, hash),
+            hash,
+        }
+    }
+
+    /// Checks that some content is the one this reference points to.
+    ///
+    /// # Returns
+    /// - `Err(CoreError)`: If the content was altered or belongs to another blob.
+    pub fn verify(&self, data: &[u8]) -> Result<(), CoreError> {
+        if content_hash(data) != self.hash {
+            return Err(CoreError::InternalError(anyhow::anyhow!(
+                "Content of blob {} does not match its hash",
+                self.key
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Computes the hex-encoded SHA-256 hash of some content.
+pub fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
@@ -4,12 +4,15 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
+use crate::entities::session::SessionStatus;
+
 /// Represents a client in a session.
 ///
 /// Each `ClientEntity` contains:
 /// - A unique `ClientId` identifying the client.
 /// - Cryptographic key shares (`pk_share`, `rp_key_share`, and `bs_key_share`).
 /// - Encrypted data associated with the client.
+/// - The client's decryption share of the session result, relayed to the other clients.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ClientEntity {
     pub id: ClientId,            // Unique identifier for the client
@@ -17,12 +20,15 @@ pub struct ClientEntity {
     pub rp_key_share: Vec<u8>,   // Ring packing key share for the client
     pub bs_key_share: Vec<u8>,   // Bootstrap key share for the client
     pub encrypted_data: Vec<u8>, // Encrypted data specific to the client
+    #[serde(default)]
+    pub dec_share: Vec<u8>, // Decryption share of the session result
 }
 
 impl ClientEntity {
     /// Constructs a new `ClientEntity` instance with the given ID and key shares.
     ///
-    /// The `bs_key_share` and `encrypted_data` fields are initialized to their default empty values.
+    /// The `bs_key_share`, `encrypted_data` and `dec_share` fields are initialized to their default
+    /// empty values.
     ///
     /// # Parameters
     /// - `id`: The unique identifier for the client.
@@ -38,12 +44,56 @@ impl ClientEntity {
             rp_key_share,
             bs_key_share: Default::default(),
             encrypted_data: Default::default(),
+            dec_share: Default::default(),
+        }
+    }
+}
+
+/// An artifact a client provides once every participant joined the session.
+///
+/// Each artifact is stored on its own, so that providing it does not rewrite the rest of the
+/// session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientArtifact {
+    /// The bootstrapping key share of the client.
+    BsKeyShare,
+    /// The encrypted input of the client.
+    EncryptedData,
+    /// The decryption share of the session result.
+    DecShare,
+}
+
+impl ClientArtifact {
+    /// Returns the status the session must be in for the artifact to be provided.
+    pub fn phase(self) -> SessionStatus {
+        match self {
+            ClientArtifact::BsKeyShare => SessionStatus::WaitingForBootstrap,
+            ClientArtifact::EncryptedData => SessionStatus::WaitingForArgument,
+            ClientArtifact::DecShare => SessionStatus::Done,
+        }
+    }
+
+    /// Returns the artifact of a client, empty if the client has not provided it yet.
+    pub fn of(self, client_entity: &ClientEntity) -> &Vec<u8> {
+        match self {
+            ClientArtifact::BsKeyShare => &client_entity.bs_key_share,
+            ClientArtifact::EncryptedData => &client_entity.encrypted_data,
+            ClientArtifact::DecShare => &client_entity.dec_share,
+        }
+    }
+
+    /// Returns the artifact of a client, to be replaced.
+    pub fn of_mut(self, client_entity: &mut ClientEntity) -> &mut Vec<u8> {
+        match self {
+            ClientArtifact::BsKeyShare => &mut client_entity.bs_key_share,
+            ClientArtifact::EncryptedData => &mut client_entity.encrypted_data,
+            ClientArtifact::DecShare => &mut client_entity.dec_share,
         }
     }
 }
 
 /// Represents a unique identifier for a client, implemented as a wrapper around `usize`.
-#[derive(Debug, Serialize, Deserialize, Eq, Hash, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord, Clone)]
 pub struct ClientId(pub usize);
 
 impl FromStr for ClientId {
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::client::ClientId;
+use crate::entities::session::ResultFormat;
+
+/// Represents an additional computation of a session, run with the keys aggregated for it.
+///
+/// The first computation of a session is the session itself: its inputs are the encrypted data
+/// of its clients and its result is the encrypted result of the session. Once it is done,
+/// further computations can be created, each with its own inputs and result.
+///
+/// Each `ComputationEntity` contains:
+/// - A unique computation ID within the session (`id`).
+/// - The current status of the computation (`status`).
+/// - The encrypted input of each client that submitted one (`inputs`).
+/// - The decryption share of the result of each client that provided one (`dec_shares`).
+/// - The encrypted result of the computation (`encrypted_result`) and its format
+///   (`result_format`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComputationEntity {
+    /// Identifier of the computation within its session.
+    pub id: ComputationId,
+    /// Current status of the computation.
+    pub status: ComputationStatus,
+    /// Encrypted input of each client, by client ID.
+    pub inputs: BTreeMap<ClientId, Vec<u8>>,
+    /// Decryption share of the result of each client, by client ID.
+    pub dec_shares: BTreeMap<ClientId, Vec<u8>>,
+    /// Encrypted result of the computation.
+    pub encrypted_result: Vec<Vec<u8>>,
+    /// Format of the ciphertexts of the encrypted result.
+    pub result_format: ResultFormat,
+    /// Time at which the computation was created.
+    pub created_at: SystemTime,
+}
+
+impl ComputationEntity {
+    /// Creates a new `ComputationEntity` waiting for the inputs of the clients.
+    ///
+    /// # Parameters
+    /// - `id`: Identifier of the computation within its session.
+    ///
+    /// # Returns
+    /// A new `ComputationEntity` instance.
+    pub fn new(id: ComputationId) -> Self {
+        Self {
+            id,
+            status: ComputationStatus::WaitingForArgument,
+            inputs: Default::default(),
+            dec_shares: Default::default(),
+            encrypted_result: Default::default(),
+            result_format: Default::default(),
+            created_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Represents the identifier of a computation within its session, starting at 1.
+#[derive(Debug, Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord, Clone, Copy)]
+pub struct ComputationId(pub usize);
+
+impl ComputationId {
+    /// Identifier of the first computation of a session, which is the session itself.
+    pub const FIRST: ComputationId = ComputationId(1);
+}
+
+impl fmt::Display for ComputationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Represents the status of a computation.
+///
+/// The computation can be in one of the following states:
+/// - `WaitingForArgument`: Waiting for clients to send their inputs.
+/// - `Done`: The result is computed.
+/// - `Failed`: A worker could not compute the result; `reason` describes the last error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ComputationStatus {
+    WaitingForArgument,
+    Done,
+    Failed { reason: String },
+}
+
+impl ComputationStatus {
+    /// Checks whether the computation ended in this status, successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, ComputationStatus::WaitingForArgument)
+    }
+}
+
+impl FromStr for ComputationStatus {
+    type Err = String;
+
+    /// Parses a `ComputationStatus` from its string representation.
+    ///
+    /// `Failed` is parsed with an empty reason, since the reason is not part of the string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "WaitingForArgument" => Ok(ComputationStatus::WaitingForArgument),
+            "Done" => Ok(ComputationStatus::Done),
+            "Failed" => Ok(ComputationStatus::Failed {
+                reason: String::new(),
+            }),
+            _ => Err(format!("'{}' is not a valid computation status", s)),
+        }
+    }
+}
+
+impl fmt::Display for ComputationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputationStatus::WaitingForArgument => write!(f, "WaitingForArgument"),
+            ComputationStatus::Done => write!(f, "Done"),
+            ComputationStatus::Failed { .. } => write!(f, "Failed"),
+        }
+    }
+}
+
+/// A lightweight view of a computation, without any ciphertext.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComputationSummary {
+    /// Identifier of the computation within its session.
+    pub id: ComputationId,
+    /// Current status of the computation.
+    pub status: ComputationStatus,
+    /// Clients that submitted their input, ordered by client ID.
+    pub submitted: Vec<ClientId>,
+    /// Whether the encrypted result is available.
+    pub has_result: bool,
+    /// Format of the ciphertexts of the encrypted result.
+    pub result_format: ResultFormat,
+    /// Time at which the computation was created.
+    pub created_at: SystemTime,
+}
+
+impl From<&ComputationEntity> for ComputationSummary {
+    /// Summarizes a computation, dropping its ciphertexts.
+    fn from(computation_entity: &ComputationEntity) -> Self {
+        Self {
+            id: computation_entity.id,
+            status: computation_entity.status.clone(),
+            submitted: computation_entity.inputs.keys().cloned().collect(),
+            has_result: !computation_entity.encrypted_result.is_empty(),
+            result_format: computation_entity.result_format,
+            created_at: computation_entity.created_at,
+        }
+    }
+}
@@ -1,2 +1,8 @@
+pub mod blob;
 pub mod client;
+pub mod computation;
+pub mod params;
 pub mod session;
+pub mod session_event;
+pub mod session_query;
+pub mod session_summary;
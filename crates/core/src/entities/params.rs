@@ -0,0 +1,148 @@
+use phantom::crs::Crs;
+use phantom::param::ParamPreset;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::blob::content_hash;
+
+/// Canonical hashes of the Phantom parameters and CRS a session runs with.
+///
+/// Clients compare it with their own configuration before joining, since keys generated with
+/// other parameters or another CRS cannot be aggregated and only fail much later.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParamsFingerprint {
+    /// Name of the parameter preset.
+    pub preset: String,
+    /// Hex-encoded SHA-256 hash of the `bincode` encoding of the parameters.
+    pub param_hash: String,
+    /// Hex-encoded SHA-256 hash of the `bincode` encoding of the CRS, empty when every session
+    /// gets its own CRS.
+    pub crs_hash: String,
+}
+
+impl ParamsFingerprint {
+    /// Computes the fingerprint of a parameter preset used with a CRS, if the CRS is fixed.
+    pub fn new(param_preset: &ParamPreset, crs: Option<&Crs>) -> Self {
+        Self {
+            preset: param_preset.name.to_string(),
+            param_hash: content_hash(
+                &bincode::serialize(&param_preset.param).expect("Parameters are serializable"),
+            ),
+            crs_hash: crs
+                .map(|crs| content_hash(&bincode::serialize(crs).expect("CRS is serializable")))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Lists the fields that differ from another fingerprint, along with both values.
+    ///
+    /// A field that is empty on either side is unknown, e.g. the CRS hash when every session gets
+    /// its own CRS, and is only compared when both fingerprints set it.
+    ///
+    /// # Parameters
+    /// - `expected`: The fingerprint this one should match, e.g. the one of the server.
+    ///
+    /// # Returns
+    /// One description per differing field, empty if both fingerprints match.
+    pub fn differences(&self, expected: &ParamsFingerprint) -> Vec<String> {
+        [
+            ("preset", &self.preset, &expected.preset),
+            ("param_hash", &self.param_hash, &expected.param_hash),
+            ("crs_hash", &self.crs_hash, &expected.crs_hash),
+        ]
+        .into_iter()
+        .filter(|(_, actual, expected)| {
+            !actual.is_empty() && !expected.is_empty() && actual != expected
+        })
+        .map(|(field, actual, expected)| {
+            format!("{} (expected {}, got {})", field, expected, actual)
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(preset: &str, param_hash: &str, crs_hash: &str) -> ParamsFingerprint {
+        ParamsFingerprint {
+            preset: preset.to_string(),
+            param_hash: param_hash.to_string(),
+            crs_hash: crs_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn matching_fingerprints_have_no_differences() {
+        let expected = fingerprint("i_2p_60", "params", "crs");
+        assert!(fingerprint("i_2p_60", "params", "crs")
+            .differences(&expected)
+            .is_empty());
+    }
+
+    #[test]
+    fn differences_name_each_differing_field() {
+        let expected = fingerprint("i_2p_60", "params", "crs");
+
+        assert_eq!(
+            fingerprint("i_3p_60", "params", "crs").differences(&expected),
+            vec!["preset (expected i_2p_60, got i_3p_60)"]
+        );
+        assert_eq!(
+            fingerprint("i_2p_60", "other", "crs").differences(&expected),
+            vec!["param_hash (expected params, got other)"]
+        );
+        assert_eq!(
+            fingerprint("i_2p_60", "params", "other").differences(&expected),
+            vec!["crs_hash (expected crs, got other)"]
+        );
+        assert_eq!(
+            fingerprint("i_3p_60", "other", "other").differences(&expected),
+            vec![
+                "preset (expected i_2p_60, got i_3p_60)",
+                "param_hash (expected params, got other)",
+                "crs_hash (expected crs, got other)",
+            ]
+        );
+    }
+
+    #[test]
+    fn fields_are_only_compared_when_both_are_set() {
+        let expected = fingerprint("i_2p_60", "params", "crs");
+        for unknown in [
+            fingerprint("", "params", "crs"),
+            fingerprint("i_2p_60", "", "crs"),
+            fingerprint("i_2p_60", "params", ""),
+            fingerprint("", "", ""),
+        ] {
+            assert!(unknown.differences(&expected).is_empty(), "{:?}", unknown);
+            assert!(expected.differences(&unknown).is_empty(), "{:?}", unknown);
+        }
+
+        // The fields set on both sides are still compared.
+        assert_eq!(
+            fingerprint("", "other", "").differences(&expected),
+            vec!["param_hash (expected params, got other)"]
+        );
+        assert_eq!(
+            expected.differences(&fingerprint("i_3p_60", "", "")),
+            vec!["preset (expected i_3p_60, got i_2p_60)"]
+        );
+    }
+
+    #[test]
+    fn presets_have_distinct_fingerprints() {
+        let two_parties = ParamsFingerprint::new(ParamPreset::by_name("i_2p_60").unwrap(), None);
+        let three_parties = ParamsFingerprint::new(ParamPreset::by_name("i_3p_60").unwrap(), None);
+        assert_eq!(
+            three_parties.differences(&two_parties),
+            vec![
+                "preset (expected i_2p_60, got i_3p_60)".to_string(),
+                format!(
+                    "param_hash (expected {}, got {})",
+                    two_parties.param_hash, three_parties.param_hash
+                ),
+            ]
+        );
+    }
+}
@@ -1,15 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::mem;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 use phantom::crs::Crs;
+use phantom::function::{ValueType, DEFAULT_FUNCTION};
 use phantom::native_ops::NativeOps;
-use phantom::param::Param;
+use phantom::param::ParamPreset;
 use phantom::server::PhantomServer;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::entities::client::{ClientEntity, ClientId};
+use crate::entities::computation::{ComputationEntity, ComputationId};
+use crate::entities::params::ParamsFingerprint;
 use crate::errors::CoreError;
 
 /// Represents a session entity, which manages the state and data of a session.
@@ -17,21 +22,50 @@ use crate::errors::CoreError;
 /// Each `SessionEntity` contains:
 /// - A unique session ID (`id`).
 /// - The current status of the session (`status`).
+/// - The settings chosen when the session was created (`settings`).
+/// - The common reference string generated for the session (`crs`).
+/// - The fingerprint of the Phantom parameters and CRS of the session (`params`).
 /// - Information about the connected clients (`client_info`).
 /// - Cryptographic data such as the aggregated public key (`pk`) and encrypted results (`encrypted_result`).
+/// - The format of the encrypted result (`result_format`).
+/// - The computations run after the first one with the same keys (`computations`).
 /// - A `PhantomServer` instance for handling Phantom related operations.
+/// - The version of the stored session this entity was read from (`version`).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SessionEntity {
     /// Unique identifier for the session.
     pub id: SessionId,
     /// Current status of the session.
     pub status: SessionStatus,
+    /// Settings chosen when the session was created.
+    pub settings: SessionSettings,
+    /// Common reference string of the session, missing for sessions created before it was
+    /// generated per session.
+    #[serde(default)]
+    pub crs: Option<Crs>,
+    /// Fingerprint of the Phantom parameters and CRS the session runs with.
+    #[serde(default)]
+    pub params: ParamsFingerprint,
     /// Mapping of client IDs to their respective entities.
     pub client_info: HashMap<ClientId, ClientEntity>,
     /// Public key associated with the session.
     pub pk: Vec<u8>,
     /// Encrypted results generated during the session.
     pub encrypted_result: Vec<Vec<u8>>,
+    /// Format of the ciphertexts of the encrypted result.
+    #[serde(default)]
+    pub result_format: ResultFormat,
+    /// Computations run once the session is done, reusing its keys, by computation ID.
+    #[serde(default)]
+    pub computations: BTreeMap<ComputationId, ComputationEntity>,
+    /// Time at which the session was created.
+    pub created_at: SystemTime,
+    /// Time after which the session expires, whatever its status.
+    pub expires_at: SystemTime,
+    /// Time after which the session expires if it is still waiting in its current phase.
+    pub phase_deadline: SystemTime,
+    /// Version of the stored session, incremented on every update.
+    pub version: u64,
 
     /// Server-side Phantom related data.
     #[serde(skip_serializing, skip_deserializing)]
@@ -43,31 +77,166 @@ impl SessionEntity {
     ///
     /// Initializes the session with default values for `status`, `client_info`,
     /// `encrypted_result`, and `pk`. A `PhantomServer` instance is created using the provided
-    /// `phantom_param` and `crs`.
+    /// `param_preset` and `crs`.
     ///
     /// # Parameters
     /// - `id`: Unique identifier for the session.
-    /// - `phantom_param`: Parameters required for the PhantomServer instance.
+    /// - `settings`: Settings chosen by the creator of the session.
+    /// - `param_preset`: Parameters required for the PhantomServer instance.
     /// - `crs`: Common Reference String.
     ///
     /// # Returns
     /// A new `SessionEntity` instance.
-    pub fn new(id: SessionId, phantom_param: Param, crs: Crs) -> Self {
-        Self {
+    pub fn new(
+        id: SessionId,
+        settings: SessionSettings,
+        param_preset: &ParamPreset,
+        crs: Crs,
+    ) -> Self {
+        let created_at = SystemTime::now();
+        let expires_at = created_at + Duration::from_secs(settings.ttl_secs);
+        let mut session_entity = Self {
             id,
             status: SessionStatus::WaitingForClients,
+            settings,
+            crs: Some(crs),
+            params: ParamsFingerprint::new(param_preset, Some(&crs)),
             client_info: Default::default(),
             encrypted_result: Default::default(),
+            result_format: Default::default(),
+            computations: Default::default(),
             pk: Default::default(),
-            phantom_server: Some(PhantomServer::new(phantom_param, crs, None, None, None).unwrap()),
+            created_at,
+            expires_at,
+            phase_deadline: expires_at,
+            version: 0,
+            phantom_server: Some(
+                PhantomServer::new(param_preset.param, crs, None, None, None).unwrap(),
+            ),
+        };
+        session_entity.transition(SessionStatus::WaitingForClients);
+        session_entity
+    }
+
+    /// Moves the session to a new status and restarts the deadline of the new phase.
+    ///
+    /// The phase deadline never exceeds the expiry time of the session.
+    ///
+    /// # Parameters
+    /// - `status`: The new status of the session.
+    pub fn transition(&mut self, status: SessionStatus) {
+        let phase_deadline =
+            SystemTime::now() + Duration::from_secs(self.settings.phase_timeout_secs);
+        self.phase_deadline = phase_deadline.min(self.expires_at);
+        self.status = status;
+    }
+
+    /// Checks whether the session has outlived its TTL or its current phase deadline.
+    ///
+    /// Sessions that already ended in a failure status are never overdue.
+    ///
+    /// # Parameters
+    /// - `now`: The time to compare the deadlines against.
+    ///
+    /// # Returns
+    /// `true` if the session should be expired.
+    pub fn is_overdue(&self, now: SystemTime) -> bool {
+        if self.status.is_failure() {
+            return false;
         }
+        now >= self.expires_at || (self.status.is_waiting() && now >= self.phase_deadline)
+    }
+
+    /// Ends the session in a failure status and drops all of its key material and ciphertexts.
+    ///
+    /// # Parameters
+    /// - `status`: The failure status the session ends with.
+    pub fn terminate(&mut self, status: SessionStatus) {
+        self.status = status;
+        self.client_info.clear();
+        self.pk.clear();
+        self.encrypted_result.clear();
+        self.computations.clear();
+        self.phantom_server = None;
     }
+
+    /// Returns the ID the next computation of the session gets.
+    ///
+    /// The session itself is its first computation, so the additional ones start at the second.
+    pub fn next_computation_id(&self) -> ComputationId {
+        let last = self
+            .computations
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or(ComputationId::FIRST);
+        ComputationId(last.0 + 1)
+    }
+}
+
+/// Settings chosen by the creator of a session.
+///
+/// These are provided when the session is created and stay fixed for its whole lifetime.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SessionSettings {
+    /// Number of clients required before the key aggregation starts.
+    pub participant_number: usize,
+    /// Name of the function computed over the clients' inputs, see `phantom::function::FUNCTIONS`.
+    #[serde(default = "default_computation")]
+    pub computation: String,
+    /// Type of the input every client provides, e.g. `u32`, `i64` or `bool`.
+    #[serde(default = "default_input_type")]
+    pub input_type: ValueType,
+    /// Number of values every client provides, the outputs having as many values.
+    #[serde(default = "default_input_len")]
+    pub input_len: usize,
+    /// User-supplied labels attached to the session.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Maximum lifetime of the session, in seconds.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Maximum time, in seconds, the session may wait in a single phase.
+    #[serde(default = "default_phase_timeout_secs")]
+    pub phase_timeout_secs: u64,
+}
+
+/// Default computation used when a session is created without specifying one.
+fn default_computation() -> String {
+    DEFAULT_FUNCTION.to_string()
+}
+
+/// Default type of the inputs of the clients.
+fn default_input_type() -> ValueType {
+    ValueType::U64
+}
+
+/// Default number of values of the inputs of the clients.
+fn default_input_len() -> usize {
+    1
+}
+
+/// Default lifetime of a session: one day.
+fn default_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+/// Default time a session may wait in a single phase: one hour.
+fn default_phase_timeout_secs() -> u64 {
+    60 * 60
 }
 
 /// Represents a unique identifier for a session, implemented as a wrapper around `Uuid`.
 #[derive(Debug, Serialize, Deserialize, Eq, Hash, PartialEq, Clone)]
 pub struct SessionId(pub Uuid);
 
+impl SessionId {
+    /// Generates a new random `SessionId`.
+    pub fn generate() -> Self {
+        SessionId(Uuid::new_v4())
+    }
+}
+
 impl TryFrom<&str> for SessionId {
     type Error = CoreError;
 
@@ -102,12 +271,58 @@ impl fmt::Display for SessionId {
 /// - `WaitingForBootstrap`: Waiting for the bootstrap phase to complete.
 /// - `WaitingForArgument`: Waiting for clients to send their arguments.
 /// - `Done`: The session is complete.
+/// - `Expired`: The session outlived its deadlines and its data was purged.
+/// - `Failed`: A worker could not process the session; `reason` describes the last error.
+/// - `Aborted`: The session was cancelled on request.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SessionStatus {
     WaitingForClients,
     WaitingForBootstrap,
     WaitingForArgument,
     Done,
+    Expired,
+    Failed { reason: String },
+    Aborted,
+}
+
+impl SessionStatus {
+    /// Statuses in which the session waits for clients and is bound by a phase deadline.
+    pub const WAITING: [SessionStatus; 3] = [
+        SessionStatus::WaitingForClients,
+        SessionStatus::WaitingForBootstrap,
+        SessionStatus::WaitingForArgument,
+    ];
+
+    /// Statuses in which the session ended without producing a result.
+    ///
+    /// The `Failed` reason is left empty, so compare with [`SessionStatus::same_kind`].
+    pub const FAILURES: [SessionStatus; 3] = [
+        SessionStatus::Expired,
+        SessionStatus::Failed {
+            reason: String::new(),
+        },
+        SessionStatus::Aborted,
+    ];
+
+    /// Checks whether the session is waiting for clients in this status.
+    pub fn is_waiting(&self) -> bool {
+        Self::WAITING.contains(self)
+    }
+
+    /// Checks whether the session ended without producing a result in this status.
+    pub fn is_failure(&self) -> bool {
+        Self::FAILURES.iter().any(|status| status.same_kind(self))
+    }
+
+    /// Checks whether the session ended in this status, successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        *self == SessionStatus::Done || self.is_failure()
+    }
+
+    /// Compares two statuses while ignoring the details they carry, such as a failure reason.
+    pub fn same_kind(&self, other: &SessionStatus) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
 }
 
 impl FromStr for SessionStatus {
@@ -118,6 +333,8 @@ impl FromStr for SessionStatus {
     /// # Parameters
     /// - `s`: A string representing the session status.
     ///
+    /// `Failed` is parsed with an empty reason, since the reason is not part of the string.
+    ///
     /// # Returns
     /// - `Ok(SessionStatus)`: If the string matches a valid status.
     /// - `Err`: If the string does not match any valid status.
@@ -127,6 +344,11 @@ impl FromStr for SessionStatus {
             "WaitingForBootstrap" => Ok(SessionStatus::WaitingForBootstrap),
             "WaitingForArgument" => Ok(SessionStatus::WaitingForArgument),
             "Done" => Ok(SessionStatus::Done),
+            "Expired" => Ok(SessionStatus::Expired),
+            "Failed" => Ok(SessionStatus::Failed {
+                reason: String::new(),
+            }),
+            "Aborted" => Ok(SessionStatus::Aborted),
             _ => Err(format!("'{}' is not a valid status", s)),
         }
     }
@@ -142,6 +364,43 @@ impl fmt::Display for SessionStatus {
             SessionStatus::WaitingForBootstrap => write!(f, "WaitingForBootstrap"),
             SessionStatus::WaitingForArgument => write!(f, "WaitingForArgument"),
             SessionStatus::Done => write!(f, "Done"),
+            SessionStatus::Expired => write!(f, "Expired"),
+            SessionStatus::Failed { .. } => write!(f, "Failed"),
+            SessionStatus::Aborted => write!(f, "Aborted"),
+        }
+    }
+}
+
+/// Represents how the bits of an encrypted result are laid out in its ciphertexts.
+///
+/// - `Unpacked`: One LWE ciphertext per bit, each decrypted with its own decryption share.
+/// - `Packed`: The bits are ring-packed into RLWE ciphertexts holding up to `ring_size` bits
+///   each, so a client sends a single decryption share per ciphertext.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultFormat {
+    #[default]
+    Unpacked,
+    Packed,
+}
+
+impl FromStr for ResultFormat {
+    type Err = String;
+
+    /// Parses a `ResultFormat` from its string representation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Unpacked" => Ok(ResultFormat::Unpacked),
+            "Packed" => Ok(ResultFormat::Packed),
+            _ => Err(format!("'{}' is not a valid result format", s)),
+        }
+    }
+}
+
+impl fmt::Display for ResultFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResultFormat::Unpacked => write!(f, "Unpacked"),
+            ResultFormat::Packed => write!(f, "Packed"),
         }
     }
 }
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::session::{SessionEntity, SessionId, SessionStatus};
+use crate::entities::session_summary::SessionSummary;
+
+/// A change of status of a session, pushed to the clients following it.
+///
+/// A session reaching `Done` means its encrypted result is available.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    /// ID of the session that changed.
+    pub session_id: SessionId,
+    /// Status of the session after the change.
+    pub status: SessionStatus,
+    /// Version of the session after the change.
+    pub version: u64,
+}
+
+impl From<&SessionEntity> for SessionEvent {
+    /// Builds the event describing the current status of a session.
+    fn from(session_entity: &SessionEntity) -> Self {
+        Self {
+            session_id: session_entity.id.clone(),
+            status: session_entity.status.clone(),
+            version: session_entity.version,
+        }
+    }
+}
+
+impl From<&SessionSummary> for SessionEvent {
+    /// Builds the event describing the current status of a summarized session.
+    fn from(session_summary: &SessionSummary) -> Self {
+        Self {
+            session_id: session_summary.id.clone(),
+            status: session_summary.status.clone(),
+            version: session_summary.version,
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::session::{SessionEntity, SessionId, SessionStatus};
+use crate::entities::session_summary::SessionSummary;
+use crate::errors::CoreError;
+
+/// Default number of sessions returned in a single page.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Maximum number of sessions returned in a single page.
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// Criteria used to search for sessions.
+///
+/// All filters are optional and combined with a logical AND. Sessions are returned ordered by
+/// creation time, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct SessionQuery {
+    /// Only return sessions in this status.
+    pub status: Option<SessionStatus>,
+    /// Only return sessions created at or after this time.
+    pub created_after: Option<SystemTime>,
+    /// Only return sessions created strictly before this time.
+    pub created_before: Option<SystemTime>,
+    /// Only return sessions carrying all of these labels.
+    pub labels: HashMap<String, String>,
+    /// Resume the listing after the session identified by this cursor.
+    pub cursor: Option<SessionCursor>,
+    /// Maximum number of sessions to return.
+    pub limit: usize,
+}
+
+impl SessionQuery {
+    /// Checks whether a session matches the filters of the query, ignoring pagination.
+    pub fn matches(&self, session_entity: &SessionEntity) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| status.same_kind(&session_entity.status))
+            && self
+                .created_after
+                .is_none_or(|after| session_entity.created_at >= after)
+            && self
+                .created_before
+                .is_none_or(|before| session_entity.created_at < before)
+            && self
+                .labels
+                .iter()
+                .all(|(key, value)| session_entity.settings.labels.get(key) == Some(value))
+    }
+}
+
+/// A single page of sessions returned by a search.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionPage<T> {
+    /// The sessions of this page.
+    pub sessions: Vec<T>,
+    /// Cursor to pass to the next query, or `None` if this is the last page.
+    pub next_cursor: Option<SessionCursor>,
+}
+
+/// Position of a session in the listing order, used for cursor pagination.
+///
+/// The cursor is exchanged with callers as an opaque string of the form
+/// `<nanoseconds since epoch>_<session id>`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct SessionCursor {
+    /// Creation time of the last session returned.
+    pub created_at: SystemTime,
+    /// ID of the last session returned.
+    pub id: SessionId,
+}
+
+impl SessionCursor {
+    /// Builds the cursor pointing right after the given session.
+    pub fn after(session_summary: &SessionSummary) -> Self {
+        Self {
+            created_at: session_summary.created_at,
+            id: session_summary.id.clone(),
+        }
+    }
+}
+
+impl TryFrom<&str> for SessionCursor {
+    type Error = CoreError;
+
+    /// Parses a cursor from its string representation.
+    ///
+    /// # Parameters
+    /// - `cursor`: A string of the form `<nanoseconds since epoch>_<session id>`.
+    ///
+    /// # Returns
+    /// - `Ok(SessionCursor)`: If the string is a valid cursor.
+    /// - `Err`: If the string is malformed.
+    fn try_from(cursor: &str) -> Result<Self, Self::Error> {
+        let invalid = || CoreError::ValidationFail(format!("Invalid cursor: {}", cursor));
+        let (nanos, id) = cursor.split_once('_').ok_or_else(invalid)?;
+        let nanos = nanos.parse::<u64>().map_err(|_| invalid())?;
+        Ok(Self {
+            created_at: UNIX_EPOCH + Duration::from_nanos(nanos),
+            id: SessionId(Uuid::parse_str(id).map_err(|_| invalid())?),
+        })
+    }
+}
+
+impl TryFrom<String> for SessionCursor {
+    type Error = CoreError;
+
+    fn try_from(cursor: String) -> Result<Self, Self::Error> {
+        SessionCursor::try_from(cursor.as_str())
+    }
+}
+
+impl From<SessionCursor> for String {
+    fn from(cursor: SessionCursor) -> Self {
+        cursor.to_string()
+    }
+}
+
+impl fmt::Display for SessionCursor {
+    /// Formats the cursor as `<nanoseconds since epoch>_<session id>`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self
+            .created_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        write!(f, "{}_{}", nanos, self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_through_its_string_form() {
+        let cursor = SessionCursor {
+            created_at: UNIX_EPOCH + Duration::from_nanos(1_735_000_000_123_456_789),
+            id: SessionId::generate(),
+        };
+
+        let encoded = cursor.to_string();
+        assert_eq!(
+            encoded,
+            format!("1735000000123456789_{}", cursor.id),
+            "cursor is encoded as <nanos>_<id>"
+        );
+        assert_eq!(SessionCursor::try_from(encoded.as_str()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_strings() {
+        let id = SessionId::generate();
+        for cursor in [
+            String::new(),
+            "123".to_string(),
+            format!("abc_{}", id),
+            format!("-1_{}", id),
+            "123_not-a-uuid".to_string(),
+            format!("{}0_{}", u64::MAX, id),
+        ] {
+            assert!(
+                matches!(
+                    SessionCursor::try_from(cursor.as_str()),
+                    Err(CoreError::ValidationFail(_))
+                ),
+                "cursor {:?} should be rejected",
+                cursor
+            );
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::SystemTime;
+
+use phantom::crs::Crs;
+use phantom::function::{FheFunction, OutputDescriptor};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::client::ClientId;
+use crate::entities::params::ParamsFingerprint;
+use crate::entities::session::{
+    ResultFormat, SessionEntity, SessionId, SessionSettings, SessionStatus,
+};
+
+/// A lightweight view of a session, without any key material or ciphertext.
+///
+/// This is what clients poll to follow the progress of a session, and where they download its
+/// CRS when joining. The aggregated public key and the encrypted result are fetched separately,
+/// once they are available.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// Unique identifier for the session.
+    pub id: SessionId,
+    /// Current status of the session.
+    pub status: SessionStatus,
+    /// Settings chosen when the session was created.
+    pub settings: SessionSettings,
+    /// Common reference string the key shares of the session are generated with.
+    pub crs: Option<Crs>,
+    /// Fingerprint of the Phantom parameters and CRS the session runs with.
+    pub params: ParamsFingerprint,
+    /// Layout of the outputs in the result, in order.
+    pub outputs: Vec<OutputDescriptor>,
+    /// Progress of each client that joined the session, ordered by client ID.
+    pub participants: Vec<ClientProgress>,
+    /// Whether the aggregated public key is available.
+    pub has_pk: bool,
+    /// Whether the encrypted result is available.
+    pub has_result: bool,
+    /// Format of the ciphertexts of the encrypted result.
+    pub result_format: ResultFormat,
+    /// Time at which the session was created.
+    pub created_at: SystemTime,
+    /// Time after which the session expires, whatever its status.
+    pub expires_at: SystemTime,
+    /// Time after which the session expires if it is still waiting in its current phase.
+    pub phase_deadline: SystemTime,
+    /// Version of the stored session, incremented on every update.
+    pub version: u64,
+}
+
+/// The artifacts a client of a session has provided so far.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientProgress {
+    /// ID of the client.
+    pub id: ClientId,
+    /// Whether the client provided its bootstrapping key share.
+    pub has_bs_key_share: bool,
+    /// Whether the client provided its encrypted data.
+    pub has_encrypted_data: bool,
+    /// Whether the client provided its decryption share of the result.
+    pub has_dec_share: bool,
+}
+
+impl SessionSummary {
+    /// Describes the outputs in the result of a session created with the given settings.
+    ///
+    /// Empty if the computation of the settings is unknown.
+    pub fn output_layout(settings: &SessionSettings) -> Vec<OutputDescriptor> {
+        FheFunction::by_name(&settings.computation)
+            .map(|function| function.output_descriptors(settings.input_type, settings.input_len))
+            .unwrap_or_default()
+    }
+}
+
+impl From<&SessionEntity> for SessionSummary {
+    /// Summarizes a session, dropping its key material and ciphertexts.
+    fn from(session_entity: &SessionEntity) -> Self {
+        let mut participants = session_entity
+            .client_info
+            .values()
+            .map(|client| ClientProgress {
+                id: client.id.clone(),
+                has_bs_key_share: !client.bs_key_share.is_empty(),
+                has_encrypted_data: !client.encrypted_data.is_empty(),
+                has_dec_share: !client.dec_share.is_empty(),
+            })
+            .collect::<Vec<_>>();
+        participants.sort_by_key(|client| client.id.0);
+
+        Self {
+            id: session_entity.id.clone(),
+            status: session_entity.status.clone(),
+            settings: session_entity.settings.clone(),
+            crs: session_entity.crs,
+            params: session_entity.params.clone(),
+            outputs: SessionSummary::output_layout(&session_entity.settings),
+            participants,
+            has_pk: !session_entity.pk.is_empty(),
+            has_result: !session_entity.encrypted_result.is_empty(),
+            result_format: session_entity.result_format,
+            created_at: session_entity.created_at,
+            expires_at: session_entity.expires_at,
+            phase_deadline: session_entity.phase_deadline,
+            version: session_entity.version,
+        }
+    }
+}
@@ -37,6 +37,12 @@ pub enum CoreError {
     #[error("Validation fails: {0}")]
     ValidationFail(String),
 
+    /// Error indicating that a resource was modified concurrently.
+    ///
+    /// The caller should read the resource again and retry its change.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     /// Error originating from worker nodes.
     ///
     /// This wraps `anyhow::Error` for errors reported by worker processes or tasks.
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+
+use crate::errors::CoreError;
+
+/// Defines an asynchronous interface for storing large binary objects.
+///
+/// Key material and ciphertexts are kept in a blob store rather than in the database, which
+/// only keeps references to them. It can be implemented by different storage backends, such as
+/// a local filesystem or an S3-compatible object storage.
+#[async_trait]
+pub trait BlobStorePort {
+    /// Stores a blob under a key, replacing any blob stored under the same key.
+    ///
+    /// # Parameters
+    /// - `key`: The key of the blob, made of `/`-separated segments.
+    /// - `data`: The content of the blob.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the blob is stored.
+    /// - `Err(CoreError)`: Returns an error if the blob cannot be stored.
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), CoreError>;
+
+    /// Retrieves the blob stored under a key.
+    ///
+    /// # Parameters
+    /// - `key`: The key of the blob.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: Returns the content of the blob.
+    /// - `Err(CoreError::NotFound)`: Returns an error if no blob is stored under the key.
+    /// - `Err(CoreError)`: Returns an error if the blob cannot be retrieved.
+    async fn get(&self, key: &str) -> Result<Vec<u8>, CoreError>;
+
+    /// Lists the keys of the blobs whose key starts with a prefix.
+    ///
+    /// # Parameters
+    /// - `prefix`: The prefix of the keys to list, e.g. the namespace of a session.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<String>)`: Returns the keys, empty if there are none.
+    /// - `Err(CoreError)`: Returns an error if the keys cannot be listed.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, CoreError>;
+
+    /// Deletes the blob stored under a key.
+    ///
+    /// # Parameters
+    /// - `key`: The key of the blob.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the blob is deleted, or if there was none.
+    /// - `Err(CoreError)`: Returns an error if the blob cannot be deleted.
+    async fn delete(&self, key: &str) -> Result<(), CoreError>;
+
+    /// Deletes every blob whose key starts with a prefix.
+    ///
+    /// # Parameters
+    /// - `prefix`: The prefix of the keys to delete, e.g. the namespace of a session.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the blobs are deleted, or if there were none.
+    /// - `Err(CoreError)`: Returns an error if the blobs cannot be deleted.
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), CoreError>;
+}
@@ -1,4 +1,6 @@
+pub mod blob_store;
 pub mod peer;
 pub mod session;
 pub mod session_client;
+pub mod session_event;
 pub mod worker;
@@ -1,22 +1,34 @@
 use async_trait::async_trait;
 
+use crate::entities::client::ClientId;
 use crate::errors::CoreError;
 
-/// Defines an asynchronous interface for interacting with peers.
+/// Defines an asynchronous interface for exchanging decryption shares with peers.
 ///
-/// The `PeerPort` trait allows for requesting decryption shares from peer nodes.
+/// The `PeerPort` trait allows for publishing the client's decryption share and requesting the
+/// decryption shares of peer nodes, either directly from the peers or through the server.
 #[async_trait]
 pub trait PeerPort {
+    /// Makes the client's decryption share available to its peers.
+    ///
+    /// # Parameters
+    /// - `dec_share`: The serialized decryption share of the client.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the decryption share is published.
+    /// - `Err(CoreError)`: If the request fails due to network errors, invalid responses, or other issues.
+    async fn publish_dec_share(&self, dec_share: Vec<u8>) -> Result<(), CoreError>;
+
     /// Requests a decryption share from a specified peer.
     ///
-    /// This method communicates with a peer node to retrieve its contribution (decryption share),
-    /// which can be used in the decryption process during multi-party computations.
+    /// This method retrieves the contribution (decryption share) of a peer, which can be used in
+    /// the decryption process during multi-party computations.
     ///
     /// # Parameters
-    /// - `peer_endpoint`: The network address or endpoint of the peer node.
+    /// - `peer_id`: The ID of the peer client.
     ///
     /// # Returns
-    /// - `Ok(Vec<u8>)`: If the decryption share is successfully retrieved as a byte vector.
+    /// - `Ok(Vec<u8>)`: If the request succeeds; the share is empty if the peer has not published it yet.
     /// - `Err(CoreError)`: If the request fails due to network errors, invalid responses, or other issues.
-    async fn get_dec_share(&self, peer_endpoint: &str) -> Result<Vec<u8>, CoreError>;
+    async fn get_dec_share(&self, peer_id: &ClientId) -> Result<Vec<u8>, CoreError>;
 }
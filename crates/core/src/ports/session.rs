@@ -1,11 +1,20 @@
+use std::time::SystemTime;
+
 use async_trait::async_trait;
+use phantom::native_ops::NativeOps;
+use phantom::server::PhantomServer;
 
+use crate::entities::blob::BlobRef;
+use crate::entities::client::{ClientArtifact, ClientEntity, ClientId};
+use crate::entities::computation::ComputationEntity;
 use crate::entities::session::{SessionEntity, SessionId};
+use crate::entities::session_query::{SessionPage, SessionQuery};
+use crate::entities::session_summary::SessionSummary;
 use crate::errors::CoreError;
 
 /// Defines an asynchronous interface for managing session entities.
 ///
-/// This trait abstracts the operations required to create, retrieve,
+/// This trait abstracts the operations required to create, retrieve, search,
 /// update, and delete sessions. It can be implemented by different storage backends,
 /// such as databases or in-memory stores.
 #[async_trait]
@@ -30,14 +39,152 @@ pub trait SessionPort {
     /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
     async fn get(&self, session_id: SessionId) -> Result<SessionEntity, CoreError>;
 
+    /// Retrieves a session without its `PhantomServer`, the most expensive part to load.
+    ///
+    /// The `phantom_server` of the returned entity is always `None`, so it must not be written
+    /// back as is.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session to retrieve.
+    ///
+    /// # Returns
+    /// - `Ok(SessionEntity)`: Returns the requested session entity, without its Phantom server.
+    /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
+    async fn get_without_server(&self, session_id: SessionId) -> Result<SessionEntity, CoreError>;
+
+    /// Retrieves the summary of a session, without loading any key material or ciphertext.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session to summarize.
+    ///
+    /// # Returns
+    /// - `Ok(SessionSummary)`: Returns the summary of the session.
+    /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
+    async fn get_summary(&self, session_id: SessionId) -> Result<SessionSummary, CoreError>;
+
+    /// Retrieves the reference to the aggregated public key of a session, without loading it.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    ///
+    /// # Returns
+    /// - `Ok(Some(BlobRef))`: Returns the reference to the public key, to be loaded with
+    ///   [`SessionPort::get_blob`].
+    /// - `Ok(None)`: If not every participant joined the session yet.
+    /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
+    async fn get_pk_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError>;
+
+    /// Retrieves the references to the ciphertexts of the encrypted result of a session, without
+    /// loading them.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<BlobRef>)`: Returns the references to the ciphertexts in order, empty if the
+    ///   result was not computed yet.
+    /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
+    async fn get_result_refs(&self, session_id: SessionId) -> Result<Vec<BlobRef>, CoreError>;
+
+    /// Loads a blob of a session from its reference.
+    ///
+    /// # Parameters
+    /// - `blob_ref`: The reference returned by [`SessionPort::get_pk_ref`] or
+    ///   [`SessionPort::get_result_refs`].
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: Returns the content of the blob, checked against its hash.
+    /// - `Err(CoreError::NotFound)`: Returns an error if the blob no longer exists, e.g. because
+    ///   its session was purged in the meantime.
+    /// - `Err(CoreError)`: Returns an error if retrieval fails.
+    async fn get_blob(&self, blob_ref: &BlobRef) -> Result<Vec<u8>, CoreError>;
+
+    /// Retrieves the reference to the Phantom server of a session, without loading it.
+    ///
+    /// The server is stored content-addressed, so the hash of the reference changes whenever keys
+    /// are aggregated into it, and only then.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    ///
+    /// # Returns
+    /// - `Ok(Some(BlobRef))`: Returns the reference to the server, to be loaded with
+    ///   [`SessionPort::get_server`].
+    /// - `Ok(None)`: If the session ended and its server was purged.
+    /// - `Err(CoreError)`: Returns an error if the session does not exist or retrieval fails.
+    async fn get_server_ref(&self, session_id: SessionId) -> Result<Option<BlobRef>, CoreError>;
+
+    /// Loads the Phantom server of a session from its reference.
+    ///
+    /// # Parameters
+    /// - `server_ref`: The reference returned by [`SessionPort::get_server_ref`].
+    ///
+    /// # Returns
+    /// - `Ok(PhantomServer)`: Returns the server, with its aggregated keys.
+    /// - `Err(CoreError::NotFound)`: Returns an error if the server was replaced or purged in the
+    ///   meantime.
+    /// - `Err(CoreError)`: Returns an error if retrieval fails.
+    async fn get_server(&self, server_ref: &BlobRef)
+        -> Result<PhantomServer<NativeOps>, CoreError>;
+
+    /// Retrieves a single client of a session, without loading the rest of the session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session the client joined.
+    /// - `client_id`: The unique identifier of the client to retrieve.
+    ///
+    /// # Returns
+    /// - `Ok(ClientEntity)`: Returns the requested client entity.
+    /// - `Err(CoreError)`: Returns an error if the client did not join the session or retrieval
+    ///   fails.
+    async fn get_client(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+    ) -> Result<ClientEntity, CoreError>;
+
+    /// Searches for sessions matching the given query.
+    ///
+    /// Sessions are returned ordered by creation time, oldest first, and at most
+    /// `query.limit` sessions are returned per call. They are summarized without loading any key
+    /// material or ciphertext.
+    ///
+    /// # Parameters
+    /// - `query`: The filters and pagination cursor of the search.
+    ///
+    /// # Returns
+    /// - `Ok(SessionPage)`: Returns the summaries of the matching sessions and the cursor of the
+    ///   next page, if any.
+    /// - `Err(CoreError)`: Returns an error if the search fails.
+    async fn list(&self, query: SessionQuery) -> Result<SessionPage<SessionSummary>, CoreError>;
+
+    /// Finds sessions that have outlived their TTL or their current phase deadline.
+    ///
+    /// # Parameters
+    /// - `now`: The time to compare the deadlines against.
+    /// - `limit`: The maximum number of session IDs to return.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<SessionId>)`: Returns the IDs of the overdue sessions.
+    /// - `Err(CoreError)`: Returns an error if the search fails.
+    async fn list_overdue(
+        &self,
+        now: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<SessionId>, CoreError>;
+
     /// Updates an existing session with new data.
     ///
+    /// The update only succeeds if the stored session still has the `version` the entity was
+    /// read with, in which case the stored version is incremented.
+    ///
     /// # Parameters
     /// - `session_id`: The unique identifier of the session to update.
     /// - `session_entity`: The updated session entity data.
     ///
     /// # Returns
     /// - `Ok(SessionId)`: Returns the unique identifier of the updated session.
+    /// - `Err(CoreError::Conflict)`: Returns an error if the session was updated concurrently.
     /// - `Err(CoreError)`: Returns an error if the update fails.
     async fn update(
         &self,
@@ -45,6 +192,77 @@ pub trait SessionPort {
         session_entity: SessionEntity,
     ) -> Result<SessionId, CoreError>;
 
+    /// Stores an artifact of a client, without reading or writing the rest of the session.
+    ///
+    /// The artifact replaces the one the client may have provided before, and the version of
+    /// the session is incremented.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session the client joined.
+    /// - `client_id`: The unique identifier of the client providing the artifact.
+    /// - `artifact`: The kind of artifact provided.
+    /// - `data`: The serialized artifact.
+    ///
+    /// # Returns
+    /// - `Ok(true)`: If this artifact was the last one missing, so that every participant has
+    ///   now provided it. Concurrent calls never both return `true`.
+    /// - `Ok(false)`: If some participant has still not provided it, or all had already.
+    /// - `Err(CoreError::ValidationFail)`: Returns an error if the session is not in the
+    ///   [`ClientArtifact::phase`] of the artifact.
+    /// - `Err(CoreError::NotFound)`: Returns an error if the session does not exist or the client
+    ///   did not join it.
+    /// - `Err(CoreError)`: Returns an error if the update fails.
+    async fn set_client_artifact(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+        artifact: ClientArtifact,
+        data: Vec<u8>,
+    ) -> Result<bool, CoreError>;
+
+    /// Stores the encrypted result of a session along with its new status.
+    ///
+    /// Only the status, phase deadline, result format and encrypted result of the entity are
+    /// written; its clients, computations and Phantom server are left as stored, so the entity
+    /// may be read with [`SessionPort::get_without_server`]. Like [`SessionPort::update`], it
+    /// only succeeds if the stored session still has the `version` the entity was read with.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session to update.
+    /// - `session_entity`: The session, with its result and new status.
+    ///
+    /// # Returns
+    /// - `Ok(SessionId)`: Returns the unique identifier of the updated session.
+    /// - `Err(CoreError::Conflict)`: Returns an error if the session was updated concurrently.
+    /// - `Err(CoreError)`: Returns an error if the update fails.
+    async fn set_result(
+        &self,
+        session_id: SessionId,
+        session_entity: SessionEntity,
+    ) -> Result<SessionId, CoreError>;
+
+    /// Stores an additional computation of a session, without writing the rest of the session.
+    ///
+    /// The computation replaces the stored one with the same ID, if any. Like
+    /// [`SessionPort::update`], it only succeeds if the stored session still has the given
+    /// version, in which case the stored version is incremented.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session the computation belongs to.
+    /// - `version`: The version of the session the computation was read with.
+    /// - `computation_entity`: The computation to store.
+    ///
+    /// # Returns
+    /// - `Ok(SessionId)`: Returns the unique identifier of the updated session.
+    /// - `Err(CoreError::Conflict)`: Returns an error if the session was updated concurrently.
+    /// - `Err(CoreError)`: Returns an error if the update fails.
+    async fn set_computation(
+        &self,
+        session_id: SessionId,
+        version: u64,
+        computation_entity: ComputationEntity,
+    ) -> Result<SessionId, CoreError>;
+
     /// Deletes a session by its unique identifier.
     ///
     /// # Parameters
@@ -55,3 +273,56 @@ pub trait SessionPort {
     /// - `Err(CoreError)`: Returns an error if the deletion fails.
     async fn delete(&self, session_id: SessionId) -> Result<(), CoreError>;
 }
+
+/// Maximum number of attempts to apply a change to a session that is updated concurrently.
+pub const MAX_UPDATE_ATTEMPTS: usize = 10;
+
+/// Applies a change to a session, retrying on concurrent updates.
+///
+/// The session is read, changed and written back until the write does not conflict with another
+/// update, so the change is always applied to the latest version of the session.
+///
+/// # Parameters
+/// - `session_port`: The repository storing the session.
+/// - `session_id`: The unique identifier of the session to change.
+/// - `change`: Changes the session and returns whether it must be written back.
+///   It may run several times, so it must not have side effects.
+///
+/// # Returns
+/// - `Ok(SessionEntity)`: Returns the session as it was last written or read.
+/// - `Err(E)`: Returns the error of the change, or a `CoreError::Conflict` if the session kept
+///   being updated concurrently.
+pub async fn update_with_retry<E, F>(
+    session_port: &(dyn SessionPort + Send + Sync),
+    session_id: SessionId,
+    mut change: F,
+) -> Result<SessionEntity, E>
+where
+    E: From<CoreError>,
+    F: FnMut(&mut SessionEntity) -> Result<bool, E>,
+{
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+        let mut session_entity = session_port.get(session_id.clone()).await?;
+        if !change(&mut session_entity)? {
+            return Ok(session_entity);
+        }
+
+        match session_port
+            .update(session_id.clone(), session_entity.clone())
+            .await
+        {
+            Ok(_) => {
+                session_entity.version += 1;
+                return Ok(session_entity);
+            }
+            Err(CoreError::Conflict(_)) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(CoreError::Conflict(format!(
+        "Session {} is updated too often, gave up after {} attempts",
+        session_id, MAX_UPDATE_ATTEMPTS
+    ))
+    .into())
+}
@@ -1,12 +1,23 @@
 use async_trait::async_trait;
 
 use crate::entities::client::{ClientEntity, ClientId};
-use crate::entities::session::{SessionEntity, SessionId};
+use crate::entities::computation::{ComputationId, ComputationSummary};
+use crate::entities::params::ParamsFingerprint;
+use crate::entities::session::{SessionId, SessionStatus};
+use crate::entities::session_event::SessionEvent;
+use crate::entities::session_summary::SessionSummary;
 use crate::errors::CoreError;
 
 /// Defines an asynchronous interface for client interactions within a session on the server.
 #[async_trait]
 pub trait SessionClientPort {
+    /// Retrieves the fingerprint of the Phantom parameters and CRS of the server.
+    ///
+    /// # Returns
+    /// - `Ok(ParamsFingerprint)`: Returns the fingerprint new sessions are created with.
+    /// - `Err(CoreError)`: If the retrieval fails.
+    async fn get_params(&self) -> Result<ParamsFingerprint, CoreError>;
+
     /// Allows a client to join an existing session.
     ///
     /// # Parameters
@@ -22,15 +33,51 @@ pub trait SessionClientPort {
         client_entity: ClientEntity,
     ) -> Result<(), CoreError>;
 
-    /// Retrieves the current state of a session.
+    /// Retrieves a summary of the current state of a session, without its key material.
     ///
     /// # Parameters
     /// - `session_id`: The unique identifier of the session to retrieve.
     ///
     /// # Returns
-    /// - `Ok(SessionEntity)`: Returns the session entity containing its current state and metadata.
+    /// - `Ok(SessionSummary)`: Returns the status, participants and progress of the session.
     /// - `Err(CoreError)`: If the session does not exist or retrieval fails.
-    async fn get_session(&self, session_id: SessionId) -> Result<SessionEntity, CoreError>;
+    async fn get_session(&self, session_id: SessionId) -> Result<SessionSummary, CoreError>;
+
+    /// Downloads the aggregated public key of a session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: Returns the serialized public key.
+    /// - `Err(CoreError)`: If not every participant joined yet or the download fails.
+    async fn get_pk(&self, session_id: SessionId) -> Result<Vec<u8>, CoreError>;
+
+    /// Downloads the encrypted result of a session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Vec<u8>>)`: Returns the serialized ciphertexts of the result.
+    /// - `Err(CoreError)`: If the result was not computed yet or the download fails.
+    async fn get_encrypted_result(&self, session_id: SessionId) -> Result<Vec<Vec<u8>>, CoreError>;
+
+    /// Waits until a session reaches a status, without polling the whole session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session to follow.
+    /// - `status`: The status to wait for.
+    ///
+    /// # Returns
+    /// - `Ok(SessionEvent)`: Returns the event of the session reaching `status`, or ending in
+    ///   another status, which means `status` can no longer be reached.
+    /// - `Err(CoreError)`: If the session does not exist or the events cannot be received.
+    async fn wait_for_status(
+        &self,
+        session_id: SessionId,
+        status: SessionStatus,
+    ) -> Result<SessionEvent, CoreError>;
 
     /// Sends the client's bootstrap key to the server within a session.
     ///
@@ -65,4 +112,102 @@ pub trait SessionClientPort {
         client_id: ClientId,
         data: Vec<u8>,
     ) -> Result<(), CoreError>;
+
+    /// Starts an additional computation of a session, reusing the keys of the session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session, whose first computation is done.
+    ///
+    /// # Returns
+    /// - `Ok(ComputationId)`: Returns the identifier of the new computation.
+    /// - `Err(CoreError)`: If the session is not done yet or the creation fails.
+    async fn create_computation(&self, session_id: SessionId) -> Result<ComputationId, CoreError>;
+
+    /// Retrieves a summary of an additional computation of a session, without its ciphertexts.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    /// - `computation_id`: The identifier of the computation within the session.
+    ///
+    /// # Returns
+    /// - `Ok(ComputationSummary)`: Returns the status and progress of the computation.
+    /// - `Err(CoreError)`: If the computation does not exist or retrieval fails.
+    async fn get_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<ComputationSummary, CoreError>;
+
+    /// Sends the encrypted input of a client for an additional computation of a session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    /// - `computation_id`: The identifier of the computation within the session.
+    /// - `client_id`: The unique identifier of the client sending the input.
+    /// - `data`: A vector of bytes containing the encrypted input.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the input is successfully sent and stored on the server.
+    /// - `Err(CoreError)`: If sending or storing the input fails.
+    async fn send_computation_data(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        data: Vec<u8>,
+    ) -> Result<(), CoreError>;
+
+    /// Downloads the encrypted result of an additional computation of a session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    /// - `computation_id`: The identifier of the computation within the session.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Vec<u8>>)`: Returns the serialized ciphertexts of the result.
+    /// - `Err(CoreError)`: If the result was not computed yet or the download fails.
+    async fn get_computation_result(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<Vec<Vec<u8>>, CoreError>;
+
+    /// Publishes the decryption share of a client for the result of an additional computation.
+    ///
+    /// The decryption shares of additional computations are always relayed by the server.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    /// - `computation_id`: The identifier of the computation within the session.
+    /// - `client_id`: The unique identifier of the client providing the share.
+    /// - `dec_share`: The serialized decryption share of the client.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the decryption share is stored on the server.
+    /// - `Err(CoreError)`: If the computation is not done yet or the upload fails.
+    async fn send_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        dec_share: Vec<u8>,
+    ) -> Result<(), CoreError>;
+
+    /// Downloads the decryption share of a client for the result of an additional computation.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session.
+    /// - `computation_id`: The identifier of the computation within the session.
+    /// - `client_id`: The unique identifier of the client whose share is requested.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)`: Returns the serialized decryption share, empty if the client has not
+    ///   published it yet.
+    /// - `Err(CoreError)`: If the computation does not exist or the download fails.
+    async fn get_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+    ) -> Result<Vec<u8>, CoreError>;
 }
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::entities::session::SessionId;
+use crate::entities::session_event::SessionEvent;
+use crate::errors::CoreError;
+
+/// A stream of events of a single session.
+///
+/// The stream ends when the subscription is lost, e.g. because the subscriber fell behind or
+/// because events were missed while the storage was unreachable, in which case the subscriber
+/// should read the session again and subscribe anew.
+pub type SessionEventStream = BoxStream<'static, SessionEvent>;
+
+/// Defines an asynchronous interface for following the status changes of sessions.
+///
+/// Implementations must observe the changes made by every process sharing the session storage,
+/// such as other server replicas and workers.
+#[async_trait]
+pub trait SessionEventPort {
+    /// Subscribes to the events of a session.
+    ///
+    /// Only the changes made after the subscription are delivered.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session to follow.
+    ///
+    /// # Returns
+    /// - `Ok(SessionEventStream)`: Returns the stream of the events of the session.
+    /// - `Err(CoreError)`: Returns an error if the subscription fails.
+    async fn subscribe(&self, session_id: SessionId) -> Result<SessionEventStream, CoreError>;
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 
+use crate::entities::computation::ComputationId;
 use crate::entities::session::SessionId;
 use crate::errors::CoreError;
 
@@ -34,4 +35,22 @@ pub trait WorkerPort {
     /// - `Ok(())`: If the command to compute the function is successfully sent.
     /// - `Err(CoreError)`: If sending the command fails or the session is invalid.
     async fn compute_function(&self, session_id: SessionId) -> Result<(), CoreError>;
+
+    /// Sends a command to workers to compute an additional computation of the specified session.
+    ///
+    /// The function of the session is computed over the inputs submitted for this computation,
+    /// reusing the keys aggregated for the session.
+    ///
+    /// # Parameters
+    /// - `session_id`: The unique identifier of the session the computation belongs to.
+    /// - `computation_id`: The identifier of the computation within the session.
+    ///
+    /// # Returns
+    /// - `Ok(())`: If the command to compute the computation is successfully sent.
+    /// - `Err(CoreError)`: If sending the command fails or the session is invalid.
+    async fn compute_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<(), CoreError>;
 }
@@ -0,0 +1,872 @@
+use std::fmt;
+use std::str::FromStr;
+
+use phantom_zone_evaluator::boolean::{BoolEvaluator, FheBool};
+use serde::{Deserialize, Serialize};
+
+/// Name of the function computed when a session does not choose one.
+pub const DEFAULT_FUNCTION: &str = "sum";
+
+/// Type of a value taken or returned by a function.
+///
+/// Values are encrypted bit by bit, least significant bit first, and signed integers use two's
+/// complement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+}
+
+/// Types of the integers.
+const INTEGERS: &[ValueType] = &[
+    ValueType::U8,
+    ValueType::U16,
+    ValueType::U32,
+    ValueType::U64,
+    ValueType::I8,
+    ValueType::I16,
+    ValueType::I32,
+    ValueType::I64,
+];
+
+/// Types of every value.
+const ALL: &[ValueType] = &[
+    ValueType::Bool,
+    ValueType::U8,
+    ValueType::U16,
+    ValueType::U32,
+    ValueType::U64,
+    ValueType::I8,
+    ValueType::I16,
+    ValueType::I32,
+    ValueType::I64,
+];
+
+impl ValueType {
+    /// Returns the number of encrypted bits of a value of this type.
+    pub fn bits(&self) -> usize {
+        match self {
+            ValueType::Bool => 1,
+            ValueType::U8 | ValueType::I8 => 8,
+            ValueType::U16 | ValueType::I16 => 16,
+            ValueType::U32 | ValueType::I32 => 32,
+            ValueType::U64 | ValueType::I64 => 64,
+        }
+    }
+
+    /// Whether values of this type are signed integers.
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            ValueType::I8 | ValueType::I16 | ValueType::I32 | ValueType::I64
+        )
+    }
+
+    /// Returns the smallest value of this type.
+    pub fn min_value(&self) -> i128 {
+        if self.is_signed() {
+            -(1 << (self.bits() - 1))
+        } else {
+            0
+        }
+    }
+
+    /// Returns the largest value of this type.
+    pub fn max_value(&self) -> i128 {
+        if self.is_signed() {
+            (1 << (self.bits() - 1)) - 1
+        } else {
+            (1 << self.bits()) - 1
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueType::Bool => "bool",
+            ValueType::U8 => "u8",
+            ValueType::U16 => "u16",
+            ValueType::U32 => "u32",
+            ValueType::U64 => "u64",
+            ValueType::I8 => "i8",
+            ValueType::I16 => "i16",
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ValueType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL.iter()
+            .find(|value_type| value_type.to_string() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown value type: {}", s))
+    }
+}
+
+/// Type of an output of a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputType {
+    /// The output has the type of the inputs.
+    SameAsInput,
+    /// The output always has this type.
+    Fixed(ValueType),
+}
+
+/// Circuits the outputs of the functions are evaluated with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Circuit {
+    /// Wrapping sum of the integers.
+    Sum,
+    /// Largest of the integers.
+    Max,
+    /// Position of the largest of the integers, the first one on ties.
+    Argmax,
+    /// Wrapping product of the integers.
+    Mul,
+    /// Whether all the values are equal.
+    Eq,
+    /// Whether the first integer is lower than the second one.
+    Lt,
+    /// Whether all the booleans are true.
+    And,
+    /// Whether any of the booleans is true.
+    Or,
+}
+
+/// A named output of a function.
+#[derive(Debug, Clone, Copy)]
+pub struct FunctionOutput {
+    /// Name of the output.
+    pub name: &'static str,
+    /// Type of the output.
+    pub output: OutputType,
+    /// Circuit evaluating the output.
+    pub circuit: Circuit,
+}
+
+/// Layout of an output in the result of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputDescriptor {
+    /// Name of the output.
+    pub name: String,
+    /// Type of each value of the output.
+    pub value_type: ValueType,
+    /// Number of values of the output.
+    pub len: usize,
+}
+
+/// A named function sessions can compute over the inputs of their clients.
+///
+/// Each client provides an array of values, and every output is computed element-wise: its
+/// value at a position only depends on the values of the clients at that position.
+#[derive(Debug, Clone, Copy)]
+pub struct FheFunction {
+    /// Name of the function, as chosen when creating a session.
+    pub name: &'static str,
+    /// Types the input of each client may have, all inputs sharing the same type.
+    pub input_types: &'static [ValueType],
+    /// Minimum number of inputs, hence of clients.
+    pub min_inputs: usize,
+    /// Maximum number of inputs, hence of clients.
+    pub max_inputs: usize,
+    /// Outputs of the function, in the order they appear in the result.
+    pub outputs: &'static [FunctionOutput],
+}
+
+/// Registry of the functions sessions can compute.
+pub const FUNCTIONS: &[FheFunction] = &[
+    FheFunction {
+        name: "sum",
+        input_types: INTEGERS,
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "sum",
+            output: OutputType::SameAsInput,
+            circuit: Circuit::Sum,
+        }],
+    },
+    FheFunction {
+        name: "max",
+        input_types: INTEGERS,
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "max",
+            output: OutputType::SameAsInput,
+            circuit: Circuit::Max,
+        }],
+    },
+    FheFunction {
+        name: "argmax",
+        input_types: INTEGERS,
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "argmax",
+            output: OutputType::Fixed(ValueType::U64),
+            circuit: Circuit::Argmax,
+        }],
+    },
+    FheFunction {
+        name: "mul",
+        input_types: INTEGERS,
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "mul",
+            output: OutputType::SameAsInput,
+            circuit: Circuit::Mul,
+        }],
+    },
+    FheFunction {
+        name: "eq",
+        input_types: ALL,
+        min_inputs: 2,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "eq",
+            output: OutputType::Fixed(ValueType::Bool),
+            circuit: Circuit::Eq,
+        }],
+    },
+    FheFunction {
+        name: "lt",
+        input_types: INTEGERS,
+        min_inputs: 2,
+        max_inputs: 2,
+        outputs: &[FunctionOutput {
+            name: "lt",
+            output: OutputType::Fixed(ValueType::Bool),
+            circuit: Circuit::Lt,
+        }],
+    },
+    FheFunction {
+        name: "and",
+        input_types: &[ValueType::Bool],
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "and",
+            output: OutputType::Fixed(ValueType::Bool),
+            circuit: Circuit::And,
+        }],
+    },
+    FheFunction {
+        name: "or",
+        input_types: &[ValueType::Bool],
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[FunctionOutput {
+            name: "or",
+            output: OutputType::Fixed(ValueType::Bool),
+            circuit: Circuit::Or,
+        }],
+    },
+    // Sealed-bid auction of each item, every client bidding on all of them.
+    FheFunction {
+        name: "auction",
+        input_types: INTEGERS,
+        min_inputs: 1,
+        max_inputs: usize::MAX,
+        outputs: &[
+            FunctionOutput {
+                name: "price",
+                output: OutputType::SameAsInput,
+                circuit: Circuit::Max,
+            },
+            FunctionOutput {
+                name: "winner",
+                output: OutputType::Fixed(ValueType::U64),
+                circuit: Circuit::Argmax,
+            },
+        ],
+    },
+];
+
+impl FunctionOutput {
+    /// Returns the type of the output for inputs of the given type.
+    pub fn value_type(&self, input_type: ValueType) -> ValueType {
+        match self.output {
+            OutputType::SameAsInput => input_type,
+            OutputType::Fixed(output_type) => output_type,
+        }
+    }
+}
+
+impl FheFunction {
+    /// Looks up a function of the registry by name.
+    ///
+    /// # Returns
+    /// - `None` if no function has this name.
+    pub fn by_name(name: &str) -> Option<&'static FheFunction> {
+        FUNCTIONS.iter().find(|function| function.name == name)
+    }
+
+    /// Returns the names of the functions of the registry, comma-separated.
+    pub fn known_names() -> String {
+        FUNCTIONS
+            .iter()
+            .map(|function| function.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether the function can be computed over this number of inputs.
+    pub fn accepts_inputs(&self, count: usize) -> bool {
+        (self.min_inputs..=self.max_inputs).contains(&count)
+    }
+
+    /// Whether the function can be computed over inputs of this type.
+    pub fn accepts_input_type(&self, input_type: ValueType) -> bool {
+        self.input_types.contains(&input_type)
+    }
+
+    /// Describes the outputs of the function for inputs of `input_len` values of `input_type`.
+    pub fn output_descriptors(
+        &self,
+        input_type: ValueType,
+        input_len: usize,
+    ) -> Vec<OutputDescriptor> {
+        self.outputs
+            .iter()
+            .map(|output| OutputDescriptor {
+                name: output.name.to_string(),
+                value_type: output.value_type(input_type),
+                len: input_len,
+            })
+            .collect()
+    }
+
+    /// Evaluates the function on the encrypted bits of its inputs.
+    ///
+    /// # Arguments
+    /// - `input_type`: The type of every value of the inputs.
+    /// - `input_len`: The number of values of every input.
+    /// - `inputs`: The bits of each input, ordered by client. The values of an input follow each
+    ///   other, each with its least significant bit first.
+    ///
+    /// # Returns
+    /// - The bits of the outputs, laid out as described by `output_descriptors`.
+    /// - `None` if the type or the number of inputs is not accepted, or the number of bits of an
+    ///   input does not match the input type and length.
+    pub fn evaluate<E: BoolEvaluator>(
+        &self,
+        input_type: ValueType,
+        input_len: usize,
+        inputs: Vec<Vec<FheBool<E>>>,
+    ) -> Option<Vec<FheBool<E>>> {
+        let width = input_type.bits();
+        if !self.accepts_input_type(input_type)
+            || !self.accepts_inputs(inputs.len())
+            || input_len == 0
+            || inputs.iter().any(|bits| bits.len() != input_len * width)
+        {
+            return None;
+        }
+        let signed = input_type.is_signed();
+
+        // The values of every client at each position.
+        let positions = (0..input_len)
+            .map(|position| {
+                inputs
+                    .iter()
+                    .map(|bits| &bits[position * width..(position + 1) * width])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::new();
+        for output in self.outputs {
+            for values in &positions {
+                result.extend(evaluate_circuit(output.circuit, values, signed));
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Evaluates a circuit on the values of every client at a position.
+fn evaluate_circuit<E: BoolEvaluator>(
+    circuit: Circuit,
+    values: &[&[FheBool<E>]],
+    signed: bool,
+) -> Vec<FheBool<E>> {
+    let (first, rest) = values
+        .split_first()
+        .expect("Functions take at least one input");
+    match circuit {
+        Circuit::Sum => rest
+            .iter()
+            .fold(first.to_vec(), |acc, value| add(&acc, value)),
+        Circuit::Mul => rest
+            .iter()
+            .fold(first.to_vec(), |acc, value| mul(&acc, value)),
+        Circuit::Eq => vec![rest
+            .iter()
+            .map(|value| eq(first, value))
+            .reduce(|acc, bit| &acc & &bit)
+            .expect("Eq takes at least two inputs")],
+        Circuit::Lt => vec![lt(first, rest[0], signed)],
+        Circuit::Max => rest.iter().fold(first.to_vec(), |max, value| {
+            select(&lt(&max, value, signed), value, &max)
+        }),
+        Circuit::Argmax => argmax(values, signed),
+        Circuit::And => vec![rest
+            .iter()
+            .fold(first[0].clone(), |acc, value| &acc & &value[0])],
+        Circuit::Or => vec![rest
+            .iter()
+            .fold(first[0].clone(), |acc, value| &acc | &value[0])],
+    }
+}
+
+/// Adds two integers given by their bits with a ripple-carry adder, wrapping on overflow.
+fn add<E: BoolEvaluator>(a: &[FheBool<E>], b: &[FheBool<E>]) -> Vec<FheBool<E>> {
+    let mut sum = Vec::with_capacity(a.len());
+    let mut carry: Option<FheBool<E>> = None;
+    for (a, b) in a.iter().zip(b) {
+        let half_sum = a ^ b;
+        let half_carry = a & b;
+        carry = Some(match carry {
+            None => {
+                sum.push(half_sum);
+                half_carry
+            }
+            Some(carry) => {
+                sum.push(&half_sum ^ &carry);
+                &half_carry | &(&carry & &half_sum)
+            }
+        });
+    }
+    sum
+}
+
+/// Multiplies two integers given by their bits by shifting and adding, wrapping on overflow.
+///
+/// Two's complement makes the wrapped product the same for signed and unsigned integers.
+fn mul<E: BoolEvaluator>(a: &[FheBool<E>], b: &[FheBool<E>]) -> Vec<FheBool<E>> {
+    let mut product = a.iter().map(|a| a & &b[0]).collect::<Vec<_>>();
+    for shift in 1..b.len() {
+        let partial = a[..a.len() - shift]
+            .iter()
+            .map(|a| a & &b[shift])
+            .collect::<Vec<_>>();
+        let high = add(&product[shift..], &partial);
+        product.truncate(shift);
+        product.extend(high);
+    }
+    product
+}
+
+/// Picks `a` where `condition` holds and `b` otherwise, bit by bit: b ^ (c & (a ^ b)).
+fn select<E: BoolEvaluator>(
+    condition: &FheBool<E>,
+    a: &[FheBool<E>],
+    b: &[FheBool<E>],
+) -> Vec<FheBool<E>> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| b ^ &(condition & &(a ^ b)))
+        .collect()
+}
+
+/// Computes the position of the largest integer given by their bits, as 64 bits.
+///
+/// The position of each input is known in clear, so each bit of the tracked position is set or
+/// cleared under the encrypted condition that the input is larger than the maximum so far.
+fn argmax<E: BoolEvaluator>(values: &[&[FheBool<E>]], signed: bool) -> Vec<FheBool<E>> {
+    // Only the bits needed to write the last position are ever set.
+    let width = (usize::BITS - (values.len() - 1).leading_zeros()) as usize;
+    // A single bootstrap builds the encrypted zero every bit of the index starts from.
+    let zero = &values[0][0] ^ &values[0][0];
+    let mut index = vec![zero; 64];
+
+    let mut max = values[0].to_vec();
+    for (position, value) in values.iter().enumerate().skip(1) {
+        let is_max = lt(&max, value, signed);
+        max = select(&is_max, value, &max);
+        for (bit, index_bit) in index.iter_mut().enumerate().take(width) {
+            *index_bit = if (position >> bit) & 1 == 1 {
+                &*index_bit | &is_max
+            } else {
+                &*index_bit & &!&is_max
+            };
+        }
+    }
+    index
+}
+
+/// Whether two values given by their bits are equal.
+fn eq<E: BoolEvaluator>(a: &[FheBool<E>], b: &[FheBool<E>]) -> FheBool<E> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| !&(a ^ b))
+        .reduce(|acc, bit| &acc & &bit)
+        .expect("Values have at least one bit")
+}
+
+/// Whether the first integer given by its bits is lower than the second one.
+///
+/// Walks from the least significant bit, so that each more significant bit that differs
+/// overrides the comparison of the lower bits. The sign bit of a signed integer is set when it is
+/// negative, so it compares the other way around.
+fn lt<E: BoolEvaluator>(a: &[FheBool<E>], b: &[FheBool<E>], signed: bool) -> FheBool<E> {
+    let sign = a.len() - 1;
+    a.iter()
+        .zip(b)
+        .enumerate()
+        .map(|(bit, (a, b))| {
+            let bit_lt = if signed && bit == sign {
+                a & &!b
+            } else {
+                &!a & b
+            };
+            (bit_lt, !&(a ^ b))
+        })
+        .reduce(|(lt, _), (bit_lt, bit_eq)| (&bit_lt | &(&bit_eq & &lt), bit_eq))
+        .map(|(lt, _)| lt)
+        .expect("Integers have at least one bit")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use phantom_zone_evaluator::boolean::dev::MockBoolEvaluator;
+
+    use super::*;
+    use crate::utils::{decode_outputs, decode_value, encode_value};
+
+    type Bits<'a> = Vec<FheBool<&'a MockBoolEvaluator>>;
+
+    /// Encodes a value as plaintext bits of the mock evaluator.
+    fn encrypt(evaluator: &MockBoolEvaluator, value: i128, value_type: ValueType) -> Bits<'_> {
+        encode_value(value, value_type)
+            .expect("Value fits its type")
+            .into_iter()
+            .map(|bit| FheBool::new(evaluator, bit))
+            .collect()
+    }
+
+    fn decrypt(bits: &[FheBool<&MockBoolEvaluator>]) -> Vec<bool> {
+        bits.iter().map(|bit| *bit.ct()).collect()
+    }
+
+    /// Evaluates a function of the registry on plaintext inputs, one slice of values per client.
+    fn evaluate(
+        name: &str,
+        input_type: ValueType,
+        inputs: &[&[i128]],
+    ) -> BTreeMap<String, Vec<i128>> {
+        let function = FheFunction::by_name(name).unwrap();
+        let evaluator = MockBoolEvaluator::new();
+        let input_len = inputs[0].len();
+        let inputs = inputs
+            .iter()
+            .map(|values| {
+                values
+                    .iter()
+                    .flat_map(|&value| encrypt(&evaluator, value, input_type))
+                    .collect()
+            })
+            .collect();
+        let bits = function
+            .evaluate(input_type, input_len, inputs)
+            .expect("Inputs are accepted");
+        decode_outputs(
+            decrypt(&bits),
+            &function.output_descriptors(input_type, input_len),
+        )
+        .expect("Bits match the output layout")
+    }
+
+    /// Evaluates a single-output function and returns its values.
+    fn evaluate_one(name: &str, input_type: ValueType, inputs: &[&[i128]]) -> Vec<i128> {
+        evaluate(name, input_type, inputs)
+            .remove(name)
+            .expect("The output is named after the function")
+    }
+
+    #[test]
+    fn registry_has_unique_names_and_looks_them_up() {
+        for function in FUNCTIONS {
+            assert_eq!(
+                FUNCTIONS.iter().filter(|f| f.name == function.name).count(),
+                1,
+                "function {} is registered once",
+                function.name
+            );
+            assert_eq!(
+                FheFunction::by_name(function.name).unwrap().name,
+                function.name
+            );
+            assert!(FheFunction::known_names().contains(function.name));
+            assert!(!function.outputs.is_empty());
+            assert!(function.min_inputs >= 1 && function.min_inputs <= function.max_inputs);
+        }
+        assert!(FheFunction::by_name("div").is_none());
+        assert!(FheFunction::by_name(DEFAULT_FUNCTION).is_some());
+    }
+
+    #[test]
+    fn registry_checks_input_counts_and_types() {
+        let less_than = FheFunction::by_name("lt").unwrap();
+        assert!(!less_than.accepts_inputs(1));
+        assert!(less_than.accepts_inputs(2));
+        assert!(!less_than.accepts_inputs(3));
+        assert!(!less_than.accepts_input_type(ValueType::Bool));
+        assert!(less_than.accepts_input_type(ValueType::I16));
+
+        let eq = FheFunction::by_name("eq").unwrap();
+        assert!(!eq.accepts_inputs(1));
+        assert!(eq.accepts_inputs(5));
+        assert!(eq.accepts_input_type(ValueType::Bool));
+
+        let and = FheFunction::by_name("and").unwrap();
+        assert!(and.accepts_input_type(ValueType::Bool));
+        assert!(!and.accepts_input_type(ValueType::U8));
+    }
+
+    #[test]
+    fn registry_describes_the_outputs() {
+        let auction = FheFunction::by_name("auction").unwrap();
+        assert_eq!(
+            auction.output_descriptors(ValueType::I32, 3),
+            vec![
+                OutputDescriptor {
+                    name: "price".to_string(),
+                    value_type: ValueType::I32,
+                    len: 3,
+                },
+                OutputDescriptor {
+                    name: "winner".to_string(),
+                    value_type: ValueType::U64,
+                    len: 3,
+                },
+            ]
+        );
+        let eq = FheFunction::by_name("eq").unwrap();
+        assert_eq!(
+            eq.output_descriptors(ValueType::U16, 1)[0].value_type,
+            ValueType::Bool
+        );
+    }
+
+    #[test]
+    fn evaluate_rejects_inputs_not_matching_the_type_or_length() {
+        let evaluator = MockBoolEvaluator::new();
+        let sum = FheFunction::by_name("sum").unwrap();
+        let input = || encrypt(&evaluator, 1, ValueType::U8);
+
+        assert!(sum
+            .evaluate(ValueType::Bool, 8, vec![input(), input()])
+            .is_none());
+        assert!(sum
+            .evaluate(ValueType::U8, 2, vec![input(), input()])
+            .is_none());
+        assert!(sum.evaluate(ValueType::U8, 0, Vec::<Bits>::new()).is_none());
+        assert!(FheFunction::by_name("lt")
+            .unwrap()
+            .evaluate(ValueType::U8, 1, vec![input(), input(), input()])
+            .is_none());
+        assert!(sum
+            .evaluate(ValueType::U8, 1, vec![input(), input()])
+            .is_some());
+    }
+
+    #[test]
+    fn add_wraps_for_every_width() {
+        let evaluator = MockBoolEvaluator::new();
+        for (a, b, value_type, expected) in [
+            (200, 100, ValueType::U8, 44),
+            (3, 4, ValueType::U16, 7),
+            (u32::MAX as i128, 2, ValueType::U32, 1),
+            (u64::MAX as i128, 1, ValueType::U64, 0),
+            (-5, 3, ValueType::I8, -2),
+            (127, 1, ValueType::I8, -128),
+            (-32768, -1, ValueType::I16, 32767),
+            (-7, -8, ValueType::I64, -15),
+        ] {
+            let sum = add(
+                &encrypt(&evaluator, a, value_type),
+                &encrypt(&evaluator, b, value_type),
+            );
+            assert_eq!(
+                decode_value(decrypt(&sum), value_type),
+                expected,
+                "{} + {} as {}",
+                a,
+                b,
+                value_type
+            );
+        }
+    }
+
+    #[test]
+    fn mul_wraps_for_signed_and_unsigned_integers() {
+        let evaluator = MockBoolEvaluator::new();
+        for (a, b, value_type, expected) in [
+            (6, 7, ValueType::U8, 42),
+            (16, 16, ValueType::U8, 0),
+            (255, 255, ValueType::U8, 1),
+            (0, 123, ValueType::U16, 0),
+            (-3, 5, ValueType::I8, -15),
+            (-4, -4, ValueType::I8, 16),
+            (64, 2, ValueType::I8, -128),
+            (-1, -1, ValueType::I32, 1),
+        ] {
+            let product = mul(
+                &encrypt(&evaluator, a, value_type),
+                &encrypt(&evaluator, b, value_type),
+            );
+            assert_eq!(
+                decode_value(decrypt(&product), value_type),
+                expected,
+                "{} * {} as {}",
+                a,
+                b,
+                value_type
+            );
+        }
+    }
+
+    #[test]
+    fn lt_compares_unsigned_integers() {
+        let evaluator = MockBoolEvaluator::new();
+        let values = [0, 1, 2, 127, 128, 200, 255];
+        for a in values {
+            for b in values {
+                let is_lt = lt(
+                    &encrypt(&evaluator, a, ValueType::U8),
+                    &encrypt(&evaluator, b, ValueType::U8),
+                    false,
+                );
+                assert_eq!(*is_lt.ct(), a < b, "{} < {} as u8", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn lt_compares_signed_integers_by_their_sign_bit() {
+        let evaluator = MockBoolEvaluator::new();
+        let values = [-128, -127, -2, -1, 0, 1, 2, 126, 127];
+        for a in values {
+            for b in values {
+                let is_lt = lt(
+                    &encrypt(&evaluator, a, ValueType::I8),
+                    &encrypt(&evaluator, b, ValueType::I8),
+                    true,
+                );
+                assert_eq!(*is_lt.ct(), a < b, "{} < {} as i8", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn select_picks_bit_by_bit() {
+        let evaluator = MockBoolEvaluator::new();
+        let a = encrypt(&evaluator, 0b1010_1100, ValueType::U8);
+        let b = encrypt(&evaluator, 0b0110_0101, ValueType::U8);
+        for (condition, expected) in [(true, 0b1010_1100), (false, 0b0110_0101)] {
+            let selected = select(&FheBool::new(&evaluator, condition), &a, &b);
+            assert_eq!(decode_value(decrypt(&selected), ValueType::U8), expected);
+        }
+    }
+
+    #[test]
+    fn evaluates_arithmetic_element_wise() {
+        assert_eq!(
+            evaluate_one("sum", ValueType::U8, &[&[1, 250], &[2, 10], &[3, 0]]),
+            vec![6, 4]
+        );
+        assert_eq!(
+            evaluate_one("sum", ValueType::I16, &[&[-5, 100], &[3, -200]]),
+            vec![-2, -100]
+        );
+        assert_eq!(
+            evaluate_one("mul", ValueType::I8, &[&[-2, 3], &[3, 3], &[5, 3]]),
+            vec![-30, 27]
+        );
+        assert_eq!(
+            evaluate_one("max", ValueType::I8, &[&[-5, 0], &[-7, -1], &[-6, 1]]),
+            vec![-5, 1]
+        );
+        assert_eq!(
+            evaluate_one("max", ValueType::U8, &[&[5, 255], &[200, 0]]),
+            vec![200, 255]
+        );
+    }
+
+    #[test]
+    fn evaluates_comparisons_and_booleans() {
+        assert_eq!(
+            evaluate_one("eq", ValueType::U8, &[&[4, 4], &[4, 5], &[4, 4]]),
+            vec![1, 0]
+        );
+        assert_eq!(
+            evaluate_one("eq", ValueType::Bool, &[&[1, 0], &[1, 0]]),
+            vec![1, 1]
+        );
+        assert_eq!(
+            evaluate_one("lt", ValueType::I32, &[&[-1, 1, 3], &[1, -1, 3]]),
+            vec![1, 0, 0]
+        );
+        assert_eq!(
+            evaluate_one("and", ValueType::Bool, &[&[1, 1, 0], &[1, 0, 0]]),
+            vec![1, 0, 0]
+        );
+        assert_eq!(
+            evaluate_one("or", ValueType::Bool, &[&[1, 1, 0], &[1, 0, 0]]),
+            vec![1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn argmax_returns_the_first_position_on_ties() {
+        assert_eq!(
+            evaluate_one("argmax", ValueType::U8, &[&[3], &[9], &[9], &[1]]),
+            vec![1]
+        );
+        assert_eq!(
+            evaluate_one("argmax", ValueType::U8, &[&[7], &[7], &[7]]),
+            vec![0]
+        );
+        assert_eq!(
+            evaluate_one("argmax", ValueType::I8, &[&[-3], &[-1], &[-2], &[-1]]),
+            vec![1]
+        );
+        assert_eq!(evaluate_one("argmax", ValueType::U16, &[&[42]]), vec![0]);
+
+        // Positions beyond the first few bits are written in full.
+        let mut inputs = vec![[0i128]; 6];
+        inputs[5] = [1];
+        let inputs = inputs.iter().map(|input| &input[..]).collect::<Vec<_>>();
+        assert_eq!(evaluate_one("argmax", ValueType::U8, &inputs), vec![5]);
+    }
+
+    #[test]
+    fn auction_returns_the_price_and_winner_of_each_item() {
+        let result = evaluate(
+            "auction",
+            ValueType::U32,
+            &[&[10, 50], &[30, 50], &[20, 40]],
+        );
+        assert_eq!(result["price"], vec![30, 50]);
+        assert_eq!(result["winner"], vec![1, 0]);
+    }
+}
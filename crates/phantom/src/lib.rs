@@ -1,5 +1,6 @@
 pub mod client;
 pub mod crs;
+pub mod function;
 pub mod native_ops;
 pub mod ops;
 pub mod param;
@@ -1,3 +1,5 @@
+use std::error::Error;
+use std::fmt;
 use std::ops::Deref;
 
 use phantom_zone_evaluator::boolean::fhew::prelude::{
@@ -5,6 +7,8 @@ use phantom_zone_evaluator::boolean::fhew::prelude::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::utils::{I_2P_60, I_3P_60, I_4P_60};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Param {
     pub param: FhewBoolMpiParam,
@@ -19,3 +23,251 @@ impl Deref for Param {
         &self.param
     }
 }
+
+/// Name of the preset used when none is configured.
+pub const DEFAULT_PRESET: &str = "i_2p_60";
+
+/// A named set of Phantom parameters, shared by the server, the workers and the clients.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamPreset {
+    /// Name of the preset, as written in the configuration.
+    pub name: &'static str,
+    /// Security level the parameters were vetted for, in bits.
+    ///
+    /// `None` for the presets of more than 2 parties: they reuse the parameters of `i_2p_60`
+    /// with more key shares, and their noise growth and security were not analysed for it.
+    pub security_bits: Option<u32>,
+    /// Number of parties the key shares are aggregated for.
+    pub total_shares: usize,
+    /// The parameters themselves.
+    pub param: Param,
+}
+
+/// Catalogue of the supported parameter presets, one per number of parties.
+pub const PRESETS: &[ParamPreset] = &[
+    preset("i_2p_60", Some(128), I_2P_60),
+    preset("i_3p_60", None, I_3P_60),
+    preset("i_4p_60", None, I_4P_60),
+];
+
+/// Builds a preset around MPI parameters, with the ring-packing parameters every preset shares.
+const fn preset(
+    name: &'static str,
+    security_bits: Option<u32>,
+    param: FhewBoolMpiParam,
+) -> ParamPreset {
+    ParamPreset {
+        name,
+        security_bits,
+        total_shares: param.total_shares,
+        param: Param {
+            param,
+            ring_packing_modulus: Some(Modulus::Prime(2305843009213554689)),
+            ring_packing_auto_decomposition_param: DecompositionParam {
+                log_base: 20,
+                level: 1,
+            },
+        },
+    }
+}
+
+/// Error of a configuration naming a preset missing from the catalogue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPreset {
+    /// The configured name.
+    pub name: String,
+}
+
+impl fmt::Display for UnknownPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let known = PRESETS
+            .iter()
+            .map(|preset| preset.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "Unknown Phantom parameter preset '{}', expected one of: {}",
+            self.name, known
+        )
+    }
+}
+
+impl Error for UnknownPreset {}
+
+impl ParamPreset {
+    /// Looks up a preset of the catalogue by name.
+    ///
+    /// # Returns
+    /// - `None` if no preset has this name.
+    pub fn by_name(name: &str) -> Option<&'static ParamPreset> {
+        PRESETS.iter().find(|preset| preset.name == name)
+    }
+
+    /// Looks up a preset of the catalogue by name, for use at startup.
+    ///
+    /// # Returns
+    /// - `Err(UnknownPreset)` if no preset has this name, its message listing the known presets.
+    pub fn from_config(name: &str) -> Result<&'static ParamPreset, UnknownPreset> {
+        Self::by_name(name).ok_or_else(|| UnknownPreset {
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::crs::Crs;
+    use crate::native_ops::NativeOps;
+    use crate::ops::Ops;
+    use crate::server::PhantomServer;
+
+    #[test]
+    fn presets_are_named_after_their_party_count() {
+        assert!(ParamPreset::by_name(DEFAULT_PRESET).is_some());
+        for preset in PRESETS {
+            assert_eq!(preset.total_shares, preset.param.total_shares);
+            assert_eq!(preset.name, format!("i_{}p_60", preset.total_shares));
+            assert_eq!(
+                ParamPreset::from_config(preset.name).unwrap().name,
+                preset.name
+            );
+        }
+        assert!(PRESETS.iter().any(|preset| preset.total_shares > 2));
+    }
+
+    #[test]
+    fn unknown_presets_are_rejected_with_the_known_ones() {
+        let err = ParamPreset::from_config("i_5p_60").unwrap_err();
+        assert_eq!(err.name, "i_5p_60");
+        assert_eq!(
+            err.to_string(),
+            "Unknown Phantom parameter preset 'i_5p_60', expected one of: i_2p_60, i_3p_60, i_4p_60"
+        );
+    }
+
+    #[test]
+    fn every_party_of_each_preset_is_needed_to_decrypt() {
+        for preset in PRESETS {
+            let crs = Crs::new([7; 32]);
+            let clients = (0..preset.total_shares)
+                .map(|share_idx| {
+                    Client::<NativeOps>::new(
+                        preset.param,
+                        crs,
+                        share_idx,
+                        [share_idx as u8; 32],
+                        None,
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            // Aggregate the public key and ring-packing key from the share of every party.
+            let mut server =
+                PhantomServer::<NativeOps>::new(preset.param, crs, None, None, None).unwrap();
+            server.aggregate_pk_shares(
+                &clients
+                    .iter()
+                    .map(|client| client.pk_share_gen())
+                    .collect::<Vec<_>>(),
+            );
+            server.aggregate_rp_key_shares(
+                &clients
+                    .iter()
+                    .map(|client| client.rp_key_share_gen())
+                    .collect::<Vec<_>>(),
+            );
+            let pk = server.serialize_pk().unwrap();
+
+            // Encrypt with the aggregated key and pack the bits, as the result of a session.
+            let bits = (0..64).map(|bit| bit % 3 == 0).collect::<Vec<_>>();
+            let encrypter =
+                Client::<NativeOps>::new(preset.param, crs, 0, [0; 32], Some(&pk)).unwrap();
+            let cts = encrypter
+                .batched_pk_encrypt(bits.iter().copied())
+                .extract_all(server.ring());
+            let packed = server.pack(&cts);
+
+            let dec_shares = clients
+                .iter()
+                .map(|client| client.rp_decrypt_share(&packed))
+                .collect::<Vec<_>>();
+            let decrypted = server.aggregate_rp_decryption_shares(&packed, &dec_shares);
+            assert_eq!(
+                &decrypted[..bits.len()],
+                &bits[..],
+                "preset {}",
+                preset.name
+            );
+
+            // Missing the share of a single party, the bits stay hidden.
+            let partial = server.aggregate_rp_decryption_shares(&packed, &dec_shares[1..]);
+            assert_ne!(&partial[..bits.len()], &bits[..], "preset {}", preset.name);
+        }
+    }
+    #[test]
+    fn every_preset_bootstraps_gates_with_all_parties() {
+        for preset in PRESETS {
+            let crs = Crs::new([11; 32]);
+            let mut clients = (0..preset.total_shares)
+                .map(|share_idx| {
+                    Client::<NativeOps>::new(
+                        preset.param,
+                        crs,
+                        share_idx,
+                        [share_idx as u8 + 1; 32],
+                        None,
+                    )
+                    .unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            // Aggregate the public key, then the bootstrapping key generated under it.
+            let mut server =
+                PhantomServer::<NativeOps>::new(preset.param, crs, None, None, None).unwrap();
+            server.aggregate_pk_shares(
+                &clients
+                    .iter()
+                    .map(|client| client.pk_share_gen())
+                    .collect::<Vec<_>>(),
+            );
+            for client in &mut clients {
+                client.with_pk(server.pk().clone());
+            }
+            server.aggregate_bs_key_shares(
+                &clients
+                    .iter()
+                    .map(|client| client.bs_key_share_gen())
+                    .collect::<Vec<_>>(),
+            );
+
+            // Evaluate bootstrapped gates on every combination of inputs.
+            let (a, b) = ([false, false, true, true], [false, true, false, true]);
+            let cts_a = server.wrap_batched_ct(&clients[0].batched_pk_encrypt(a));
+            let cts_b = server.wrap_batched_ct(&clients[1].batched_pk_encrypt(b));
+            for (position, (ct_a, ct_b)) in cts_a.iter().zip(&cts_b).enumerate() {
+                for (gate, ct_out, expected) in [
+                    ("AND", ct_a & ct_b, a[position] & b[position]),
+                    ("XOR", ct_a ^ ct_b, a[position] ^ b[position]),
+                ] {
+                    let dec_shares = clients
+                        .iter()
+                        .map(|client| client.decrypt_share(ct_out.ct()))
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        server.aggregate_decryption_shares(ct_out.ct(), &dec_shares),
+                        expected,
+                        "{} {} {} with preset {}",
+                        a[position],
+                        gate,
+                        b[position],
+                        preset.name
+                    );
+                }
+            }
+        }
+    }
+}
@@ -88,6 +88,11 @@ impl<O: Ops> PhantomServer<O> {
         self.rp_key_prep.as_ref().unwrap()
     }
 
+    /// Whether the ring-packing key is aggregated, so that ciphertexts can be packed.
+    pub fn can_pack(&self) -> bool {
+        self.rp_key_prep.is_some()
+    }
+
     pub fn evaluator(&self) -> &FhewBoolEvaluator<O::EvaluationRing, O::KeySwitchMod> {
         self.evaluator.as_ref().unwrap()
     }
@@ -1,12 +1,11 @@
+use std::collections::BTreeMap;
+
 use phantom_zone_evaluator::boolean::fhew::prelude::{
-    DecompositionParam, FheU64, FhewBoolMpiParam, FhewBoolParam, Gaussian, Modulus,
-    NoiseDistribution, RgswDecompositionParam, SecretDistribution, Ternary,
+    DecompositionParam, FhewBoolMpiParam, FhewBoolParam, Gaussian, Modulus, NoiseDistribution,
+    RgswDecompositionParam, SecretDistribution, Ternary,
 };
-use phantom_zone_evaluator::boolean::BoolEvaluator;
 
-pub fn fhe_function<E: BoolEvaluator>(a: &FheU64<E>, b: &FheU64<E>) -> FheU64<E> {
-    a + b
-}
+use crate::function::{OutputDescriptor, ValueType};
 
 pub fn u64_to_binary<const N: usize>(v: u64) -> Vec<bool> {
     assert!((v as u128) < 2u128.pow(N as u32));
@@ -26,6 +25,71 @@ pub fn binary_to_u64(v: Vec<bool>) -> u64 {
         .fold(0u64, |acc, (i, &bit)| acc | ((bit as u64) << i))
 }
 
+/// Decodes a signed integer from its bits in two's complement, least significant bit first.
+pub fn binary_to_i64(v: Vec<bool>) -> i64 {
+    assert!(!v.is_empty() && v.len() <= 64);
+    let shift = 64 - v.len();
+    ((binary_to_u64(v) << shift) as i64) >> shift
+}
+
+/// Encodes a value as the bits of the given type, least significant bit first.
+///
+/// # Returns
+/// - `None` if the value does not fit the type.
+pub fn encode_value(value: i128, value_type: ValueType) -> Option<Vec<bool>> {
+    if value < value_type.min_value() || value > value_type.max_value() {
+        return None;
+    }
+    Some(
+        (0..value_type.bits())
+            .map(|i| (value >> i) & 1 == 1)
+            .collect(),
+    )
+}
+
+/// Decodes a value of the given type from its bits, least significant bit first.
+pub fn decode_value(v: Vec<bool>, value_type: ValueType) -> i128 {
+    assert_eq!(v.len(), value_type.bits());
+    if value_type.is_signed() {
+        binary_to_i64(v) as i128
+    } else {
+        binary_to_u64(v) as i128
+    }
+}
+
+/// Decodes the named outputs of a result from its bits, laid out as described by `outputs`.
+///
+/// # Returns
+/// - `None` if the number of bits does not match the outputs.
+pub fn decode_outputs(
+    v: Vec<bool>,
+    outputs: &[OutputDescriptor],
+) -> Option<BTreeMap<String, Vec<i128>>> {
+    let expected = outputs
+        .iter()
+        .map(|output| output.len * output.value_type.bits())
+        .sum::<usize>();
+    if v.len() != expected {
+        return None;
+    }
+
+    let mut bits = v.into_iter();
+    Some(
+        outputs
+            .iter()
+            .map(|output| {
+                let values = (0..output.len)
+                    .map(|_| {
+                        let value = bits.by_ref().take(output.value_type.bits()).collect();
+                        decode_value(value, output.value_type)
+                    })
+                    .collect();
+                (output.name.clone(), values)
+            })
+            .collect(),
+    )
+}
+
 pub const I_2P_60: FhewBoolMpiParam = FhewBoolMpiParam {
     param: FhewBoolParam {
         message_bits: 2,
@@ -63,6 +127,18 @@ pub const I_2P_60: FhewBoolMpiParam = FhewBoolMpiParam {
     total_shares: 2,
 };
 
+/// Parameters of [`I_2P_60`], with key shares aggregated for three parties.
+pub const I_3P_60: FhewBoolMpiParam = FhewBoolMpiParam {
+    total_shares: 3,
+    ..I_2P_60
+};
+
+/// Parameters of [`I_2P_60`], with key shares aggregated for four parties.
+pub const I_4P_60: FhewBoolMpiParam = FhewBoolMpiParam {
+    total_shares: 4,
+    ..I_2P_60
+};
+
 /// Pads a seed to 32 bytes.
 ///
 /// # Arguments
@@ -76,3 +152,195 @@ pub fn pad_seed_to_32_bytes(seed: &[u8]) -> [u8; 32] {
     padded[..len].copy_from_slice(&seed[..len]);
     padded
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Types of every value, with the integers of each width and sign.
+    const VALUE_TYPES: &[ValueType] = &[
+        ValueType::Bool,
+        ValueType::U8,
+        ValueType::U16,
+        ValueType::U32,
+        ValueType::U64,
+        ValueType::I8,
+        ValueType::I16,
+        ValueType::I32,
+        ValueType::I64,
+    ];
+
+    #[test]
+    fn binary_to_i64_sign_extends_every_width() {
+        assert_eq!(binary_to_i64(vec![true]), -1);
+        assert_eq!(binary_to_i64(vec![false]), 0);
+        assert_eq!(binary_to_i64(vec![true, false]), 1);
+        assert_eq!(binary_to_i64(vec![false, true]), -2);
+        assert_eq!(binary_to_i64(u64_to_binary::<8>(0x7f)), 127);
+        assert_eq!(binary_to_i64(u64_to_binary::<8>(0x80)), -128);
+        assert_eq!(binary_to_i64(u64_to_binary::<8>(0xff)), -1);
+        assert_eq!(binary_to_i64(u64_to_binary::<16>(0xfffe)), -2);
+        assert_eq!(
+            binary_to_i64(u64_to_binary::<32>(0x8000_0000)),
+            i32::MIN as i64
+        );
+        assert_eq!(binary_to_i64(vec![true; 64]), -1);
+        assert_eq!(
+            binary_to_i64((0..64).map(|bit| bit == 63).collect()),
+            i64::MIN
+        );
+        assert_eq!(
+            binary_to_i64((0..64).map(|bit| bit != 63).collect()),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn encode_value_writes_the_least_significant_bit_first() {
+        assert_eq!(
+            encode_value(6, ValueType::U8),
+            Some(vec![false, true, true, false, false, false, false, false])
+        );
+        assert_eq!(encode_value(-1, ValueType::I8), Some(vec![true; 8]));
+        assert_eq!(
+            encode_value(-128, ValueType::I8),
+            Some(vec![false, false, false, false, false, false, false, true])
+        );
+        assert_eq!(encode_value(1, ValueType::Bool), Some(vec![true]));
+        assert_eq!(encode_value(0, ValueType::Bool), Some(vec![false]));
+    }
+
+    #[test]
+    fn encode_value_rejects_values_out_of_range() {
+        for &value_type in VALUE_TYPES {
+            assert_eq!(
+                encode_value(value_type.min_value() - 1, value_type),
+                None,
+                "below the range of {}",
+                value_type
+            );
+            assert_eq!(
+                encode_value(value_type.max_value() + 1, value_type),
+                None,
+                "above the range of {}",
+                value_type
+            );
+        }
+        assert_eq!(encode_value(2, ValueType::Bool), None);
+        assert_eq!(encode_value(-1, ValueType::U64), None);
+    }
+
+    #[test]
+    fn value_types_have_the_range_of_their_width_and_sign() {
+        for (value_type, min, max) in [
+            (ValueType::Bool, 0, 1),
+            (ValueType::U8, 0, u8::MAX as i128),
+            (ValueType::U16, 0, u16::MAX as i128),
+            (ValueType::U32, 0, u32::MAX as i128),
+            (ValueType::U64, 0, u64::MAX as i128),
+            (ValueType::I8, i8::MIN as i128, i8::MAX as i128),
+            (ValueType::I16, i16::MIN as i128, i16::MAX as i128),
+            (ValueType::I32, i32::MIN as i128, i32::MAX as i128),
+            (ValueType::I64, i64::MIN as i128, i64::MAX as i128),
+        ] {
+            assert_eq!(value_type.min_value(), min, "min of {}", value_type);
+            assert_eq!(value_type.max_value(), max, "max of {}", value_type);
+        }
+    }
+
+    #[test]
+    fn values_round_trip_for_every_width_and_sign() {
+        for &value_type in VALUE_TYPES {
+            let (min, max) = (value_type.min_value(), value_type.max_value());
+            for value in [min, min + 1, 0, 1, max - 1, max] {
+                let bits = encode_value(value, value_type).unwrap();
+                assert_eq!(bits.len(), value_type.bits());
+                assert_eq!(
+                    decode_value(bits, value_type),
+                    value,
+                    "{} as {}",
+                    value,
+                    value_type
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn value_types_round_trip_through_their_names() {
+        for &value_type in VALUE_TYPES {
+            assert_eq!(value_type.to_string().parse::<ValueType>(), Ok(value_type));
+        }
+        assert!("u128".parse::<ValueType>().is_err());
+    }
+
+    fn output(name: &str, value_type: ValueType, len: usize) -> OutputDescriptor {
+        OutputDescriptor {
+            name: name.to_string(),
+            value_type,
+            len,
+        }
+    }
+
+    /// Lays out the bits of the values of each output, one output after the other.
+    fn encode_outputs(outputs: &[(&OutputDescriptor, &[i128])]) -> Vec<bool> {
+        outputs
+            .iter()
+            .flat_map(|(output, values)| {
+                values
+                    .iter()
+                    .flat_map(|&value| encode_value(value, output.value_type).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_outputs_splits_each_output_and_value() {
+        let price = output("price", ValueType::I16, 3);
+        let winner = output("winner", ValueType::U64, 3);
+        let bits = encode_outputs(&[(&price, &[-300, 0, 32767]), (&winner, &[2, 0, 1])]);
+
+        let result = decode_outputs(bits, &[price, winner]).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result["price"], vec![-300, 0, 32767]);
+        assert_eq!(result["winner"], vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn decode_outputs_mixes_widths_and_signs() {
+        let outputs = [
+            output("flag", ValueType::Bool, 2),
+            output("small", ValueType::I8, 1),
+            output("large", ValueType::U32, 2),
+        ];
+        let bits = encode_outputs(&[
+            (&outputs[0], &[1, 0]),
+            (&outputs[1], &[-128]),
+            (&outputs[2], &[u32::MAX as i128, 5]),
+        ]);
+        assert_eq!(bits.len(), 2 + 8 + 2 * 32);
+
+        let result = decode_outputs(bits, &outputs).unwrap();
+        assert_eq!(result["flag"], vec![1, 0]);
+        assert_eq!(result["small"], vec![-128]);
+        assert_eq!(result["large"], vec![u32::MAX as i128, 5]);
+    }
+
+    #[test]
+    fn decode_outputs_rejects_bits_not_matching_the_layout() {
+        let outputs = [
+            output("price", ValueType::U8, 2),
+            output("winner", ValueType::U64, 2),
+        ];
+        let bits = encode_outputs(&[(&outputs[0], &[1, 2]), (&outputs[1], &[0, 1])]);
+
+        let mut short = bits.clone();
+        short.pop();
+        assert!(decode_outputs(short, &outputs).is_none());
+        let mut long = bits.clone();
+        long.push(false);
+        assert!(decode_outputs(long, &outputs).is_none());
+        assert!(decode_outputs(bits, &outputs[..1]).is_none());
+        assert_eq!(decode_outputs(Vec::new(), &[]), Some(BTreeMap::new()));
+    }
+}
@@ -0,0 +1,55 @@
+use std::io;
+
+use frog_core::entities::client::ClientId;
+use frog_core::entities::computation::{ComputationId, ComputationStatus};
+use frog_core::entities::session::SessionStatus;
+use frog_core::errors::CoreError;
+use thiserror::Error;
+
+/// Represents the errors a participant can run into.
+#[derive(Error, Debug)]
+pub enum SdkError {
+    /// Error from the server or the peers, or from the ports talking to them.
+    #[error("Core error: {0}")]
+    CoreError(#[from] CoreError),
+
+    /// Error reading or writing the state directory.
+    #[error("I/O error: {0}")]
+    IOError(#[from] io::Error),
+
+    /// Error during serialization or deserialization with `bincode`.
+    #[error("Bincode error: {0}")]
+    BincodeError(#[from] bincode::Error),
+
+    /// The session reached a status from which it can never progress.
+    #[error("Session ended with status: {0:?}")]
+    SessionEnded(SessionStatus),
+
+    /// An additional computation ended before this client could take part in it.
+    #[error("Computation {0} ended with status: {1:?}")]
+    ComputationEnded(ComputationId, ComputationStatus),
+
+    /// The Phantom parameters or CRS of the client differ from the ones of the server.
+    #[error("Phantom parameters mismatch with the server: {}", .0.join(", "))]
+    ParamsMismatch(Vec<String>),
+
+    /// The input does not match the input type or length of the session.
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The session runs a computation this client does not know.
+    #[error("Unknown computation '{0}'")]
+    UnknownComputation(String),
+
+    /// A step ran before the step it depends on, e.g. decrypting before fetching the result.
+    #[error("Step not ready: {0}")]
+    NotReady(String),
+
+    /// A peer has not published its decryption share yet.
+    #[error("Client {0} has not published its decryption share yet")]
+    MissingDecShare(ClientId),
+
+    /// A peer did not publish its decryption share within the given number of requests.
+    #[error("Client {0} did not publish its decryption share after {1} requests")]
+    DecShareTimeout(ClientId, u32),
+}
@@ -0,0 +1,9 @@
+pub mod errors;
+pub mod participant;
+pub mod progress;
+pub mod state;
+
+pub use errors::SdkError;
+pub use participant::{Participant, ParticipantConfig, SessionResult};
+pub use progress::Progress;
+pub use state::Phase;
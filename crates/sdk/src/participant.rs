@@ -0,0 +1,937 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use frog_core::entities::client::{ClientEntity, ClientId};
+use frog_core::entities::computation::{ComputationId, ComputationStatus, ComputationSummary};
+use frog_core::entities::params::ParamsFingerprint;
+use frog_core::entities::session::{ResultFormat, SessionId, SessionSettings, SessionStatus};
+use frog_core::entities::session_summary::SessionSummary;
+use frog_core::errors::CoreError;
+use frog_core::ports::peer::PeerPort;
+use frog_core::ports::session_client::SessionClientPort;
+use phantom::client::Client;
+use phantom::crs::Crs;
+use phantom::function::{FheFunction, OutputDescriptor};
+use phantom::native_ops::NativeOps;
+use phantom::ops::Ops;
+use phantom::param::ParamPreset;
+use phantom::utils::{decode_outputs, encode_value};
+use tokio::sync::{OnceCell, RwLock};
+use tokio::time::sleep;
+use tracing::debug;
+
+use crate::errors::SdkError;
+use crate::progress::{Progress, ProgressCallback};
+use crate::state::{Artifact, Phase, StateStore};
+
+/// Decrypted values of each output of the computation, by output name.
+pub type SessionResult = BTreeMap<String, Vec<i128>>;
+
+/// Delay between two requests for the decryption share of a peer.
+const DEC_SHARE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum number of requests for the decryption share of a peer, i.e. about ten minutes of
+/// waiting with `DEC_SHARE_RETRY_DELAY` between them.
+const DEC_SHARE_MAX_ATTEMPTS: u32 = 600;
+
+/// Delay between two requests for the status of an additional computation.
+const COMPUTATION_POLL_DELAY: Duration = Duration::from_secs(1);
+
+/// Configuration of a participant.
+#[derive(Debug, Clone)]
+pub struct ParticipantConfig {
+    /// ID of the client within the session.
+    pub client_id: ClientId,
+    /// ID of the session to take part in.
+    pub session_id: SessionId,
+    /// Phantom parameters, which must be the ones of the server.
+    pub param_preset: &'static ParamPreset,
+    /// CRS configured for deterministic tests, otherwise the CRS of the session is downloaded.
+    pub crs: Option<Crs>,
+    /// Seed the keys of the client derive from.
+    pub client_seed: [u8; 32],
+    /// Directory keeping the state of the client, by session and client ID, so that another
+    /// process can resume the protocol. The state is kept in memory when it is not set.
+    pub state_dir: Option<PathBuf>,
+}
+
+/// Encrypted result of an additional computation, with the decryption share of this client.
+#[derive(Debug, Clone)]
+struct FetchedComputation {
+    result_format: ResultFormat,
+    encrypted_result: Vec<Vec<u8>>,
+    dec_share: Vec<u8>,
+}
+
+/// Takes part in a session as one of its clients.
+///
+/// Each step of the protocol is an async method, which waits for the session to reach the
+/// status it needs and does nothing if the client already completed it. Running the steps in
+/// order, or `run`, resumes the protocol from the last completed step.
+///
+/// Once the session is done, additional computations reuse its keys: one participant creates
+/// each of them, then every participant submits, awaits and decrypts it by its ID. Their
+/// results are kept in memory only, and their decryption shares are always relayed by the
+/// server.
+pub struct Participant {
+    client_id: ClientId,
+    session_id: SessionId,
+    param_preset: &'static ParamPreset,
+    /// CRS configured for deterministic tests, otherwise the CRS of the session is downloaded.
+    crs: Option<Crs>,
+    client_seed: [u8; 32],
+    dec_share: RwLock<Vec<u8>>,
+    encrypted_result: RwLock<Vec<Vec<u8>>>,
+    /// Format of the ciphertexts of the encrypted result, which decides how it is decrypted.
+    result_format: RwLock<ResultFormat>,
+    result: RwLock<Option<SessionResult>>,
+    /// Settings of the session, fetched once.
+    settings: OnceCell<SessionSettings>,
+    /// Additional computations whose result was fetched, by ID.
+    computations: RwLock<HashMap<ComputationId, FetchedComputation>>,
+
+    session_client: Arc<dyn SessionClientPort + Sync + Send>,
+    peer_client: Arc<dyn PeerPort + Sync + Send>,
+    /// Phantom client, created with the CRS of the session on first use.
+    phantom_client: OnceCell<RwLock<Client<NativeOps>>>,
+    /// Artifacts kept between the steps of the protocol.
+    state: StateStore,
+    progress: Option<ProgressCallback>,
+}
+
+impl Participant {
+    /// Creates a participant of the session of `config`.
+    ///
+    /// # Arguments
+    /// - `config`: Configuration of the participant.
+    /// - `session_client`: Client of the server.
+    /// - `peer_client`: Client exchanging decryption shares with the other clients.
+    pub fn new(
+        config: ParticipantConfig,
+        session_client: Arc<dyn SessionClientPort + Sync + Send>,
+        peer_client: Arc<dyn PeerPort + Sync + Send>,
+    ) -> Self {
+        let state = match &config.state_dir {
+            Some(state_dir) => StateStore::new(state_dir, &config.session_id, &config.client_id),
+            None => StateStore::in_memory(),
+        };
+        Self {
+            client_id: config.client_id,
+            session_id: config.session_id,
+            param_preset: config.param_preset,
+            crs: config.crs,
+            client_seed: config.client_seed,
+            dec_share: Default::default(),
+            encrypted_result: Default::default(),
+            result_format: Default::default(),
+            result: Default::default(),
+            settings: OnceCell::new(),
+            computations: Default::default(),
+            session_client,
+            peer_client,
+            phantom_client: OnceCell::new(),
+            state,
+            progress: None,
+        }
+    }
+
+    /// Notifies `callback` of the progress of the participant.
+    pub fn with_progress(mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Returns the ID of the client.
+    pub fn client_id(&self) -> &ClientId {
+        &self.client_id
+    }
+
+    /// Returns the ID of the session.
+    pub fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    /// Runs every step of the protocol with `values` as input, and returns the result.
+    ///
+    /// # Arguments
+    /// - `values`: Values to contribute, one per value of the `input_len` of the session.
+    pub async fn run(&self, values: &[i128]) -> Result<SessionResult, SdkError> {
+        self.resume().await?;
+        self.join().await?;
+        self.bootstrap().await?;
+        self.submit(values).await?;
+        self.await_result().await?;
+        self.decrypt().await
+    }
+
+    /// Checks that the client uses the same Phantom parameters and CRS as the server.
+    pub async fn check_params(&self) -> Result<(), SdkError> {
+        let params = self.session_client.get_params().await?;
+        ensure_params(
+            &ParamsFingerprint::new(self.param_preset, self.crs.as_ref()),
+            &params,
+        )
+    }
+
+    /// Joins the session with the public key share and ring packing key share of this client.
+    pub async fn join(&self) -> Result<(), SdkError> {
+        if self.phase().await? >= Phase::Joined {
+            return Ok(());
+        }
+        let client = self.phantom_client().await?.read().await;
+
+        let pk_share = client.serialize_pk_share(&client.pk_share_gen())?;
+
+        let rp_key_share = client.serialize_rp_key_share(&client.rp_key_share_gen())?;
+
+        self.session_client
+            .join_session(
+                self.session_id.clone(),
+                ClientEntity::new(self.client_id.clone(), pk_share, rp_key_share),
+            )
+            .await?;
+        self.complete(Phase::Joined).await
+    }
+
+    /// Waits for every client to join, then sends the bootstrapping key share of this client.
+    pub async fn bootstrap(&self) -> Result<(), SdkError> {
+        if self.phase().await? >= Phase::Bootstrapped {
+            return Ok(());
+        }
+        self.wait(SessionStatus::WaitingForBootstrap).await?;
+        self.update_pk().await?;
+        let client = self.phantom_client().await?.read().await;
+
+        let bs_key = client.serialize_bs_key_share(&client.bs_key_share_gen())?;
+        self.session_client
+            .bootstrap(self.session_id.clone(), self.client_id.clone(), bs_key)
+            .await?;
+        self.complete(Phase::Bootstrapped).await
+    }
+
+    /// Waits for the bootstrapping keys to be aggregated, then encrypts `values` and sends them
+    /// as the input of this client.
+    ///
+    /// # Arguments
+    /// - `values`: Values to contribute, one per value of the `input_len` of the session.
+    pub async fn submit(&self, values: &[i128]) -> Result<(), SdkError> {
+        if self.phase().await? >= Phase::Submitted {
+            return Ok(());
+        }
+        self.validate_input(values).await?;
+
+        self.wait(SessionStatus::WaitingForArgument).await?;
+        let encrypted_data = self.encrypt_input(values).await?;
+        self.session_client
+            .send_data(
+                self.session_id.clone(),
+                self.client_id.clone(),
+                encrypted_data,
+            )
+            .await?;
+        self.complete(Phase::Submitted).await
+    }
+
+    /// Waits for the result to be computed, then downloads it and publishes the decryption share
+    /// of this client.
+    ///
+    /// The decryption share is published again when the result was already fetched, since the
+    /// client may have stopped before publishing it.
+    pub async fn await_result(&self) -> Result<(), SdkError> {
+        if self.phase().await? >= Phase::ResultFetched {
+            self.load_encrypted_result().await?;
+        } else {
+            self.wait(SessionStatus::Done).await?;
+            self.fetch_encrypted_result().await?;
+        }
+        let dec_share = self.dec_share().await;
+        self.peer_client.publish_dec_share(dec_share).await?;
+        Ok(())
+    }
+
+    /// Waits for the decryption share of every peer, then decrypts the result.
+    ///
+    /// Fails with `SdkError::SessionEnded` if the session fails meanwhile, and with
+    /// `SdkError::DecShareTimeout` if a peer does not publish its share in time.
+    pub async fn decrypt(&self) -> Result<SessionResult, SdkError> {
+        self.decrypt_with(true).await
+    }
+
+    /// Decrypts the result with the decryption shares the peers published so far.
+    ///
+    /// Fails with `SdkError::MissingDecShare` if a peer has not published its share yet.
+    pub async fn try_decrypt(&self) -> Result<SessionResult, SdkError> {
+        self.decrypt_with(false).await
+    }
+
+    /// Starts an additional computation of the session, which must be done, reusing its keys.
+    ///
+    /// # Returns
+    /// - The ID of the new computation, which every participant then submits its input for.
+    pub async fn create_computation(&self) -> Result<ComputationId, SdkError> {
+        Ok(self
+            .session_client
+            .create_computation(self.session_id.clone())
+            .await?)
+    }
+
+    /// Returns the current summary of an additional computation of the session.
+    pub async fn computation(
+        &self,
+        computation_id: ComputationId,
+    ) -> Result<ComputationSummary, SdkError> {
+        Ok(self
+            .session_client
+            .get_computation(self.session_id.clone(), computation_id)
+            .await?)
+    }
+
+    /// Encrypts `values` and sends them as the input of this client for an additional
+    /// computation, unless the server already has it.
+    ///
+    /// # Arguments
+    /// - `computation_id`: ID of the computation.
+    /// - `values`: Values to contribute, one per value of the `input_len` of the session.
+    pub async fn submit_computation(
+        &self,
+        computation_id: ComputationId,
+        values: &[i128],
+    ) -> Result<(), SdkError> {
+        let computation_summary = self.computation(computation_id).await?;
+        if computation_summary.submitted.contains(&self.client_id) {
+            return Ok(());
+        }
+        if computation_summary.status.is_terminal() {
+            return Err(SdkError::ComputationEnded(
+                computation_id,
+                computation_summary.status,
+            ));
+        }
+        self.validate_input(values).await?;
+
+        let encrypted_data = self.encrypt_input(values).await?;
+        self.session_client
+            .send_computation_data(
+                self.session_id.clone(),
+                computation_id,
+                self.client_id.clone(),
+                encrypted_data,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Waits for an additional computation to be computed, then downloads its result and
+    /// publishes the decryption share of this client.
+    ///
+    /// The decryption share is published again when the result was already fetched.
+    ///
+    /// # Arguments
+    /// - `computation_id`: ID of the computation.
+    pub async fn await_computation(&self, computation_id: ComputationId) -> Result<(), SdkError> {
+        let fetched = self.computations.read().await.get(&computation_id).cloned();
+        let fetched = match fetched {
+            Some(fetched) => fetched,
+            None => self.fetch_computation(computation_id).await?,
+        };
+        self.session_client
+            .send_computation_dec_share(
+                self.session_id.clone(),
+                computation_id,
+                self.client_id.clone(),
+                fetched.dec_share,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Waits for the decryption share of every peer, then decrypts the result of an additional
+    /// computation, awaiting it first unless `await_computation` already did.
+    ///
+    /// # Arguments
+    /// - `computation_id`: ID of the computation.
+    pub async fn decrypt_computation(
+        &self,
+        computation_id: ComputationId,
+    ) -> Result<SessionResult, SdkError> {
+        if !self.computations.read().await.contains_key(&computation_id) {
+            self.await_computation(computation_id).await?;
+        }
+        let fetched = self.computations.read().await[&computation_id].clone();
+
+        let mut dec_shares = self.collect_dec_shares(Some(computation_id), true).await?;
+        dec_shares.push(fetched.dec_share);
+        self.decode_result(
+            fetched.result_format,
+            &fetched.encrypted_result,
+            &dec_shares,
+        )
+        .await
+    }
+
+    /// Checks that `values` is a valid input of the session.
+    ///
+    /// The input must hold one value of the input type of the session for each of its
+    /// `input_len` values.
+    pub async fn validate_input(&self, values: &[i128]) -> Result<(), SdkError> {
+        let settings = self.settings().await?;
+        if values.len() != settings.input_len {
+            return Err(SdkError::InvalidInput(format!(
+                "Input has {} values, expected {}",
+                values.len(),
+                settings.input_len
+            )));
+        }
+        if let Some(value) = values
+            .iter()
+            .find(|value| encode_value(**value, settings.input_type).is_none())
+        {
+            return Err(SdkError::InvalidInput(format!(
+                "Input {} does not fit type {}",
+                value, settings.input_type
+            )));
+        }
+        Ok(())
+    }
+
+    /// Waits for the session to reach `session_status`.
+    ///
+    /// Fails with `SdkError::SessionEnded` if the session ends in another status.
+    pub async fn wait(&self, session_status: SessionStatus) -> Result<(), SdkError> {
+        self.report(Progress::Waiting(session_status.clone()));
+        let session_event = self
+            .session_client
+            .wait_for_status(self.session_id.clone(), session_status.clone())
+            .await?;
+        debug!("Session status: {:?}", session_event.status);
+
+        // The session ended before reaching the awaited status.
+        if session_event.status != session_status {
+            return Err(SdkError::SessionEnded(session_event.status));
+        }
+        Ok(())
+    }
+
+    /// Returns the current summary of the session.
+    pub async fn summary(&self) -> Result<SessionSummary, SdkError> {
+        Ok(self
+            .session_client
+            .get_session(self.session_id.clone())
+            .await?)
+    }
+
+    /// Returns the last phase this client completed.
+    pub async fn phase(&self) -> Result<Phase, SdkError> {
+        self.state.phase().await
+    }
+
+    /// Returns the last phase this client completed, to resume the protocol from there.
+    ///
+    /// The local phase can lag behind the server when the client stopped right after a request
+    /// succeeded, so the progress the server recorded for this client is taken into account.
+    pub async fn resume(&self) -> Result<Phase, SdkError> {
+        let session_summary = self.summary().await?;
+        let server_phase = session_summary
+            .participants
+            .iter()
+            .find(|participant| participant.id == self.client_id)
+            .map_or(Phase::New, |participant| {
+                if participant.has_encrypted_data {
+                    Phase::Submitted
+                } else if participant.has_bs_key_share {
+                    Phase::Bootstrapped
+                } else {
+                    Phase::Joined
+                }
+            });
+        self.state.complete(server_phase).await?;
+
+        // Serve the artifacts of the completed steps again.
+        let phase = self.state.phase().await?;
+        if phase >= Phase::ResultFetched {
+            self.load_encrypted_result().await?;
+        }
+        if phase >= Phase::Decrypted {
+            *self.result.write().await = self.state.load(Artifact::Result).await?;
+        }
+        Ok(phase)
+    }
+
+    /// Returns the decryption share of this client, empty until the result is fetched.
+    pub async fn dec_share(&self) -> Vec<u8> {
+        self.dec_share.read().await.clone()
+    }
+
+    /// Returns the decrypted result, if any.
+    pub async fn result(&self) -> Option<SessionResult> {
+        self.result.read().await.clone()
+    }
+
+    /// Returns the Phantom client, creating it with the CRS of the session on first use.
+    ///
+    /// The keys of the client derive from its seed, so the same client is created in every
+    /// process. The public key is loaded when a previous step saved it.
+    async fn phantom_client(&self) -> Result<&RwLock<Client<NativeOps>>, SdkError> {
+        self.phantom_client
+            .get_or_try_init(|| async {
+                let session_summary = self.summary().await?;
+                // The settings never change, so keep them for the later steps.
+                let _ = self.settings.set(session_summary.settings.clone());
+                let crs = match (self.crs, session_summary.crs) {
+                    (Some(crs), _) | (None, Some(crs)) => crs,
+                    (None, None) => {
+                        return Err(SdkError::NotReady(format!(
+                            "Session {} has no CRS to download",
+                            self.session_id
+                        )))
+                    }
+                };
+
+                // Key shares generated with other parameters could not be aggregated with the
+                // others.
+                ensure_params(
+                    &ParamsFingerprint::new(self.param_preset, Some(&crs)),
+                    &session_summary.params,
+                )?;
+
+                let pk: Option<Vec<u8>> = self.state.load(Artifact::Pk).await?;
+                let phantom_client = Client::<NativeOps>::new(
+                    self.param_preset.param,
+                    crs,
+                    self.client_id.0,
+                    self.client_seed,
+                    pk.as_deref(),
+                )?;
+                Ok::<_, SdkError>(RwLock::new(phantom_client))
+            })
+            .await
+    }
+
+    /// Returns the settings of the session, fetching them on first use.
+    async fn settings(&self) -> Result<&SessionSettings, SdkError> {
+        self.settings
+            .get_or_try_init(|| async { Ok::<_, SdkError>(self.summary().await?.settings) })
+            .await
+    }
+
+    /// Returns the layout of the outputs in the result of the computation of the session.
+    async fn outputs(&self) -> Result<Vec<OutputDescriptor>, SdkError> {
+        let settings = self.settings().await?;
+        let function = FheFunction::by_name(&settings.computation)
+            .ok_or_else(|| SdkError::UnknownComputation(settings.computation.clone()))?;
+        Ok(function.output_descriptors(settings.input_type, settings.input_len))
+    }
+
+    /// Records that the client completed `phase` and reports it.
+    async fn complete(&self, phase: Phase) -> Result<(), SdkError> {
+        self.state.complete(phase).await?;
+        self.report(Progress::Completed(phase));
+        Ok(())
+    }
+
+    /// Notifies the progress callback, if any.
+    fn report(&self, progress: Progress) {
+        if let Some(callback) = &self.progress {
+            callback(&progress);
+        }
+    }
+
+    /// Downloads the aggregated public key of the session and saves it.
+    async fn update_pk(&self) -> Result<(), SdkError> {
+        let pk = self.session_client.get_pk(self.session_id.clone()).await?;
+
+        let mut client = self.phantom_client().await?.write().await;
+        let deserialized_pk = client.deserialize_pk(&pk)?;
+        self.state.save(Artifact::Pk, &pk).await?;
+        client.with_pk(deserialized_pk);
+        Ok(())
+    }
+
+    /// Downloads the aggregated public key unless a previous step saved it.
+    ///
+    /// A saved public key is always loaded in the Phantom client, either when creating it or by
+    /// `update_pk`.
+    async fn ensure_pk(&self) -> Result<(), SdkError> {
+        if self.state.load::<Vec<u8>>(Artifact::Pk).await?.is_none() {
+            self.update_pk().await?;
+        }
+        Ok(())
+    }
+
+    /// Encrypts a valid input with the aggregated public key, downloading it if needed.
+    async fn encrypt_input(&self, values: &[i128]) -> Result<Vec<u8>, SdkError> {
+        let input_type = self.settings().await?.input_type;
+        // Every value of the input is encoded on its own, one after the other.
+        let input = values
+            .iter()
+            .flat_map(|value| encode_value(*value, input_type).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        self.ensure_pk().await?;
+        let client = self.phantom_client().await?.read().await;
+        Ok(client.serialize_batched_ct(&client.batched_pk_encrypt(input.into_iter()))?)
+    }
+
+    /// Downloads the encrypted result and computes the decryption share of this client.
+    async fn fetch_encrypted_result(&self) -> Result<(), SdkError> {
+        let result_format = self.summary().await?.result_format;
+        let encrypted_result = self
+            .session_client
+            .get_encrypted_result(self.session_id.clone())
+            .await?;
+        let dec_share = self
+            .compute_dec_share(result_format, &encrypted_result)
+            .await?;
+        self.state
+            .save(
+                Artifact::EncryptedResult,
+                &(result_format, &encrypted_result),
+            )
+            .await?;
+        self.state.save(Artifact::DecShare, &dec_share).await?;
+        *self.dec_share.write().await = dec_share;
+        *self.encrypted_result.write().await = encrypted_result;
+        *self.result_format.write().await = result_format;
+        self.complete(Phase::ResultFetched).await
+    }
+
+    /// Waits for an additional computation to be done, then downloads its encrypted result and
+    /// computes the decryption share of this client.
+    ///
+    /// Fails with `SdkError::ComputationEnded` if the computation failed.
+    async fn fetch_computation(
+        &self,
+        computation_id: ComputationId,
+    ) -> Result<FetchedComputation, SdkError> {
+        let mut reported = false;
+        let computation_summary = loop {
+            let computation_summary = self.computation(computation_id).await?;
+            match computation_summary.status {
+                ComputationStatus::Done => break computation_summary,
+                ComputationStatus::Failed { .. } => {
+                    return Err(SdkError::ComputationEnded(
+                        computation_id,
+                        computation_summary.status,
+                    ))
+                }
+                ComputationStatus::WaitingForArgument => {
+                    if !reported {
+                        self.report(Progress::WaitingForComputation(computation_id));
+                        reported = true;
+                    }
+                    sleep(COMPUTATION_POLL_DELAY).await;
+                }
+            }
+        };
+
+        let encrypted_result = self
+            .session_client
+            .get_computation_result(self.session_id.clone(), computation_id)
+            .await?;
+        let dec_share = self
+            .compute_dec_share(computation_summary.result_format, &encrypted_result)
+            .await?;
+        let fetched = FetchedComputation {
+            result_format: computation_summary.result_format,
+            encrypted_result,
+            dec_share,
+        };
+        self.computations
+            .write()
+            .await
+            .insert(computation_id, fetched.clone());
+        Ok(fetched)
+    }
+
+    /// Computes the decryption share of this client for an encrypted result.
+    ///
+    /// A packed result gets one decryption share per packed ciphertext. Results of sessions
+    /// computed before results were packed fall back to one decryption share per bit.
+    async fn compute_dec_share(
+        &self,
+        result_format: ResultFormat,
+        encrypted_result: &[Vec<u8>],
+    ) -> Result<Vec<u8>, SdkError> {
+        let client = self.phantom_client().await?.read().await;
+        let dec_share = match result_format {
+            ResultFormat::Packed => {
+                let dec_shares = encrypted_result
+                    .iter()
+                    .map(|ct| {
+                        let dec_share = client.rp_decrypt_share(&client.deserialize_rp_ct(ct)?);
+                        client.serialize_rp_dec_share(&dec_share)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                bincode::serialize(&dec_shares)?
+            }
+            ResultFormat::Unpacked => {
+                let dec_shares = encrypted_result
+                    .iter()
+                    .map(|ct| Ok(client.decrypt_share(&client.deserialize_ct(ct)?)))
+                    .collect::<Result<Vec<_>, bincode::Error>>()?;
+                client.serialize_dec_shares(&dec_shares)?
+            }
+        };
+        Ok(dec_share)
+    }
+
+    /// Loads the encrypted result and the decryption share a previous step saved.
+    async fn load_encrypted_result(&self) -> Result<(), SdkError> {
+        let not_fetched = || SdkError::NotReady("The encrypted result was not fetched".into());
+        let (result_format, encrypted_result): (ResultFormat, Vec<Vec<u8>>) = self
+            .state
+            .load(Artifact::EncryptedResult)
+            .await?
+            .ok_or_else(not_fetched)?;
+        let dec_share: Vec<u8> = self
+            .state
+            .load(Artifact::DecShare)
+            .await?
+            .ok_or_else(not_fetched)?;
+        *self.dec_share.write().await = dec_share;
+        *self.encrypted_result.write().await = encrypted_result;
+        *self.result_format.write().await = result_format;
+        Ok(())
+    }
+
+    /// Returns the IDs of the other clients of the session.
+    async fn peer_ids(&self) -> Result<Vec<ClientId>, SdkError> {
+        Ok(self
+            .summary()
+            .await?
+            .participants
+            .into_iter()
+            .map(|participant| participant.id)
+            .filter(|client_id| *client_id != self.client_id)
+            .collect())
+    }
+
+    /// Collects the decryption share of every peer.
+    ///
+    /// # Arguments
+    /// - `computation_id`: The additional computation whose result is decrypted, whose shares
+    ///   the server relays, or `None` for the result of the session.
+    /// - `wait`: Whether to retry until every peer published its decryption share, rather than
+    ///   failing on the first missing one. Only a share not published yet is retried, until the
+    ///   session fails or `DEC_SHARE_MAX_ATTEMPTS` requests were made.
+    async fn collect_dec_shares(
+        &self,
+        computation_id: Option<ComputationId>,
+        wait: bool,
+    ) -> Result<Vec<Vec<u8>>, SdkError> {
+        let mut dec_shares = vec![];
+        for peer_id in self.peer_ids().await? {
+            let mut attempts = 0;
+            loop {
+                let dec_share = match computation_id {
+                    Some(computation_id) => {
+                        self.session_client
+                            .get_computation_dec_share(
+                                self.session_id.clone(),
+                                computation_id,
+                                peer_id.clone(),
+                            )
+                            .await
+                    }
+                    None => self.peer_client.get_dec_share(&peer_id).await,
+                };
+                let dec_share = dec_share?;
+                if !dec_share.is_empty() {
+                    self.report(Progress::DecShareReceived(peer_id.clone()));
+                    dec_shares.push(dec_share);
+                    break;
+                }
+                if !wait {
+                    return Err(SdkError::MissingDecShare(peer_id));
+                }
+                attempts += 1;
+                if attempts >= DEC_SHARE_MAX_ATTEMPTS {
+                    return Err(SdkError::DecShareTimeout(peer_id, attempts));
+                }
+
+                // The peers of a failed session never publish their share.
+                let status = self.summary().await?.status;
+                if status.is_failure() {
+                    return Err(SdkError::SessionEnded(status));
+                }
+                sleep(DEC_SHARE_RETRY_DELAY).await;
+            }
+        }
+        Ok(dec_shares)
+    }
+
+    /// Decrypts the result with the decryption shares of every client, unless it already was.
+    ///
+    /// # Arguments
+    /// - `wait`: Whether to wait for the decryption shares of the peers.
+    async fn decrypt_with(&self, wait: bool) -> Result<SessionResult, SdkError> {
+        let phase = self.phase().await?;
+        if phase >= Phase::Decrypted {
+            if let Some(result) = self.state.load(Artifact::Result).await? {
+                *self.result.write().await = Some(result);
+            }
+            return self
+                .result()
+                .await
+                .ok_or_else(|| SdkError::NotReady("The result was not saved".into()));
+        }
+        if phase < Phase::ResultFetched {
+            return Err(SdkError::NotReady(
+                "The encrypted result was not fetched".into(),
+            ));
+        }
+        if self.encrypted_result.read().await.is_empty() {
+            self.load_encrypted_result().await?;
+        }
+
+        let mut dec_shares = self.collect_dec_shares(None, wait).await?;
+        dec_shares.push(self.dec_share().await);
+
+        let result_format = *self.result_format.read().await;
+        let encrypted_result = self.encrypted_result.read().await.clone();
+        let result = self
+            .decode_result(result_format, &encrypted_result, &dec_shares)
+            .await?;
+        self.state.save(Artifact::Result, &result).await?;
+        *self.result.write().await = Some(result.clone());
+        self.complete(Phase::Decrypted).await?;
+        Ok(result)
+    }
+
+    /// Decrypts an encrypted result with the decryption shares of every client, and decodes the
+    /// values of its outputs.
+    ///
+    /// # Arguments
+    /// - `result_format`: Format of the ciphertexts of the result.
+    /// - `encrypted_result`: Serialized ciphertexts of the result.
+    /// - `dec_shares`: Serialized decryption share of every client, this one included.
+    async fn decode_result(
+        &self,
+        result_format: ResultFormat,
+        encrypted_result: &[Vec<u8>],
+        dec_shares: &[Vec<u8>],
+    ) -> Result<SessionResult, SdkError> {
+        let outputs = self.outputs().await?;
+        let result = match result_format {
+            ResultFormat::Packed => {
+                let expected_bits = outputs
+                    .iter()
+                    .map(|output| output.len * output.value_type.bits())
+                    .sum();
+                self.aggregate_packed(encrypted_result, dec_shares, expected_bits)
+                    .await?
+            }
+            ResultFormat::Unpacked => {
+                self.aggregate_unpacked(encrypted_result, dec_shares)
+                    .await?
+            }
+        };
+        let bits = result.len();
+        let result = decode_outputs(result, &outputs).ok_or_else(|| {
+            CoreError::UnexpectedResponse(format!(
+                "Result has {} bits, which does not match the outputs {:?}",
+                bits, outputs
+            ))
+        })?;
+        Ok(result)
+    }
+
+    /// Decrypts the bits of a packed result with the decryption shares of every client.
+    ///
+    /// Each packed ciphertext holds up to `ring_size` bits, the last one holding the remaining
+    /// bits of the `expected_bits` the outputs take.
+    async fn aggregate_packed(
+        &self,
+        encrypted_result: &[Vec<u8>],
+        dec_shares: &[Vec<u8>],
+        expected_bits: usize,
+    ) -> Result<Vec<bool>, SdkError> {
+        let client = self.phantom_client().await?.read().await;
+        let ring_size = client.ring_packing_param().ring_size;
+
+        let cts = encrypted_result
+            .iter()
+            .map(|ct| client.deserialize_rp_ct(ct))
+            .collect::<Result<Vec<_>, _>>()?;
+        let dec_shares = dec_shares
+            .iter()
+            .map(|bytes| {
+                bincode::deserialize::<Vec<Vec<u8>>>(bytes)?
+                    .iter()
+                    .map(|dec_share| client.deserialize_rp_dec_share(dec_share))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(dec_share) = dec_shares
+            .iter()
+            .find(|dec_share| dec_share.len() != cts.len())
+        {
+            return Err(CoreError::UnexpectedResponse(format!(
+                "Decryption share covers {} ciphertexts, expected {}",
+                dec_share.len(),
+                cts.len()
+            ))
+            .into());
+        }
+
+        let mut result = Vec::with_capacity(expected_bits);
+        for (idx, ct) in cts.iter().enumerate() {
+            let bits = client.aggregate_rp_decryption_shares(
+                ct,
+                dec_shares.iter().map(|dec_shares| &dec_shares[idx]),
+            );
+            let remaining = expected_bits.saturating_sub(idx * ring_size);
+            result.extend(bits.into_iter().take(remaining.min(ring_size)));
+        }
+        Ok(result)
+    }
+
+    /// Decrypts the bits of an unpacked result with the decryption shares of every client.
+    async fn aggregate_unpacked(
+        &self,
+        encrypted_result: &[Vec<u8>],
+        dec_shares: &[Vec<u8>],
+    ) -> Result<Vec<bool>, SdkError> {
+        let client = self.phantom_client().await?.read().await;
+
+        let ct_out = encrypted_result
+            .iter()
+            .map(|ct| client.deserialize_ct(ct))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ct_out_dec_shares = dec_shares
+            .iter()
+            .map(|bytes| client.deserialize_dec_shares(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(dec_shares) = ct_out_dec_shares
+            .iter()
+            .find(|dec_shares| dec_shares.len() != ct_out.len())
+        {
+            return Err(CoreError::UnexpectedResponse(format!(
+                "Decryption share covers {} ciphertexts, expected {}",
+                dec_shares.len(),
+                ct_out.len()
+            ))
+            .into());
+        }
+
+        Ok((0..ct_out.len())
+            .map(|idx| {
+                client.aggregate_decryption_shares(
+                    &ct_out[idx],
+                    ct_out_dec_shares.iter().map(|dec_shares| &dec_shares[idx]),
+                )
+            })
+            .collect())
+    }
+}
+
+/// Fails with the differing fields if the fingerprint of the client does not match `expected`.
+fn ensure_params(params: &ParamsFingerprint, expected: &ParamsFingerprint) -> Result<(), SdkError> {
+    let differences = params.differences(expected);
+    if !differences.is_empty() {
+        return Err(SdkError::ParamsMismatch(differences));
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use frog_core::entities::client::ClientId;
+use frog_core::entities::computation::ComputationId;
+use frog_core::entities::session::SessionStatus;
+
+use crate::state::Phase;
+
+/// Progress of a participant through the protocol, reported to its progress callback.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Progress {
+    /// The participant waits for the session to reach the status.
+    Waiting(SessionStatus),
+    /// The participant waits for an additional computation to be computed.
+    WaitingForComputation(ComputationId),
+    /// The participant completed a phase of the protocol.
+    Completed(Phase),
+    /// The decryption share of a peer was received.
+    DecShareReceived(ClientId),
+}
+
+/// Callback notified of the progress of a participant.
+pub type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
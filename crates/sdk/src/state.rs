@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use frog_core::entities::client::ClientId;
+use frog_core::entities::session::SessionId;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::errors::SdkError;
+
+/// Last step of the protocol a client completed, in order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Phase {
+    /// No step completed yet.
+    #[default]
+    New,
+    /// The key shares of the client were sent to the server.
+    Joined,
+    /// The bootstrapping key share of the client was sent to the server.
+    Bootstrapped,
+    /// The encrypted input of the client was sent to the server.
+    Submitted,
+    /// The encrypted result and the decryption share of the client were saved.
+    ResultFetched,
+    /// The result was decrypted and saved.
+    Decrypted,
+}
+
+/// Artifacts a client keeps locally between the steps of the protocol.
+#[derive(Debug, Clone, Copy)]
+pub enum Artifact {
+    /// Last step of the protocol the client completed.
+    Phase,
+    /// Aggregated public key of the session, needed to bootstrap and encrypt the input.
+    Pk,
+    /// Encrypted result of the session, with its format.
+    EncryptedResult,
+    /// Decryption share of the encrypted result computed by this client.
+    DecShare,
+    /// Decrypted result of the session.
+    Result,
+}
+
+impl Artifact {
+    /// Name of the file holding the artifact.
+    fn file_name(&self) -> &'static str {
+        match self {
+            Artifact::Phase => "phase.bin",
+            Artifact::Pk => "pk.bin",
+            Artifact::EncryptedResult => "encrypted_result.bin",
+            Artifact::DecShare => "dec_share.bin",
+            Artifact::Result => "result.bin",
+        }
+    }
+}
+
+/// Local directory holding the artifacts of a client for one session, or memory when the
+/// artifacts do not need to outlive the process.
+///
+/// Each step of the protocol can run in its own process, reading the artifacts the previous
+/// steps saved. Artifacts are serialized with `bincode` and replaced atomically, so that a client
+/// stopped at any point finds either the previous or the new value of each artifact. Each step
+/// saves its artifacts before its phase, so a saved phase implies its artifacts are saved.
+#[derive(Debug)]
+pub struct StateStore {
+    backend: Backend,
+}
+
+/// Where the artifacts are kept.
+#[derive(Debug)]
+enum Backend {
+    /// Files of a directory.
+    Dir(PathBuf),
+    /// Serialized artifacts, by file name.
+    Memory(Mutex<HashMap<&'static str, Vec<u8>>>),
+}
+
+impl StateStore {
+    /// Creates a store in `<state_dir>/<session_id>/<client_id>`.
+    ///
+    /// # Arguments
+    /// - `state_dir`: Directory holding the state of every session.
+    /// - `session_id`: ID of the session.
+    /// - `client_id`: ID of the client.
+    pub fn new(state_dir: impl AsRef<Path>, session_id: &SessionId, client_id: &ClientId) -> Self {
+        Self {
+            backend: Backend::Dir(
+                state_dir
+                    .as_ref()
+                    .join(session_id.to_string())
+                    .join(client_id.to_string()),
+            ),
+        }
+    }
+
+    /// Creates a store keeping the artifacts in memory, which cannot resume another process.
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(Default::default()),
+        }
+    }
+
+    /// Saves an artifact, replacing its previous value.
+    ///
+    /// The value is written to a temporary file, flushed to disk and renamed over the artifact.
+    pub async fn save<T: Serialize>(&self, artifact: Artifact, value: &T) -> Result<(), SdkError> {
+        let bytes = bincode::serialize(value)?;
+        let dir = match &self.backend {
+            Backend::Dir(dir) => dir,
+            Backend::Memory(artifacts) => {
+                artifacts
+                    .lock()
+                    .unwrap()
+                    .insert(artifact.file_name(), bytes);
+                return Ok(());
+            }
+        };
+
+        fs::create_dir_all(dir).await?;
+        let path = dir.join(artifact.file_name());
+        let tmp_path = path.with_extension("tmp");
+
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await?;
+        fs::rename(&tmp_path, &path).await?;
+
+        // Flush the directory too, otherwise the rename itself could be lost.
+        fs::File::open(dir).await?.sync_all().await?;
+        Ok(())
+    }
+
+    /// Loads an artifact, or `None` if it was never saved.
+    pub async fn load<T: DeserializeOwned>(
+        &self,
+        artifact: Artifact,
+    ) -> Result<Option<T>, SdkError> {
+        let bytes = match &self.backend {
+            Backend::Dir(dir) => match fs::read(dir.join(artifact.file_name())).await {
+                Ok(bytes) => bytes,
+                Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(error) => return Err(error.into()),
+            },
+            Backend::Memory(artifacts) => match artifacts.lock().unwrap().get(artifact.file_name())
+            {
+                Some(bytes) => bytes.clone(),
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(bincode::deserialize(&bytes)?))
+    }
+
+    /// Returns the last phase the client completed.
+    pub async fn phase(&self) -> Result<Phase, SdkError> {
+        Ok(self.load(Artifact::Phase).await?.unwrap_or_default())
+    }
+
+    /// Records that the client completed `phase`, unless it already completed a later one.
+    pub async fn complete(&self, phase: Phase) -> Result<(), SdkError> {
+        if self.phase().await? < phase {
+            self.save(Artifact::Phase, &phase).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// A fresh state directory, to be removed with `remove_dir`.
+    fn state_dir() -> PathBuf {
+        env::temp_dir().join(format!("frog-state-{}", SessionId::generate()))
+    }
+
+    async fn remove_dir(state_dir: PathBuf) {
+        fs::remove_dir_all(state_dir).await.unwrap();
+    }
+
+    async fn assert_complete_never_moves_backwards(store: &StateStore) {
+        assert_eq!(store.phase().await.unwrap(), Phase::New);
+
+        store.complete(Phase::Submitted).await.unwrap();
+        assert_eq!(store.phase().await.unwrap(), Phase::Submitted);
+
+        for earlier in [
+            Phase::New,
+            Phase::Joined,
+            Phase::Bootstrapped,
+            Phase::Submitted,
+        ] {
+            store.complete(earlier).await.unwrap();
+            assert_eq!(store.phase().await.unwrap(), Phase::Submitted);
+        }
+
+        store.complete(Phase::Decrypted).await.unwrap();
+        assert_eq!(store.phase().await.unwrap(), Phase::Decrypted);
+        store.complete(Phase::ResultFetched).await.unwrap();
+        assert_eq!(store.phase().await.unwrap(), Phase::Decrypted);
+    }
+
+    async fn assert_artifacts_round_trip(store: &StateStore) {
+        assert_eq!(store.load::<Vec<u8>>(Artifact::Pk).await.unwrap(), None);
+
+        store.save(Artifact::Pk, &vec![1u8, 2, 3]).await.unwrap();
+        let result = (7u32, vec![vec![4u8], vec![5, 6]]);
+        store
+            .save(Artifact::EncryptedResult, &result)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.load::<Vec<u8>>(Artifact::Pk).await.unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            store.load(Artifact::EncryptedResult).await.unwrap(),
+            Some(result)
+        );
+
+        // Saving again replaces the previous value.
+        store.save(Artifact::Pk, &vec![9u8]).await.unwrap();
+        assert_eq!(
+            store.load::<Vec<u8>>(Artifact::Pk).await.unwrap(),
+            Some(vec![9])
+        );
+        assert_eq!(
+            store.load::<Vec<u8>>(Artifact::DecShare).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_never_moves_backwards() {
+        assert_complete_never_moves_backwards(&StateStore::in_memory()).await;
+
+        let state_dir = state_dir();
+        let store = StateStore::new(&state_dir, &SessionId::generate(), &ClientId(0));
+        assert_complete_never_moves_backwards(&store).await;
+        remove_dir(state_dir).await;
+    }
+
+    #[tokio::test]
+    async fn artifacts_round_trip_in_memory() {
+        assert_artifacts_round_trip(&StateStore::in_memory()).await;
+    }
+
+    #[tokio::test]
+    async fn artifacts_round_trip_through_the_state_dir() {
+        let state_dir = state_dir();
+        let (session_id, client_id) = (SessionId::generate(), ClientId(1));
+        let store = StateStore::new(&state_dir, &session_id, &client_id);
+        assert_artifacts_round_trip(&store).await;
+        store.complete(Phase::Joined).await.unwrap();
+
+        // Another process finds what was saved for the same session and client, but nothing of
+        // the other clients.
+        let resumed = StateStore::new(&state_dir, &session_id, &client_id);
+        assert_eq!(resumed.phase().await.unwrap(), Phase::Joined);
+        assert_eq!(
+            resumed.load::<Vec<u8>>(Artifact::Pk).await.unwrap(),
+            Some(vec![9])
+        );
+        let other = StateStore::new(&state_dir, &session_id, &ClientId(2));
+        assert_eq!(other.phase().await.unwrap(), Phase::New);
+
+        // The temporary files the artifacts were written to were renamed.
+        let mut entries = fs::read_dir(state_dir.join(session_id.to_string()).join("1"))
+            .await
+            .unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            assert_ne!(entry.path().extension().unwrap(), "tmp");
+        }
+        remove_dir(state_dir).await;
+    }
+}
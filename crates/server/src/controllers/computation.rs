@@ -0,0 +1,124 @@
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use frog_common::blob::Blob;
+use frog_core::entities::blob::content_hash;
+use frog_core::entities::client::ClientId;
+use frog_core::entities::computation::{ComputationId, ComputationSummary};
+use frog_core::entities::session::SessionId;
+use tracing::instrument;
+
+use crate::app_state::AppState;
+use crate::errors::AppError;
+use crate::json_response::JsonResponse;
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn create_computation(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<ComputationId>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let computation_id = app_state
+        .session_service
+        .create_computation(session_id)
+        .await?;
+    Ok(JsonResponse(computation_id))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn list_computations(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<Vec<ComputationSummary>>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let computations = app_state
+        .session_service
+        .list_computations(session_id)
+        .await?;
+    Ok(JsonResponse(
+        computations.iter().map(ComputationSummary::from).collect(),
+    ))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn get_computation(
+    Path((id, computation_id)): Path<(String, usize)>,
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<ComputationSummary>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let computation_entity = app_state
+        .session_service
+        .get_computation(session_id, ComputationId(computation_id))
+        .await?;
+    Ok(JsonResponse(ComputationSummary::from(&computation_entity)))
+}
+
+#[instrument(level = "info", skip(app_state, headers))]
+pub async fn get_computation_result(
+    Path((id, computation_id)): Path<(String, usize)>,
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let encrypted_result = app_state
+        .session_service
+        .get_computation_result(session_id, ComputationId(computation_id))
+        .await?;
+    let etag = format!("\"{}\"", content_hash(&encrypted_result));
+    Ok(Blob(encrypted_result).with_etag(etag, &headers))
+}
+
+#[instrument(level = "info", skip(app_state, data))]
+pub async fn add_computation_data(
+    Path((id, computation_id, client_id)): Path<(String, usize, usize)>,
+    State(app_state): State<AppState>,
+    Blob(data): Blob,
+) -> Result<JsonResponse<()>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    app_state
+        .session_service
+        .add_computation_data(
+            session_id,
+            ComputationId(computation_id),
+            ClientId(client_id),
+            data,
+        )
+        .await?;
+    Ok(JsonResponse(()))
+}
+
+#[instrument(level = "info", skip(app_state, dec_share))]
+pub async fn add_computation_dec_share(
+    Path((id, computation_id, client_id)): Path<(String, usize, usize)>,
+    State(app_state): State<AppState>,
+    Blob(dec_share): Blob,
+) -> Result<JsonResponse<()>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    app_state
+        .session_service
+        .add_computation_dec_share(
+            session_id,
+            ComputationId(computation_id),
+            ClientId(client_id),
+            dec_share,
+        )
+        .await?;
+    Ok(JsonResponse(()))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn get_computation_dec_share(
+    Path((id, computation_id, client_id)): Path<(String, usize, usize)>,
+    State(app_state): State<AppState>,
+) -> Result<Blob, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let dec_share = app_state
+        .session_service
+        .get_computation_dec_share(
+            session_id,
+            ComputationId(computation_id),
+            ClientId(client_id),
+        )
+        .await?;
+    Ok(Blob(dec_share))
+}
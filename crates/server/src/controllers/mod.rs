@@ -1 +1,3 @@
+pub mod computation;
+pub mod params;
 pub mod session;
@@ -0,0 +1,16 @@
+use axum::extract::State;
+use frog_core::entities::params::ParamsFingerprint;
+use tracing::instrument;
+
+use crate::app_state::AppState;
+use crate::errors::AppError;
+use crate::json_response::JsonResponse;
+
+/// Returns the fingerprint of the Phantom parameters and CRS of the server, for the clients to
+/// check their own configuration against it.
+#[instrument(level = "info", skip(app_state))]
+pub async fn get_params(
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<ParamsFingerprint>, AppError> {
+    Ok(JsonResponse(app_state.session_service.params()))
+}
@@ -1,7 +1,24 @@
-use axum::extract::{Path, State};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::header::ETAG;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
+use frog_common::blob::{is_octet_stream, not_modified, Blob};
+use frog_core::entities::blob::{content_hash, BlobRef};
 use frog_core::entities::client::{ClientEntity, ClientId};
-use frog_core::entities::session::{SessionEntity, SessionId};
+use frog_core::entities::session::{SessionId, SessionSettings, SessionStatus};
+use frog_core::entities::session_query::{
+    SessionCursor, SessionPage, SessionQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+};
+use frog_core::entities::session_summary::SessionSummary;
+use frog_core::errors::CoreError;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
 use tracing::instrument;
 
 use crate::app_state::AppState;
@@ -11,29 +28,192 @@ use crate::json_response::JsonResponse;
 #[instrument(level = "info", skip(app_state))]
 pub async fn create_session(
     State(app_state): State<AppState>,
-    Json(_input): Json<()>,
-) -> Result<JsonResponse<()>, AppError> {
-    app_state.session_service.create().await?;
-    Ok(JsonResponse(()))
+    Json(input): Json<SessionSettings>,
+) -> Result<JsonResponse<SessionId>, AppError> {
+    let session_id = app_state.session_service.create(input).await?;
+    Ok(JsonResponse(session_id))
+}
+
+/// Query parameters accepted when listing sessions.
+#[derive(Debug, Deserialize)]
+pub struct ListSessionsParams {
+    /// Only return sessions in this status, e.g. `WaitingForClients`.
+    pub status: Option<String>,
+    /// Only return sessions created at or after this UNIX timestamp (in seconds).
+    pub created_after: Option<u64>,
+    /// Only return sessions created before this UNIX timestamp (in seconds).
+    pub created_before: Option<u64>,
+    /// Comma-separated `key:value` labels the sessions must carry, e.g. `team:a,env:dev`.
+    pub labels: Option<String>,
+    /// Cursor returned by the previous page.
+    pub cursor: Option<String>,
+    /// Maximum number of sessions to return.
+    pub limit: Option<usize>,
+}
+
+impl TryFrom<ListSessionsParams> for SessionQuery {
+    type Error = CoreError;
+
+    fn try_from(params: ListSessionsParams) -> Result<Self, Self::Error> {
+        let status = params
+            .status
+            .map(|status| SessionStatus::from_str(&status).map_err(CoreError::ValidationFail))
+            .transpose()?;
+
+        let labels = params
+            .labels
+            .iter()
+            .flat_map(|labels| labels.split(','))
+            .filter(|label| !label.is_empty())
+            .map(|label| {
+                label
+                    .split_once(':')
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .ok_or_else(|| CoreError::ValidationFail(format!("Invalid label: {}", label)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SessionQuery {
+            status,
+            created_after: params.created_after.map(timestamp).transpose()?,
+            created_before: params.created_before.map(timestamp).transpose()?,
+            labels,
+            cursor: params
+                .cursor
+                .map(|cursor| SessionCursor::try_from(cursor.as_str()))
+                .transpose()?,
+            limit: params
+                .limit
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(1, MAX_PAGE_SIZE),
+        })
+    }
+}
+
+/// Converts a UNIX timestamp (in seconds) taken from the query string to a `SystemTime`.
+///
+/// # Arguments
+/// - `secs`: Seconds since the UNIX epoch.
+///
+/// # Returns
+/// - `Ok(SystemTime)`: If the timestamp can be represented.
+/// - `Err(CoreError::ValidationFail)`: If the timestamp is out of range.
+fn timestamp(secs: u64) -> Result<SystemTime, CoreError> {
+    UNIX_EPOCH
+        .checked_add(Duration::from_secs(secs))
+        .ok_or_else(|| CoreError::ValidationFail(format!("Invalid timestamp: {}", secs)))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn list_sessions(
+    Query(params): Query<ListSessionsParams>,
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<SessionPage<SessionSummary>>, AppError> {
+    let query = SessionQuery::try_from(params)?;
+    let session_page = app_state.session_service.list_sessions(query).await?;
+    Ok(JsonResponse(session_page))
 }
 
 #[instrument(level = "info", skip(app_state))]
 pub async fn get_session(
     Path(id): Path<String>,
     State(app_state): State<AppState>,
-) -> Result<JsonResponse<SessionEntity>, AppError> {
+) -> Result<JsonResponse<SessionSummary>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let session_summary = app_state.session_service.get_session(session_id).await?;
+    Ok(JsonResponse(session_summary))
+}
+
+#[instrument(level = "info", skip(app_state, headers))]
+pub async fn get_pk(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let pk_ref = app_state.session_service.get_pk_ref(session_id).await?;
+
+    // Blobs are content-addressed, so the stored hash tags the key without loading it.
+    let etag = format!("\"{}\"", pk_ref.hash);
+    if let Some(response) = not_modified(&etag, &headers) {
+        return Ok(response);
+    }
+    let pk = app_state.session_service.load_pk(&pk_ref).await?;
+    Ok(([(ETAG, etag)], Blob(pk)).into_response())
+}
+
+#[instrument(level = "info", skip(app_state, headers))]
+pub async fn get_result(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let result_refs = app_state
+        .session_service
+        .get_result_refs(session_id)
+        .await?;
+
+    let etag = result_etag(&result_refs);
+    if let Some(response) = not_modified(&etag, &headers) {
+        return Ok(response);
+    }
+    let encrypted_result = app_state.session_service.load_result(&result_refs).await?;
+    Ok(([(ETAG, etag)], Blob(encrypted_result)).into_response())
+}
+
+/// Tags an encrypted result with the hash of the stored hashes of its ciphertexts, in order.
+fn result_etag(result_refs: &[BlobRef]) -> String {
+    let hashes = result_refs
+        .iter()
+        .map(|result_ref| result_ref.hash.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("\"{}\"", content_hash(hashes.as_bytes()))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn session_events(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
     let session_id = SessionId::try_from(id.as_str())?;
-    let session_entity = app_state.session_service.get_session(session_id).await?;
-    Ok(JsonResponse(session_entity))
+    let events = app_state.session_service.events(session_id).await?;
+    Ok(
+        Sse::new(events.map(|event| Event::default().json_data(event)))
+            .keep_alive(KeepAlive::default()),
+    )
 }
 
-#[instrument(level = "info", skip(app_state, input))]
+#[instrument(level = "info", skip(app_state))]
+pub async fn abort_session(
+    Path(id): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<JsonResponse<()>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    app_state.session_service.abort(session_id).await?;
+    Ok(JsonResponse(()))
+}
+
+/// Joins a session with the key shares of a client.
+///
+/// The client is read as `bincode` when sent as `application/octet-stream`, and as JSON
+/// otherwise, for older clients.
+#[instrument(level = "info", skip(app_state, headers, body))]
 pub async fn join_session(
     Path(id): Path<String>,
     State(app_state): State<AppState>,
-    Json(input): Json<ClientEntity>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> Result<JsonResponse<()>, AppError> {
     let session_id = SessionId::try_from(id.as_str())?;
+    let input: ClientEntity = if is_octet_stream(&headers) {
+        bincode::deserialize(&body)
+            .map_err(|e| CoreError::ValidationFail(format!("Invalid client: {}", e)))?
+    } else {
+        let Json(input) = Json::from_bytes(&body).map_err(AppError::JsonRejection)?;
+        input
+    };
     app_state.session_service.join(session_id, input).await?;
     Ok(JsonResponse(()))
 }
@@ -42,7 +222,7 @@ pub async fn join_session(
 pub async fn bootstrap_client(
     Path((id, client_id)): Path<(String, usize)>,
     State(app_state): State<AppState>,
-    Json(bs_key): Json<Vec<u8>>,
+    Blob(bs_key): Blob,
 ) -> Result<JsonResponse<()>, AppError> {
     let session_id = SessionId::try_from(id.as_str())?;
     app_state
@@ -56,7 +236,7 @@ pub async fn bootstrap_client(
 pub async fn add_data(
     Path((id, client_id)): Path<(String, usize)>,
     State(app_state): State<AppState>,
-    Json(data): Json<Vec<u8>>,
+    Blob(data): Blob,
 ) -> Result<JsonResponse<()>, AppError> {
     let session_id = SessionId::try_from(id.as_str())?;
     app_state
@@ -65,3 +245,146 @@ pub async fn add_data(
         .await?;
     Ok(JsonResponse(()))
 }
+
+#[instrument(level = "info", skip(app_state, dec_share))]
+pub async fn add_dec_share(
+    Path((id, client_id)): Path<(String, usize)>,
+    State(app_state): State<AppState>,
+    Blob(dec_share): Blob,
+) -> Result<JsonResponse<()>, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    app_state
+        .session_service
+        .add_dec_share(session_id, ClientId(client_id), dec_share)
+        .await?;
+    Ok(JsonResponse(()))
+}
+
+#[instrument(level = "info", skip(app_state))]
+pub async fn get_dec_share(
+    Path((id, client_id)): Path<(String, usize)>,
+    State(app_state): State<AppState>,
+) -> Result<Blob, AppError> {
+    let session_id = SessionId::try_from(id.as_str())?;
+    let dec_share = app_state
+        .session_service
+        .get_dec_share(session_id, ClientId(client_id))
+        .await?;
+    Ok(Blob(dec_share))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> ListSessionsParams {
+        ListSessionsParams {
+            status: None,
+            created_after: None,
+            created_before: None,
+            labels: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+
+    fn is_validation_fail(result: Result<SessionQuery, CoreError>) -> bool {
+        matches!(result, Err(CoreError::ValidationFail(_)))
+    }
+
+    #[test]
+    fn converts_every_filter() {
+        let cursor = SessionCursor {
+            created_at: UNIX_EPOCH + Duration::from_secs(5),
+            id: SessionId::generate(),
+        };
+        let query = SessionQuery::try_from(ListSessionsParams {
+            status: Some("Done".to_string()),
+            created_after: Some(10),
+            created_before: Some(20),
+            labels: Some("team:a,,env:dev".to_string()),
+            cursor: Some(cursor.to_string()),
+            limit: Some(10),
+        })
+        .unwrap();
+
+        assert_eq!(query.status, Some(SessionStatus::Done));
+        assert_eq!(
+            query.created_after,
+            Some(UNIX_EPOCH + Duration::from_secs(10))
+        );
+        assert_eq!(
+            query.created_before,
+            Some(UNIX_EPOCH + Duration::from_secs(20))
+        );
+        assert_eq!(query.labels.len(), 2);
+        assert_eq!(query.labels["team"], "a");
+        assert_eq!(query.labels["env"], "dev");
+        assert_eq!(query.cursor, Some(cursor));
+        assert_eq!(query.limit, 10);
+    }
+
+    #[test]
+    fn clamps_the_limit() {
+        let query = SessionQuery::try_from(params()).unwrap();
+        assert_eq!(query.limit, DEFAULT_PAGE_SIZE);
+
+        let query = SessionQuery::try_from(ListSessionsParams {
+            limit: Some(0),
+            ..params()
+        })
+        .unwrap();
+        assert_eq!(query.limit, 1);
+
+        let query = SessionQuery::try_from(ListSessionsParams {
+            limit: Some(MAX_PAGE_SIZE + 1),
+            ..params()
+        })
+        .unwrap();
+        assert_eq!(query.limit, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn rejects_bad_labels() {
+        for labels in ["team", "team:a,env"] {
+            assert!(is_validation_fail(SessionQuery::try_from(
+                ListSessionsParams {
+                    labels: Some(labels.to_string()),
+                    ..params()
+                }
+            )));
+        }
+    }
+
+    #[test]
+    fn rejects_bad_status_and_cursor() {
+        assert!(is_validation_fail(SessionQuery::try_from(
+            ListSessionsParams {
+                status: Some("Running".to_string()),
+                ..params()
+            }
+        )));
+        assert!(is_validation_fail(SessionQuery::try_from(
+            ListSessionsParams {
+                cursor: Some("garbage".to_string()),
+                ..params()
+            }
+        )));
+    }
+
+    #[test]
+    fn rejects_timestamps_that_overflow() {
+        assert!(is_validation_fail(SessionQuery::try_from(
+            ListSessionsParams {
+                created_after: Some(u64::MAX),
+                ..params()
+            }
+        )));
+        assert!(is_validation_fail(SessionQuery::try_from(
+            ListSessionsParams {
+                created_before: Some(u64::MAX),
+                ..params()
+            }
+        )));
+    }
+}
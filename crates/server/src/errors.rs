@@ -72,10 +72,24 @@ impl IntoResponse for AppError {
                 // Session-related errors are internal server errors.
                 (StatusCode::INTERNAL_SERVER_ERROR, error)
             }
-            AppError::CoreError(error) => {
-                // Core application logic errors are internal server errors.
-                (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
-            }
+            AppError::CoreError(error) => match error {
+                CoreError::ParseIdError(_) | CoreError::ValidationFail(_) => {
+                    // Invalid input or validation failures are client errors.
+                    (StatusCode::BAD_REQUEST, error.to_string())
+                }
+                CoreError::NotFound => {
+                    // The requested session or client does not exist.
+                    (StatusCode::NOT_FOUND, error.to_string())
+                }
+                CoreError::Conflict(_) => {
+                    // The session kept being updated concurrently, the client may retry.
+                    (StatusCode::CONFLICT, error.to_string())
+                }
+                _ => {
+                    // Other core application logic errors are internal server errors.
+                    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+                }
+            },
             e => {
                 // Log unexpected errors.
                 tracing::error!(%e, "Unhandled application error");
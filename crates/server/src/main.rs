@@ -1,15 +1,19 @@
+use std::process::exit;
 use std::sync::Arc;
 use std::time::Duration;
 
 use deadpool_diesel::postgres::Pool;
 use deadpool_diesel::{Manager, Runtime};
 use diesel_migrations::MigrationHarness;
+use frog_adapter::in_memory::session_event::SessionEventInMemoryBus;
 use frog_adapter::postgres::session_db::{SessionDBRepository, MIGRATIONS};
+use frog_adapter::postgres::session_event_listener::SessionEventListener;
 use frog_adapter::worker::WorkerAdapter;
 use frog_common::cli_args::CliArgs;
 use frog_common::kill_signals;
 use frog_common::loggers::telemetry::init_telemetry;
 use frog_core::ports::session::SessionPort;
+use frog_core::ports::session_event::SessionEventPort;
 use frog_core::ports::worker::WorkerPort;
 use frog_server::app_state::AppState;
 use frog_server::options::Options;
@@ -17,12 +21,11 @@ use frog_server::routes::routes;
 use frog_server::services::session::SessionService;
 use opentelemetry::global;
 use phantom::crs::Crs;
-use phantom::param::Param;
-use phantom::utils::{pad_seed_to_32_bytes, I_2P_60};
-use phantom_zone_evaluator::boolean::fhew::prelude::{DecompositionParam, Modulus};
+use phantom::param::ParamPreset;
+use phantom::utils::pad_seed_to_32_bytes;
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
@@ -36,8 +39,18 @@ async fn main() {
         options.log.level.as_str(),
     );
 
+    // Set up the Phantom parameters for encryption, rejecting unknown presets.
+    let param_preset = match ParamPreset::from_config(&options.phantom_server.param_preset) {
+        Ok(param_preset) => param_preset,
+        Err(err) => {
+            error!("{}", err);
+            eprintln!("Failed to load config: {}", err);
+            exit(1);
+        }
+    };
+
     // Start the server as a separate asynchronous task.
-    let server_task = tokio::spawn(serve(options));
+    let server_task = tokio::spawn(serve(options, param_preset));
 
     // Wait for the server to finish shutting down
     tokio::try_join!(server_task).expect("Failed to run server");
@@ -49,7 +62,7 @@ async fn main() {
 }
 
 /// Frog Server.
-pub async fn serve(options: Options) {
+pub async fn serve(options: Options, param_preset: &'static ParamPreset) {
     // Initialize the database connection pool.
     let manager = Manager::new(&options.pg.url, Runtime::Tokio1);
     let pool = Pool::builder(manager)
@@ -70,17 +83,23 @@ pub async fn serve(options: Options) {
     .expect("Failed to connect to the database")
     .expect("Failed to migrate the database");
 
-    // Parse CRS seed from configuration and ensure it's 32 bytes long.
-    let crs_seed = pad_seed_to_32_bytes(options.phantom_server.crs_seed.as_bytes());
-
-    // Initialize the CRS and Phantom client parameters.
-    let crs = Crs::new(crs_seed);
+    // Use the same CRS for every session only if a seed is configured, e.g. in tests.
+    let crs = options
+        .phantom_server
+        .crs_seed
+        .as_ref()
+        .map(|crs_seed| Crs::new(pad_seed_to_32_bytes(crs_seed.as_bytes())));
 
-    // Set up the Phantom parameters for encryption.
-    let phantom_param = initialize_phantom_parameters();
+    // Initialize the storage holding key material and ciphertexts.
+    let blob_store = options
+        .blob_store
+        .build()
+        .await
+        .expect("Failed to initialize the blob store");
 
     // Initialize the SessionPort implementation (database repository).
-    let session_port: Arc<dyn SessionPort + Send + Sync> = Arc::new(SessionDBRepository::new(pool));
+    let session_port: Arc<dyn SessionPort + Send + Sync> =
+        Arc::new(SessionDBRepository::new(pool, blob_store));
 
     // Set up the worker adapter for background task handling.
     let worker_adapter: Arc<dyn WorkerPort + Send + Sync> = Arc::new(
@@ -92,21 +111,25 @@ pub async fn serve(options: Options) {
         .await,
     );
 
+    // Relay the session events notified by the database, whoever changed the session.
+    let session_event_bus = SessionEventInMemoryBus::new();
+    SessionEventListener::spawn(&options.pg.url, session_event_bus.clone())
+        .await
+        .expect("Failed to listen for session events");
+    let session_events: Arc<dyn SessionEventPort + Send + Sync> = Arc::new(session_event_bus);
+
     // Create and initialize the SessionService, which coordinates session operations.
     let session_service = Arc::new(SessionService::new(
         session_port,
-        phantom_param,
+        param_preset,
         crs,
-        options.phantom_server.participant_number,
         worker_adapter,
+        session_events,
+        options.server.max_computations,
     ));
 
-    // Reset the session.
-    session_service.delete().await.unwrap();
-    session_service.create().await.unwrap();
-
     // Configure HTTP routes with middleware for tracing and request timeout.
-    let routes = routes(AppState::new(session_service)).layer((
+    let routes = routes(AppState::new(session_service), options.server.max_body_size).layer((
         TraceLayer::new_for_http(),
         TimeoutLayer::new(Duration::from_secs(5 * 60)), // Ensure requests don't hang indefinitely.
     ));
@@ -126,18 +149,3 @@ pub async fn serve(options: Options) {
         .await
         .unwrap();
 }
-
-/// Configures the encryption parameters for the Phantom library.
-///
-/// # Returns
-/// A `Param` structure containing the encryption parameters.
-fn initialize_phantom_parameters() -> Param {
-    Param {
-        param: I_2P_60,
-        ring_packing_modulus: Some(Modulus::Prime(2305843009213554689)),
-        ring_packing_auto_decomposition_param: DecompositionParam {
-            log_base: 20,
-            level: 1,
-        },
-    }
-}
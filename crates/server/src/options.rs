@@ -1,4 +1,6 @@
+use frog_adapter::blob_store::config::BlobStoreConfig;
 use frog_common::options::{default_log, Log};
+use phantom::param::DEFAULT_PRESET;
 use serde::Deserialize;
 
 /// Configuration options for the application.
@@ -14,6 +16,8 @@ pub struct Options {
     pub worker: Worker,
     /// Specifies the configuration of database will be connected.
     pub pg: DBConfig,
+    /// Configuration of the storage holding key material and ciphertexts.
+    pub blob_store: BlobStoreConfig,
     /// The endpoint for the exporter.
     pub exporter_endpoint: String,
     /// The name of the service.
@@ -32,15 +36,40 @@ pub struct Server {
     pub port: u16,
     /// URL for the server.
     pub url: String,
+    /// Maximum size in bytes of a key share, ciphertext or decryption share sent to the server.
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
+    /// Maximum number of additional computations a session can run, each one storing its own
+    /// inputs and result.
+    #[serde(default = "default_max_computations")]
+    pub max_computations: usize,
+}
+
+/// Default maximum size of a binary request body, enough for the bootstrapping key shares of
+/// the supported presets.
+fn default_max_body_size() -> usize {
+    32 * 1024 * 1024
+}
+
+/// Default maximum number of additional computations of a session.
+fn default_max_computations() -> usize {
+    64
 }
 
 /// Represents phantom server configuration.
 #[derive(Debug, Deserialize, Clone)]
 pub struct PhantomServer {
-    /// Crs Seed
-    pub crs_seed: String,
-    /// Participant number
-    pub participant_number: usize,
+    /// Seed of a CRS shared by every session, for deterministic tests only. Each session gets a
+    /// fresh CRS when it is not set.
+    pub crs_seed: Option<String>,
+    /// Name of the Phantom parameter preset, see `phantom::param::PRESETS`.
+    #[serde(default = "default_param_preset")]
+    pub param_preset: String,
+}
+
+/// Default Phantom parameter preset.
+fn default_param_preset() -> String {
+    DEFAULT_PRESET.to_string()
 }
 
 /// Represents worker configuration.
@@ -9,21 +9,59 @@ use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::timeout::TimeoutLayer;
 
 use crate::app_state::AppState;
+use crate::controllers::computation::{
+    add_computation_data, add_computation_dec_share, create_computation, get_computation,
+    get_computation_dec_share, get_computation_result, list_computations,
+};
+use crate::controllers::params::get_params;
 use crate::controllers::session::{
-    add_data, bootstrap_client, create_session, get_session, join_session,
+    abort_session, add_data, add_dec_share, bootstrap_client, create_session, get_dec_share,
+    get_pk, get_result, get_session, join_session, list_sessions, session_events,
 };
 
-pub fn routes(app_state: AppState) -> Router {
+/// Builds the routes of the server.
+///
+/// # Arguments
+/// - `app_state`: The state shared by the handlers.
+/// - `max_body_size`: Maximum size in bytes of the binary request bodies, e.g. key shares.
+pub fn routes(app_state: AppState, max_body_size: usize) -> Router {
     Router::new()
         .route("/", get(root))
         .nest(
             "/v1",
             Router::new()
+                .route("/params", get(get_params))
                 .nest(
                     "/sessions",
                     Router::new()
-                        .route("/", post(create_session))
-                        .route("/{id}", get(get_session).put(join_session))
+                        .route("/", get(list_sessions).post(create_session))
+                        .route(
+                            "/{id}",
+                            get(get_session).put(join_session).delete(abort_session),
+                        )
+                        .route("/{id}/events", get(session_events))
+                        .route("/{id}/pk", get(get_pk))
+                        .route("/{id}/result", get(get_result))
+                        .route(
+                            "/{id}/computations",
+                            get(list_computations).post(create_computation),
+                        )
+                        .route("/{id}/computations/{computation_id}", get(get_computation))
+                        .route(
+                            "/{id}/computations/{computation_id}/result",
+                            get(get_computation_result),
+                        )
+                        .nest(
+                            "/{id}/computations/{computation_id}/clients/{client_id}",
+                            Router::new()
+                                .route("/data", post(add_computation_data))
+                                .route(
+                                    "/dec_share",
+                                    get(get_computation_dec_share).put(add_computation_dec_share),
+                                )
+                                .layer(DefaultBodyLimit::disable())
+                                .layer(RequestBodyLimitLayer::new(max_body_size)),
+                        )
                         .nest(
                             "/{id}",
                             Router::new().nest(
@@ -33,8 +71,9 @@ pub fn routes(app_state: AppState) -> Router {
                                     Router::new()
                                         .route("/bootstrap", put(bootstrap_client))
                                         .route("/data", post(add_data))
+                                        .route("/dec_share", get(get_dec_share).put(add_dec_share))
                                         .layer(DefaultBodyLimit::disable())
-                                        .layer(RequestBodyLimitLayer::new(100 * 1000 * 1000)),
+                                        .layer(RequestBodyLimitLayer::new(max_body_size)),
                                 ),
                             ),
                         ),
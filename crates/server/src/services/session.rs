@@ -1,73 +1,180 @@
 use std::sync::Arc;
 
-use frog_core::entities::client::{ClientEntity, ClientId};
-use frog_core::entities::session::{SessionEntity, SessionId, SessionStatus};
-use frog_core::ports::session::SessionPort;
+use frog_core::entities::blob::BlobRef;
+use frog_core::entities::client::{ClientArtifact, ClientEntity, ClientId};
+use frog_core::entities::computation::{ComputationEntity, ComputationId, ComputationStatus};
+use frog_core::entities::params::ParamsFingerprint;
+use frog_core::entities::session::{SessionEntity, SessionId, SessionSettings, SessionStatus};
+use frog_core::entities::session_event::SessionEvent;
+use frog_core::entities::session_query::{SessionPage, SessionQuery};
+use frog_core::entities::session_summary::SessionSummary;
+use frog_core::errors::CoreError;
+use frog_core::ports::session::{update_with_retry, SessionPort};
+use frog_core::ports::session_event::{SessionEventPort, SessionEventStream};
 use frog_core::ports::worker::WorkerPort;
+use futures::{future, stream, StreamExt};
 use phantom::crs::Crs;
+use phantom::function::FheFunction;
 use phantom::ops::Ops;
-use phantom::param::Param;
-use tokio::sync::Mutex;
+use phantom::param::ParamPreset;
 
 use crate::errors::AppError;
 use crate::errors::AppError::{SessionError, UnexpectedError};
 
+/// Maximum lifetime a session can be created with: 30 days.
+const MAX_SESSION_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of values each client can provide.
+const MAX_INPUT_LEN: usize = 1024;
+
 /// Service for managing session-related operations.
 pub struct SessionService {
     /// Session repository interface for persistence operations.
     session: Arc<dyn SessionPort + Sync + Send>,
     /// Phantom protocol parameters.
-    phantom_param: Param,
-    /// Common reference string (CRS).
-    crs: Crs,
-    /// Number of participants required for a session.
-    participant_number: usize,
+    param_preset: &'static ParamPreset,
+    /// Common reference string (CRS) shared by every session, only set in deterministic mode.
+    /// Otherwise each session gets a fresh CRS.
+    crs: Option<Crs>,
+    /// Fingerprint of the parameters and CRS, published to the clients.
+    params: ParamsFingerprint,
     /// Interface to interact with worker tasks.
     worker_port: Arc<dyn WorkerPort + Send + Sync>,
-
-    /// Mutex to ensure safe, synchronized bootstrap operations.
-    bootstrap_mutex: Arc<Mutex<bool>>,
-    /// Mutex to ensure safe, synchronized data addition operations.
-    add_data_mutex: Arc<Mutex<bool>>,
+    /// Interface to follow the status changes of sessions.
+    session_events: Arc<dyn SessionEventPort + Send + Sync>,
+    /// Maximum number of additional computations of a session.
+    max_computations: usize,
 }
 
 impl SessionService {
     /// Constructs a new `SessionService` instance.
     pub fn new(
         session: Arc<dyn SessionPort + Sync + Send>,
-        phantom_param: Param,
-        crs: Crs,
-        participant_number: usize,
+        param_preset: &'static ParamPreset,
+        crs: Option<Crs>,
         worker_port: Arc<dyn WorkerPort + Send + Sync>,
+        session_events: Arc<dyn SessionEventPort + Send + Sync>,
+        max_computations: usize,
     ) -> Self {
         Self {
             session,
-            phantom_param,
+            param_preset,
             crs,
-            participant_number,
+            params: ParamsFingerprint::new(param_preset, crs.as_ref()),
             worker_port,
-            bootstrap_mutex: Default::default(),
-            add_data_mutex: Default::default(),
+            session_events,
+            max_computations,
         }
     }
 
     /// Creates a new session in the system.
-    pub async fn create(&self) -> Result<(), AppError> {
-        self.session
+    ///
+    /// # Arguments
+    /// - `settings`: The settings requested for the new session.
+    ///
+    /// # Returns
+    /// - The ID generated for the new session.
+    /// - An error if the settings are not supported by the Phantom parameters or the computation
+    ///   is unknown.
+    pub async fn create(&self, settings: SessionSettings) -> Result<SessionId, AppError> {
+        // The key shares can only be aggregated for the number of parties the parameters support.
+        if settings.participant_number != self.param_preset.param.total_shares {
+            return Err(CoreError::ValidationFail(format!(
+                "Unsupported number of participants: {}, expected {}",
+                settings.participant_number, self.param_preset.param.total_shares
+            ))
+            .into());
+        }
+
+        // Only the functions of the registry can be computed, each client providing one input.
+        let function = FheFunction::by_name(&settings.computation).ok_or_else(|| {
+            CoreError::ValidationFail(format!(
+                "Unknown computation '{}', expected one of: {}",
+                settings.computation,
+                FheFunction::known_names()
+            ))
+        })?;
+        if !function.accepts_inputs(settings.participant_number) {
+            return Err(CoreError::ValidationFail(format!(
+                "Computation '{}' cannot take {} inputs, one per participant",
+                function.name, settings.participant_number
+            ))
+            .into());
+        }
+        if !function.accepts_input_type(settings.input_type) {
+            return Err(CoreError::ValidationFail(format!(
+                "Computation '{}' cannot take inputs of type {}",
+                function.name, settings.input_type
+            ))
+            .into());
+        }
+        if settings.input_len == 0 || settings.input_len > MAX_INPUT_LEN {
+            return Err(CoreError::ValidationFail(format!(
+                "Input length must be between 1 and {}",
+                MAX_INPUT_LEN
+            ))
+            .into());
+        }
+
+        if settings.ttl_secs == 0 || settings.ttl_secs > MAX_SESSION_TTL_SECS {
+            return Err(CoreError::ValidationFail(format!(
+                "TTL must be between 1 and {} seconds",
+                MAX_SESSION_TTL_SECS
+            ))
+            .into());
+        }
+
+        if settings.phase_timeout_secs == 0 {
+            return Err(
+                CoreError::ValidationFail("Phase timeout must be positive".to_string()).into(),
+            );
+        }
+
+        let session_id = self
+            .session
             .create(SessionEntity::new(
-                SessionId::try_from("f8e774bd-2f9d-4502-92ca-ac8b9c25868e")?,
-                self.phantom_param,
-                self.crs,
+                SessionId::generate(),
+                settings,
+                self.param_preset,
+                self.crs.unwrap_or_else(Crs::from_entropy),
             ))
             .await?;
-        Ok(())
+        Ok(session_id)
+    }
+
+    /// Returns the fingerprint of the Phantom parameters and CRS new sessions are created with.
+    pub fn params(&self) -> ParamsFingerprint {
+        self.params.clone()
     }
 
     /// Deletes an existing session.
-    pub async fn delete(&self) -> Result<(), AppError> {
-        self.session
-            .delete(SessionId::try_from("f8e774bd-2f9d-4502-92ca-ac8b9c25868e")?)
-            .await?;
+    pub async fn delete(&self, session_id: SessionId) -> Result<(), AppError> {
+        self.session.delete(session_id).await?;
+        Ok(())
+    }
+
+    /// Aborts a session that has not ended yet.
+    ///
+    /// The session moves to the `Aborted` status and its key material and ciphertexts are purged.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session to abort.
+    ///
+    /// # Returns
+    /// - A validation error if the session already ended.
+    pub async fn abort(&self, session_id: SessionId) -> Result<(), AppError> {
+        update_with_retry(&*self.session, session_id.clone(), |session_entity| {
+            if session_entity.status.is_terminal() {
+                return Err(AppError::from(CoreError::ValidationFail(format!(
+                    "Session {} already ended in status {}",
+                    session_id, session_entity.status
+                ))));
+            }
+
+            session_entity.terminate(SessionStatus::Aborted);
+            Ok(true)
+        })
+        .await?;
         Ok(())
     }
 
@@ -78,62 +185,186 @@ impl SessionService {
     /// - `client_entity`: Information about the client attempting to join.
     ///
     /// # Returns
+    /// - A validation error if the client provides anything but its public key and ring-packing
+    ///   key shares, which are the only artifacts accepted at join time.
+    /// - A validation error naming the client if its public key or ring-packing key share cannot
+    ///   be deserialized.
     /// - An error if the session is full or if any operations fail.
     pub async fn join(
         &self,
         session_id: SessionId,
         client_entity: ClientEntity,
     ) -> Result<(), AppError> {
-        let mut session_entity = self.session.get(session_id.clone()).await?;
-
-        // Check if the maximum number of participants is reached.
-        if session_entity.client_info.len() >= self.participant_number {
-            return Err(SessionError(format!(
-                "Number of clients exceeded the limit: {}",
-                self.participant_number
-            )));
+        // Later artifacts go through their own phase, so they cannot be smuggled in at join time.
+        if !client_entity.bs_key_share.is_empty()
+            || !client_entity.encrypted_data.is_empty()
+            || !client_entity.dec_share.is_empty()
+        {
+            return Err(AppError::from(CoreError::ValidationFail(format!(
+                "Client {} can only provide its public key and ring-packing key shares when \
+                 joining",
+                client_entity.id
+            ))));
         }
+        let client_entity = ClientEntity::new(
+            client_entity.id,
+            client_entity.pk_share,
+            client_entity.rp_key_share,
+        );
 
-        let phantom_server = session_entity
-            .phantom_server
-            .as_mut()
-            .ok_or_else(|| UnexpectedError("Phantom server not initialized".to_string()))?;
+        // Retried as a whole, so concurrent joins cannot overwrite each other's client info.
+        update_with_retry(&*self.session, session_id, |session_entity| {
+            ensure_status(session_entity, SessionStatus::WaitingForClients)?;
 
-        // Add client information to the session.
-        session_entity
-            .client_info
-            .insert(client_entity.id.clone(), client_entity.clone());
+            let participant_number = session_entity.settings.participant_number;
 
-        // If all participants have joined, finalize PK and RP key aggregation.
-        if session_entity.client_info.len() == self.participant_number {
-            let pk_shares = session_entity
-                .client_info
-                .values()
-                .map(|client| client.pk_share.clone())
-                .map(|bytes| phantom_server.deserialize_pk_share(&bytes).unwrap())
-                .collect::<Vec<_>>();
-            phantom_server.aggregate_pk_shares(&pk_shares);
+            // Check if the maximum number of participants is reached.
+            if session_entity.client_info.len() >= participant_number {
+                return Err(SessionError(format!(
+                    "Number of clients exceeded the limit: {}",
+                    participant_number
+                )));
+            }
+
+            let phantom_server = session_entity
+                .phantom_server
+                .as_mut()
+                .ok_or_else(|| UnexpectedError("Phantom server not initialized".to_string()))?;
+
+            // Reject key shares that could not be aggregated before accepting the client.
+            phantom_server
+                .deserialize_pk_share(&client_entity.pk_share)
+                .map_err(|err| invalid_key_share(&client_entity.id, "public key", err))?;
+            phantom_server
+                .deserialize_rp_key_share(&client_entity.rp_key_share)
+                .map_err(|err| invalid_key_share(&client_entity.id, "ring-packing key", err))?;
 
-            let rp_key_shares = session_entity
+            // Add client information to the session.
+            session_entity
                 .client_info
-                .values()
-                .map(|client| client.rp_key_share.clone())
-                .map(|bytes| phantom_server.deserialize_rp_key_share(&bytes).unwrap())
-                .collect::<Vec<_>>();
-            phantom_server.aggregate_rp_key_shares(&rp_key_shares);
-
-            session_entity.pk = phantom_server.serialize_pk()?;
-            session_entity.status = SessionStatus::WaitingForBootstrap;
-        }
+                .insert(client_entity.id.clone(), client_entity.clone());
+
+            // If all participants have joined, finalize PK and RP key aggregation.
+            if session_entity.client_info.len() == participant_number {
+                let pk_shares = session_entity
+                    .client_info
+                    .values()
+                    .map(|client| {
+                        phantom_server
+                            .deserialize_pk_share(&client.pk_share)
+                            .map_err(|err| invalid_key_share(&client.id, "public key", err))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                phantom_server.aggregate_pk_shares(&pk_shares);
+
+                let rp_key_shares = session_entity
+                    .client_info
+                    .values()
+                    .map(|client| {
+                        phantom_server
+                            .deserialize_rp_key_share(&client.rp_key_share)
+                            .map_err(|err| invalid_key_share(&client.id, "ring-packing key", err))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                phantom_server.aggregate_rp_key_shares(&rp_key_shares);
 
-        self.session.update(session_id, session_entity).await?;
+                session_entity.pk = phantom_server.serialize_pk()?;
+                session_entity.transition(SessionStatus::WaitingForBootstrap);
+            }
+
+            Ok(true)
+        })
+        .await?;
         Ok(())
     }
 
-    /// Retrieves session details by session ID.
-    pub async fn get_session(&self, session_id: SessionId) -> Result<SessionEntity, AppError> {
-        let session_entity = self.session.get(session_id).await?;
-        Ok(session_entity)
+    /// Retrieves the summary of a session, without loading any key material or ciphertext.
+    pub async fn get_session(&self, session_id: SessionId) -> Result<SessionSummary, AppError> {
+        let session_summary = self.session.get_summary(session_id).await?;
+        Ok(session_summary)
+    }
+
+    /// Retrieves the reference to the aggregated public key of a session, without loading it.
+    ///
+    /// # Returns
+    /// - The reference to the public key, whose hash identifies its content.
+    /// - A not found error if not every participant joined the session yet.
+    pub async fn get_pk_ref(&self, session_id: SessionId) -> Result<BlobRef, AppError> {
+        let pk_ref = self
+            .session
+            .get_pk_ref(session_id)
+            .await?
+            .ok_or(CoreError::NotFound)?;
+        Ok(pk_ref)
+    }
+
+    /// Loads the aggregated public key of a session from its reference.
+    ///
+    /// # Returns
+    /// - The serialized public key.
+    pub async fn load_pk(&self, pk_ref: &BlobRef) -> Result<Vec<u8>, AppError> {
+        let pk = self.session.get_blob(pk_ref).await?;
+        Ok(pk)
+    }
+
+    /// Retrieves the references to the ciphertexts of the encrypted result of a session, without
+    /// loading them.
+    ///
+    /// # Returns
+    /// - The references to the ciphertexts, in order.
+    /// - A not found error if the result was not computed yet.
+    pub async fn get_result_refs(&self, session_id: SessionId) -> Result<Vec<BlobRef>, AppError> {
+        let result_refs = self.session.get_result_refs(session_id).await?;
+        if result_refs.is_empty() {
+            return Err(CoreError::NotFound.into());
+        }
+        Ok(result_refs)
+    }
+
+    /// Loads the encrypted result of a session from the references to its ciphertexts.
+    ///
+    /// # Returns
+    /// - The ciphertexts of the result, serialized together with `bincode`.
+    pub async fn load_result(&self, result_refs: &[BlobRef]) -> Result<Vec<u8>, AppError> {
+        let mut encrypted_result = Vec::with_capacity(result_refs.len());
+        for result_ref in result_refs {
+            encrypted_result.push(self.session.get_blob(result_ref).await?);
+        }
+        Ok(bincode::serialize(&encrypted_result)?)
+    }
+
+    /// Follows the status changes of a session.
+    ///
+    /// The stream starts with the current status of the session and ends once the session
+    /// reaches a terminal status.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session to follow.
+    pub async fn events(&self, session_id: SessionId) -> Result<SessionEventStream, AppError> {
+        // Subscribe before reading the session, so that no change is missed in between.
+        let events = self.session_events.subscribe(session_id.clone()).await?;
+        let session_summary = self.session.get_summary(session_id).await?;
+        let current_version = session_summary.version;
+
+        let events = stream::once(future::ready(SessionEvent::from(&session_summary)))
+            .chain(events.filter(move |event| future::ready(event.version > current_version)))
+            .scan(false, |ended, event| {
+                if *ended {
+                    return future::ready(None);
+                }
+                *ended = event.status.is_terminal();
+                future::ready(Some(event))
+            });
+        Ok(events.boxed())
+    }
+
+    /// Searches for sessions matching the given query.
+    pub async fn list_sessions(
+        &self,
+        query: SessionQuery,
+    ) -> Result<SessionPage<SessionSummary>, AppError> {
+        let session_page = self.session.list(query).await?;
+        Ok(session_page)
     }
 
     /// Handles adding bootstrapping key from a client for a session.
@@ -148,34 +379,18 @@ impl SessionService {
         client_id: ClientId,
         bs_key: Vec<u8>,
     ) -> Result<(), AppError> {
-        let _guard = self.bootstrap_mutex.lock().await;
-
-        let mut session_entity = self.session.get(session_id.clone()).await?;
-        let client_entity = session_entity
-            .client_info
-            .get_mut(&client_id)
-            .ok_or_else(|| SessionError(format!("Client not found: {}", client_id)))?;
-
-        client_entity.bs_key_share = bs_key;
-
-        self.session
-            .update(session_id.clone(), session_entity.clone())
+        let completed = self
+            .session
+            .set_client_artifact(
+                session_id.clone(),
+                client_id,
+                ClientArtifact::BsKeyShare,
+                bs_key,
+            )
             .await?;
 
-        // Aggregate bootstrap keys if all participants have provided them.
-        let bs_key_shares = session_entity
-            .client_info
-            .values()
-            .filter_map(|client| {
-                if !client.bs_key_share.is_empty() {
-                    Some(client.bs_key_share.clone())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        if bs_key_shares.len() == self.participant_number {
+        // Aggregate bootstrap keys once the last participant provided them, exactly once.
+        if completed {
             self.worker_port.aggregate_bs_key_shares(session_id).await?;
         }
 
@@ -194,37 +409,293 @@ impl SessionService {
         client_id: ClientId,
         data: Vec<u8>,
     ) -> Result<(), AppError> {
-        let _guard = self.add_data_mutex.lock().await;
+        let completed = self
+            .session
+            .set_client_artifact(
+                session_id.clone(),
+                client_id,
+                ClientArtifact::EncryptedData,
+                data,
+            )
+            .await?;
 
-        let mut session_entity = self.session.get(session_id.clone()).await?;
-        let client_entity = session_entity
-            .client_info
-            .get_mut(&client_id)
-            .ok_or_else(|| SessionError(format!("Client not found: {}", client_id)))?;
+        // Trigger worker computation once the last participant provided its data, exactly once.
+        if completed {
+            self.worker_port.compute_function(session_id).await?;
+        }
 
-        client_entity.encrypted_data = data;
+        Ok(())
+    }
 
+    /// Stores the decryption share of a client, so that the other clients can download it.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session whose result was decrypted.
+    /// - `client_id`: The ID of the client providing the share.
+    /// - `dec_share`: The serialized decryption share of the client.
+    pub async fn add_dec_share(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+        dec_share: Vec<u8>,
+    ) -> Result<(), AppError> {
         self.session
-            .update(session_id.clone(), session_entity.clone())
+            .set_client_artifact(session_id, client_id, ClientArtifact::DecShare, dec_share)
             .await?;
+        Ok(())
+    }
 
-        // Trigger worker computation if all participants have provided data.
-        let all_data = session_entity
-            .client_info
-            .values()
-            .filter_map(|client| {
-                if !client.encrypted_data.is_empty() {
-                    Some(client.encrypted_data.clone())
-                } else {
-                    None
+    /// Retrieves the decryption share of a client.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session whose result was decrypted.
+    /// - `client_id`: The ID of the client that provided the share.
+    ///
+    /// # Returns
+    /// - The serialized decryption share, empty if the client has not provided it yet.
+    pub async fn get_dec_share(
+        &self,
+        session_id: SessionId,
+        client_id: ClientId,
+    ) -> Result<Vec<u8>, AppError> {
+        let client_entity = self.session.get_client(session_id, client_id).await?;
+        Ok(client_entity.dec_share)
+    }
+
+    /// Starts an additional computation of a session, reusing the keys of the session.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session whose keys the computation uses.
+    ///
+    /// # Returns
+    /// - The ID of the new computation, the session itself being the first one.
+    /// - A validation error if the first computation of the session is not done, or if the
+    ///   session already has the maximum number of additional computations.
+    pub async fn create_computation(
+        &self,
+        session_id: SessionId,
+    ) -> Result<ComputationId, AppError> {
+        let mut computation_id = ComputationId::FIRST;
+        update_with_retry(&*self.session, session_id, |session_entity| {
+            ensure_status(session_entity, SessionStatus::Done)?;
+            // Checked within the update, so concurrent requests cannot exceed the limit together.
+            if session_entity.computations.len() >= self.max_computations {
+                return Err(AppError::from(CoreError::ValidationFail(format!(
+                    "Session {} reached the limit of {} additional computations",
+                    session_entity.id, self.max_computations
+                ))));
+            }
+
+            computation_id = session_entity.next_computation_id();
+            session_entity
+                .computations
+                .insert(computation_id, ComputationEntity::new(computation_id));
+            Ok(true)
+        })
+        .await?;
+        Ok(computation_id)
+    }
+
+    /// Retrieves the additional computations of a session, ordered by computation ID.
+    pub async fn list_computations(
+        &self,
+        session_id: SessionId,
+    ) -> Result<Vec<ComputationEntity>, AppError> {
+        let session_entity = self.session.get_without_server(session_id).await?;
+        Ok(session_entity.computations.into_values().collect())
+    }
+
+    /// Retrieves an additional computation of a session.
+    ///
+    /// # Returns
+    /// - A not found error if the session has no such computation.
+    pub async fn get_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<ComputationEntity, AppError> {
+        let mut session_entity = self.session.get_without_server(session_id).await?;
+        let computation_entity = session_entity
+            .computations
+            .remove(&computation_id)
+            .ok_or(CoreError::NotFound)?;
+        Ok(computation_entity)
+    }
+
+    /// Retrieves the encrypted result of an additional computation of a session.
+    ///
+    /// # Returns
+    /// - The ciphertexts of the result, serialized together with `bincode`.
+    /// - A not found error if the computation does not exist or its result was not computed yet.
+    pub async fn get_computation_result(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<Vec<u8>, AppError> {
+        let computation_entity = self.get_computation(session_id, computation_id).await?;
+        if computation_entity.encrypted_result.is_empty() {
+            return Err(CoreError::NotFound.into());
+        }
+        Ok(bincode::serialize(&computation_entity.encrypted_result)?)
+    }
+
+    /// Adds the encrypted input of a client to an additional computation of a session.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session the computation belongs to.
+    /// - `computation_id`: The computation the input is for.
+    /// - `client_id`: The ID of the client providing the input.
+    /// - `data`: The encrypted input, under the public key of the session.
+    pub async fn add_computation_data(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        data: Vec<u8>,
+    ) -> Result<(), AppError> {
+        let mut was_complete = false;
+        let session_entity =
+            update_with_retry(&*self.session, session_id.clone(), |session_entity| {
+                ensure_status(session_entity, SessionStatus::Done)?;
+                if !session_entity.client_info.contains_key(&client_id) {
+                    return Err(SessionError(format!("Client not found: {}", client_id)));
                 }
+
+                let participant_number = session_entity.settings.participant_number;
+                let computation_entity = ensure_computation_status(
+                    session_entity,
+                    computation_id,
+                    ComputationStatus::WaitingForArgument,
+                )?;
+                was_complete = computation_entity.inputs.len() == participant_number;
+                computation_entity
+                    .inputs
+                    .insert(client_id.clone(), data.clone());
+                Ok(true)
             })
-            .collect::<Vec<_>>();
+            .await?;
 
-        if all_data.len() == self.participant_number {
-            self.worker_port.compute_function(session_id).await?;
+        // Trigger worker computation once the last participant provided its input, exactly once.
+        let is_complete = session_entity.computations[&computation_id].inputs.len()
+            == session_entity.settings.participant_number;
+        if !was_complete && is_complete {
+            self.worker_port
+                .compute_computation(session_id, computation_id)
+                .await?;
         }
 
         Ok(())
     }
+
+    /// Stores the decryption share of a client for the result of an additional computation.
+    ///
+    /// # Arguments
+    /// - `session_id`: The session the computation belongs to.
+    /// - `computation_id`: The computation whose result was decrypted.
+    /// - `client_id`: The ID of the client providing the share.
+    /// - `dec_share`: The serialized decryption share of the client.
+    pub async fn add_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+        dec_share: Vec<u8>,
+    ) -> Result<(), AppError> {
+        update_with_retry(&*self.session, session_id, |session_entity| {
+            ensure_status(session_entity, SessionStatus::Done)?;
+            if !session_entity.client_info.contains_key(&client_id) {
+                return Err(SessionError(format!("Client not found: {}", client_id)));
+            }
+
+            let computation_entity =
+                ensure_computation_status(session_entity, computation_id, ComputationStatus::Done)?;
+            computation_entity
+                .dec_shares
+                .insert(client_id.clone(), dec_share.clone());
+            Ok(true)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Retrieves the decryption share of a client for the result of an additional computation.
+    ///
+    /// # Returns
+    /// - The serialized decryption share, empty if the client has not provided it yet.
+    pub async fn get_computation_dec_share(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        client_id: ClientId,
+    ) -> Result<Vec<u8>, AppError> {
+        let mut computation_entity = self.get_computation(session_id, computation_id).await?;
+        Ok(computation_entity
+            .dec_shares
+            .remove(&client_id)
+            .unwrap_or_default())
+    }
+}
+
+/// Builds the error of a key share a client sent that cannot be deserialized.
+///
+/// # Arguments
+/// - `client_id`: The client that sent the share.
+/// - `share`: The key the share is of, e.g. `public key`.
+/// - `err`: The deserialization error.
+fn invalid_key_share(client_id: &ClientId, share: &str, err: bincode::Error) -> AppError {
+    CoreError::ValidationFail(format!(
+        "Invalid {} share of client {}: {}",
+        share, client_id, err
+    ))
+    .into()
+}
+
+/// Ensures a session is in the status an operation expects.
+///
+/// # Arguments
+/// - `session_entity`: The session the operation targets.
+/// - `expected`: The status the session must be in.
+///
+/// # Returns
+/// - A validation error if the session is in any other status, e.g. because it expired.
+fn ensure_status(session_entity: &SessionEntity, expected: SessionStatus) -> Result<(), AppError> {
+    if session_entity.status != expected {
+        return Err(CoreError::ValidationFail(format!(
+            "Session {} is in status {}, expected {}",
+            session_entity.id, session_entity.status, expected
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Ensures an additional computation of a session is in the status an operation expects.
+///
+/// # Arguments
+/// - `session_entity`: The session the computation belongs to.
+/// - `computation_id`: The computation the operation targets.
+/// - `expected`: The status the computation must be in.
+///
+/// # Returns
+/// - The computation, to be changed by the operation.
+/// - A not found error if the session has no such computation.
+/// - A validation error if the computation is in any other status.
+fn ensure_computation_status(
+    session_entity: &mut SessionEntity,
+    computation_id: ComputationId,
+    expected: ComputationStatus,
+) -> Result<&mut ComputationEntity, AppError> {
+    let session_id = session_entity.id.clone();
+    let computation_entity = session_entity
+        .computations
+        .get_mut(&computation_id)
+        .ok_or(CoreError::NotFound)?;
+    if computation_entity.status != expected {
+        return Err(CoreError::ValidationFail(format!(
+            "Computation {} of session {} is in status {}, expected {}",
+            computation_id, session_id, computation_entity.status, expected
+        ))
+        .into());
+    }
+    Ok(computation_entity)
 }
@@ -8,6 +8,10 @@ pub enum AppError {
     #[error("Core error: {0}")]
     CoreError(#[from] CoreError),
 
+    /// The inputs of a computation can never be computed, so retrying is pointless.
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
     /// A generic unexpected error with a custom message.
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
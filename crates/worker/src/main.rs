@@ -1,6 +1,7 @@
 mod options;
 mod routes;
 
+use std::process::exit;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -12,17 +13,22 @@ use frog_adapter::postgres::session_db::SessionDBRepository;
 use frog_common::cli_args::CliArgs;
 use frog_common::kill_signals;
 use frog_common::loggers::telemetry::init_telemetry;
+use frog_common::workers::EXPIRE_SESSIONS_WORKER_IDENTIFIER;
 use frog_core::ports::session::SessionPort;
 use frog_worker::app_state::AppState;
+use frog_worker::services::server_cache::PhantomServerCache;
 use frog_worker::services::session::SessionService;
 use frog_worker::workers::bs_key_shares::BsKeySharesWorker;
+use frog_worker::workers::compute_computation::ComputeComputationWorker;
 use frog_worker::workers::compute_function::ComputeFunctionWorker;
+use frog_worker::workers::expire_sessions::ExpireSessionsWorker;
 use graphile_worker::WorkerOptions;
 use opentelemetry::global;
+use phantom::param::ParamPreset;
 use sqlx::postgres::PgConnectOptions;
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{error, info, warn};
 
 use crate::options::Options;
 use crate::routes::routes;
@@ -39,11 +45,21 @@ async fn main() {
         options.log.level.as_str(),
     );
 
+    // Check the configured Phantom parameters, the sessions are processed with.
+    let param_preset = match ParamPreset::from_config(&options.worker.param_preset) {
+        Ok(param_preset) => param_preset,
+        Err(err) => {
+            error!("{}", err);
+            eprintln!("Failed to load config: {}", err);
+            exit(1);
+        }
+    };
+
     // Start the HTTP server
     let server = tokio::spawn(serve(options.clone()));
 
     // Run background workers
-    run_workers(options).await;
+    run_workers(options, param_preset).await;
 
     // Wait for the server task to complete
     tokio::try_join!(server).expect("Failed to run server");
@@ -99,7 +115,7 @@ pub async fn serve(options: Options) {
 }
 
 /// Configures and runs the background workers for the Frog Worker.
-pub async fn run_workers(options: Options) {
+pub async fn run_workers(options: Options, param_preset: &'static ParamPreset) {
     // Configure PostgreSQL connection options
     let pg_options =
         PgConnectOptions::from_str(&options.pg.url).expect("Invalid PostgreSQL connection string");
@@ -118,9 +134,29 @@ pub async fn run_workers(options: Options) {
         .build()
         .expect("Failed to initialize Deadpool-Diesel pool");
 
+    // Initialize the storage holding key material and ciphertexts.
+    let blob_store = options
+        .blob_store
+        .build()
+        .await
+        .expect("Failed to initialize the blob store");
+
+    match param_preset.security_bits {
+        Some(security_bits) => info!(
+            "Using Phantom parameter preset {} ({} parties, {}-bit security)",
+            param_preset.name, param_preset.total_shares, security_bits
+        ),
+        None => warn!(
+            "Using Phantom parameter preset {} ({} parties), whose security was not vetted",
+            param_preset.name, param_preset.total_shares
+        ),
+    }
+
     // Create the session repository and service
-    let session_port: Arc<dyn SessionPort + Send + Sync> = Arc::new(SessionDBRepository::new(pool));
-    let session_service = Arc::new(SessionService::new(session_port));
+    let session_port: Arc<dyn SessionPort + Send + Sync> =
+        Arc::new(SessionDBRepository::new(pool, blob_store));
+    let server_cache = PhantomServerCache::new(options.worker.server_cache_size);
+    let session_service = Arc::new(SessionService::new(session_port, server_cache));
 
     // Create application state to be shared across workers
     let app_state = AppState::new(session_service);
@@ -132,6 +168,13 @@ pub async fn run_workers(options: Options) {
         .add_extension(app_state)
         .define_job::<BsKeySharesWorker>() // Register the job for aggregate bootstrapping key shares
         .define_job::<ComputeFunctionWorker>() // Register the job for computing functions
+        .define_job::<ComputeComputationWorker>() // Register the job for the later computations
+        .define_job::<ExpireSessionsWorker>() // Register the job for expiring abandoned sessions
+        .with_crontab(&format!(
+            "{} {}",
+            options.worker.expire_sessions_schedule, EXPIRE_SESSIONS_WORKER_IDENTIFIER
+        ))
+        .expect("Invalid session expiry schedule")
         .pg_pool(pg_pool) // Use the SQLx pool for job storage
         .init()
         .await
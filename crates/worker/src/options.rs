@@ -1,4 +1,8 @@
+use std::num::NonZeroUsize;
+
+use frog_adapter::blob_store::config::BlobStoreConfig;
 use frog_common::options::{default_log, Log};
+use phantom::param::DEFAULT_PRESET;
 use serde::Deserialize;
 
 /// Configuration options for the application.
@@ -14,6 +18,8 @@ pub struct Options {
     pub worker: Worker,
     /// Specifies the configuration of database will be connected.
     pub pg: DBConfig,
+    /// Configuration of the storage holding key material and ciphertexts.
+    pub blob_store: BlobStoreConfig,
     /// The endpoint for the exporter.
     pub exporter_endpoint: String,
     /// The name of the service.
@@ -39,6 +45,30 @@ pub struct Worker {
     pub concurrent: usize,
     /// The postgresql schema to use for the worker.
     pub schema: String,
+    /// Cron schedule (minute granularity) of the job expiring abandoned sessions.
+    #[serde(default = "default_expire_sessions_schedule")]
+    pub expire_sessions_schedule: String,
+    /// Maximum number of Phantom servers, with their prepared keys, kept in memory.
+    #[serde(default = "default_server_cache_size")]
+    pub server_cache_size: NonZeroUsize,
+    /// Name of the Phantom parameter preset, see `phantom::param::PRESETS`.
+    #[serde(default = "default_param_preset")]
+    pub param_preset: String,
+}
+
+/// Default schedule of the session expiry job: every minute.
+fn default_expire_sessions_schedule() -> String {
+    "* * * * *".to_string()
+}
+
+/// Default Phantom parameter preset.
+fn default_param_preset() -> String {
+    DEFAULT_PRESET.to_string()
+}
+
+/// Default number of cached Phantom servers.
+fn default_server_cache_size() -> NonZeroUsize {
+    NonZeroUsize::new(16).unwrap()
 }
 
 #[derive(Deserialize, Debug, Clone)]
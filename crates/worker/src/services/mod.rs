@@ -1 +1,2 @@
+pub mod server_cache;
 pub mod session;
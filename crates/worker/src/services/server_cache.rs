@@ -0,0 +1,53 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use frog_core::entities::session::SessionId;
+use lru::LruCache;
+use phantom::native_ops::NativeOps;
+use phantom::server::PhantomServer;
+
+/// A bounded cache of the Phantom servers of recently processed sessions.
+///
+/// The bootstrapping key of a server is prepared into an evaluator once, and reused by every
+/// computation on the same keys instead of loading the server again. Entries are keyed by
+/// session ID and the hash of the stored server, which only changes when keys are aggregated
+/// into it: other updates of the session keep hitting the cache, while a server whose keys
+/// changed is never served stale. The least recently used entries are evicted first.
+pub struct PhantomServerCache {
+    servers: Mutex<LruCache<(SessionId, String), Arc<PhantomServer<NativeOps>>>>,
+}
+
+impl PhantomServerCache {
+    /// Creates a cache holding at most `capacity` servers.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            servers: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the server of a session stored with the given hash, if it is cached.
+    pub fn get(
+        &self,
+        session_id: &SessionId,
+        server_hash: &str,
+    ) -> Option<Arc<PhantomServer<NativeOps>>> {
+        self.servers
+            .lock()
+            .unwrap()
+            .get(&(session_id.clone(), server_hash.to_string()))
+            .cloned()
+    }
+
+    /// Caches the server of a session stored with the given hash.
+    pub fn insert(
+        &self,
+        session_id: SessionId,
+        server_hash: String,
+        phantom_server: Arc<PhantomServer<NativeOps>>,
+    ) {
+        self.servers
+            .lock()
+            .unwrap()
+            .put((session_id, server_hash), phantom_server);
+    }
+}
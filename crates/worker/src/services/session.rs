@@ -1,23 +1,69 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::SystemTime;
 
-use frog_core::entities::session::{SessionId, SessionStatus};
-use frog_core::ports::session::SessionPort;
+use frog_core::entities::client::ClientId;
+use frog_core::entities::computation::{ComputationId, ComputationStatus};
+use frog_core::entities::session::{ResultFormat, SessionEntity, SessionId, SessionStatus};
+use frog_core::errors::CoreError;
+use frog_core::ports::session::{update_with_retry, SessionPort};
+use phantom::function::FheFunction;
+use phantom::native_ops::NativeOps;
 use phantom::ops::Ops;
-use phantom::utils::fhe_function;
-use phantom_zone_evaluator::boolean::fhew::prelude::FheU64;
-use phantom_zone_evaluator::boolean::FheBool;
+use phantom::server::PhantomServer;
+use tracing::{info, warn};
 
 use crate::errors::AppError;
+use crate::services::server_cache::PhantomServerCache;
+
+/// Maximum number of overdue sessions expired in a single batch.
+const EXPIRE_BATCH_SIZE: usize = 100;
 
 /// Service for managing session-related operations.
 pub struct SessionService {
     session: Arc<dyn SessionPort + Sync + Send>,
+    /// Phantom servers of recently processed sessions, with their prepared keys.
+    server_cache: PhantomServerCache,
 }
 
 impl SessionService {
     /// Creates a new instance of `SessionService`.
-    pub fn new(session: Arc<dyn SessionPort + Sync + Send>) -> Self {
-        Self { session }
+    pub fn new(
+        session: Arc<dyn SessionPort + Sync + Send>,
+        server_cache: PhantomServerCache,
+    ) -> Self {
+        Self {
+            session,
+            server_cache,
+        }
+    }
+
+    /// Retrieves the Phantom server of a session, from the cache if it holds the stored one.
+    ///
+    /// On a cache miss the server is loaded, with the keys it was stored with, and cached.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session.
+    ///
+    /// # Returns
+    /// - `Err(AppError)` if the server of the session is missing.
+    async fn phantom_server(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<Arc<PhantomServer<NativeOps>>, AppError> {
+        let server_ref = self
+            .session
+            .get_server_ref(session_id.clone())
+            .await?
+            .ok_or_else(|| AppError::UnexpectedError("Phantom server is missing".into()))?;
+        if let Some(phantom_server) = self.server_cache.get(session_id, &server_ref.hash) {
+            return Ok(phantom_server);
+        }
+
+        let phantom_server = Arc::new(self.session.get_server(&server_ref).await?);
+        self.server_cache
+            .insert(session_id.clone(), server_ref.hash, phantom_server.clone());
+        Ok(phantom_server)
     }
 
     /// Aggregates bootstrapping key shares for a given session.
@@ -32,6 +78,15 @@ impl SessionService {
         // Retrieve the session entity from the session port.
         let mut session_entity = self.session.get(session_id.clone()).await?;
 
+        // Skip sessions that expired while the job was queued.
+        if session_entity.status != SessionStatus::WaitingForBootstrap {
+            warn!(
+                "Skipping bootstrapping key aggregation for session {} in status {}",
+                session_id, session_entity.status
+            );
+            return Ok(());
+        }
+
         // Collect non-empty bootstrapping key shares from the client info.
         let bs_key_shares = session_entity
             .client_info
@@ -66,9 +121,11 @@ impl SessionService {
         phantom_server.aggregate_bs_key_shares(&bs_key_shares);
 
         // Update the session status to indicate readiness for argument.
-        session_entity.status = SessionStatus::WaitingForArgument;
+        session_entity.transition(SessionStatus::WaitingForArgument);
 
-        // Save the updated session entity.
+        // Save the updated session entity. A concurrent update fails the job, and the retried job
+        // skips the session if it left the expected status in the meantime. The first
+        // computation loads the aggregated server and caches it.
         self.session.update(session_id, session_entity).await?;
         Ok(())
     }
@@ -82,61 +139,418 @@ impl SessionService {
     /// - `Ok(())` on success.
     /// - `Err(AppError)` if an error occurs during the computation.
     pub async fn compute_function(&self, session_id: SessionId) -> Result<(), AppError> {
-        // Retrieve the session entity from the session port.
-        let mut session_entity = self.session.get(session_id.clone()).await?;
+        // Retrieve the session entity from the session port, leaving its server to the cache.
+        let mut session_entity = self.session.get_without_server(session_id.clone()).await?;
+
+        // Skip sessions that expired while the job was queued.
+        if session_entity.status != SessionStatus::WaitingForArgument {
+            warn!(
+                "Skipping computation for session {} in status {}",
+                session_id, session_entity.status
+            );
+            return Ok(());
+        }
 
-        // Collect non-empty encrypted data from the client info.
-        let params = session_entity
+        // Retrieve the Phantom server, with its prepared bootstrapping key.
+        let phantom_server = self.phantom_server(&session_id).await?;
+
+        // Collect the encrypted data of every client, ordered by client ID.
+        let inputs = session_entity
             .client_info
             .values()
-            .filter_map(|client| {
-                if !client.encrypted_data.is_empty() {
-                    Some(client.encrypted_data.clone())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+            .map(|client| (client.id.clone(), &client.encrypted_data))
+            .collect::<BTreeMap<_, _>>();
 
-        // Ensure the session has a valid Phantom server instance.
-        let phantom_server = session_entity
-            .phantom_server
-            .as_mut()
-            .ok_or_else(|| AppError::UnexpectedError("Phantom server is missing".into()))?;
+        // An input that can never be computed fails the session right away.
+        let (encrypted_result, result_format) =
+            match evaluate(&phantom_server, &session_entity, &inputs) {
+                Ok(result) => result,
+                Err(AppError::InvalidInput(reason)) => return self.fail(session_id, reason).await,
+                Err(e) => return Err(e),
+            };
+
+        // Update the session entity with the result and mark it as done.
+        session_entity.encrypted_result = encrypted_result;
+        session_entity.result_format = result_format;
+        session_entity.transition(SessionStatus::Done);
+
+        // Save the result alone, leaving the clients and the server as stored. A concurrent update
+        // fails the job, and the retried job skips the session if it left the expected status in
+        // the meantime.
+        self.session.set_result(session_id, session_entity).await?;
+        Ok(())
+    }
+
+    /// Computes an additional computation of a session, reusing the keys of the session.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session the computation belongs to.
+    /// - `computation_id`: The ID of the computation within the session.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(AppError)` if an error occurs during the computation.
+    pub async fn compute_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+    ) -> Result<(), AppError> {
+        // Retrieve the session entity from the session port, leaving its server to the cache.
+        let mut session_entity = self.session.get_without_server(session_id.clone()).await?;
 
-        // Deserialize, wrap, and convert the encrypted data into `FheU64`.
-        let cts = params
+        // Skip computations of sessions that expired, or that ended, while the job was queued.
+        let status = session_entity
+            .computations
+            .get(&computation_id)
+            .map(|computation_entity| computation_entity.status.clone());
+        if session_entity.status != SessionStatus::Done
+            || status != Some(ComputationStatus::WaitingForArgument)
+        {
+            warn!(
+                "Skipping computation {} of session {} in status {}",
+                computation_id, session_id, session_entity.status
+            );
+            return Ok(());
+        }
+
+        // Retrieve the Phantom server, with its prepared bootstrapping key.
+        let phantom_server = self.phantom_server(&session_id).await?;
+
+        // Collect the inputs of the computation, ordered by client ID.
+        let inputs = session_entity.computations[&computation_id]
+            .inputs
             .iter()
-            .map(|bytes| {
-                let cts = phantom_server.deserialize_batched_ct(bytes).unwrap();
-                let wrapped_cts = phantom_server.wrap_batched_ct(&cts);
-                let wrapped_cts: [FheBool<_>; 64] =
-                    <[FheBool<_>; 64]>::try_from(wrapped_cts).expect("Incorrect vector length");
-                FheU64::new(wrapped_cts)
+            .map(|(client_id, data)| (client_id.clone(), data))
+            .collect::<BTreeMap<_, _>>();
+
+        // An input that can never be computed fails the computation, but not the session.
+        let (encrypted_result, result_format) =
+            match evaluate(&phantom_server, &session_entity, &inputs) {
+                Ok(result) => result,
+                Err(AppError::InvalidInput(reason)) => {
+                    return self
+                        .fail_computation(session_id, computation_id, reason)
+                        .await
+                }
+                Err(e) => return Err(e),
+            };
+
+        // Update the computation with the result and mark it as done.
+        let mut computation_entity = session_entity
+            .computations
+            .remove(&computation_id)
+            .expect("The computation was found above");
+        computation_entity.encrypted_result = encrypted_result;
+        computation_entity.result_format = result_format;
+        computation_entity.status = ComputationStatus::Done;
+
+        // Save the computation alone, leaving the rest of the session as stored. A concurrent
+        // update fails the job, and the retried job skips the computation if it left the expected
+        // status in the meantime.
+        self.session
+            .set_computation(session_id, session_entity.version, computation_entity)
+            .await?;
+        Ok(())
+    }
+
+    /// Expires every session that outlived its TTL or its current phase deadline.
+    ///
+    /// Expired sessions are moved to the `Expired` status and their key material and
+    /// ciphertexts are purged.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(AppError)` if an error occurs while expiring the sessions.
+    pub async fn expire_sessions(&self) -> Result<(), AppError> {
+        let now = SystemTime::now();
+        let mut expired = 0;
+
+        loop {
+            let session_ids = self.session.list_overdue(now, EXPIRE_BATCH_SIZE).await?;
+            if session_ids.is_empty() {
+                break;
+            }
+
+            let mut expired_in_batch = 0;
+            for session_id in session_ids {
+                let mut session_entity = self.session.get(session_id.clone()).await?;
+
+                // The session may have progressed since it was listed.
+                if !session_entity.is_overdue(now) {
+                    continue;
+                }
+
+                session_entity.terminate(SessionStatus::Expired);
+                match self.session.update(session_id, session_entity).await {
+                    Ok(_) => expired_in_batch += 1,
+                    // The session was updated concurrently, the next run checks it again.
+                    Err(CoreError::Conflict(_)) => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            // Stop once a batch makes no progress, so leftovers cannot loop forever.
+            if expired_in_batch == 0 {
+                break;
+            }
+            expired += expired_in_batch;
+        }
+
+        if expired > 0 {
+            info!("Expired {} sessions", expired);
+        }
+        Ok(())
+    }
+
+    /// Marks a session as failed after a worker gave up processing it.
+    ///
+    /// Sessions that already reached a terminal status are left untouched.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session that failed.
+    /// - `reason`: A description of the last error.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(AppError)` if an error occurs while updating the session.
+    pub async fn fail(&self, session_id: SessionId, reason: String) -> Result<(), AppError> {
+        warn!("Session {} failed: {}", session_id, reason);
+        update_with_retry(&*self.session, session_id, |session_entity| {
+            if session_entity.status.is_terminal() {
+                return Ok::<_, AppError>(false);
+            }
+
+            session_entity.terminate(SessionStatus::Failed {
+                reason: reason.clone(),
+            });
+            Ok(true)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Marks a computation of a session as failed after a worker gave up computing it.
+    ///
+    /// The session itself is left untouched, so that further computations can still be run with
+    /// its keys. Computations that already ended are left untouched as well.
+    ///
+    /// # Arguments
+    /// - `session_id`: The ID of the session the computation belongs to.
+    /// - `computation_id`: The ID of the computation that failed.
+    /// - `reason`: A description of the last error.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(AppError)` if an error occurs while updating the session.
+    pub async fn fail_computation(
+        &self,
+        session_id: SessionId,
+        computation_id: ComputationId,
+        reason: String,
+    ) -> Result<(), AppError> {
+        warn!(
+            "Computation {} of session {} failed: {}",
+            computation_id, session_id, reason
+        );
+        update_with_retry(&*self.session, session_id, |session_entity| {
+            let Some(computation_entity) = session_entity.computations.get_mut(&computation_id)
+            else {
+                return Ok::<_, AppError>(false);
+            };
+            if computation_entity.status.is_terminal() {
+                return Ok(false);
+            }
+
+            computation_entity.status = ComputationStatus::Failed {
+                reason: reason.clone(),
+            };
+            Ok(true)
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+/// Evaluates the function of a session over encrypted inputs.
+///
+/// # Arguments
+/// - `phantom_server`: The Phantom server of the session, with its prepared bootstrapping key.
+/// - `session_entity`: The session whose function and input layout are used.
+/// - `inputs`: The encrypted input of every client, ordered by client ID.
+///
+/// # Returns
+/// - The serialized ciphertexts of the encrypted result and their format. The bits are
+///   ring-packed, unless the ring-packing key of the session is missing, in which case each bit
+///   is kept in its own ciphertext.
+/// - `Err(AppError::InvalidInput)` if an input is missing or does not match the input layout of
+///   the session, so computing it again can never succeed.
+fn evaluate(
+    phantom_server: &PhantomServer<NativeOps>,
+    session_entity: &SessionEntity,
+    inputs: &BTreeMap<ClientId, &Vec<u8>>,
+) -> Result<(Vec<Vec<u8>>, ResultFormat), AppError> {
+    let session_id = &session_entity.id;
+
+    // Sessions can only be created with a function of the registry.
+    let function = FheFunction::by_name(&session_entity.settings.computation).ok_or_else(|| {
+        AppError::UnexpectedError(format!(
+            "Unknown computation '{}' for session {}",
+            session_entity.settings.computation, session_id
+        ))
+    })?;
+
+    let participant_number = session_entity.settings.participant_number;
+    if inputs.len() != participant_number {
+        return Err(AppError::InvalidInput(format!(
+            "Got {} inputs, expected one per participant for {} participants",
+            inputs.len(),
+            participant_number
+        )));
+    }
+    if let Some((client_id, _)) = inputs.iter().find(|(_, data)| data.is_empty()) {
+        return Err(AppError::InvalidInput(format!(
+            "Missing input of client {} in session {}",
+            client_id, session_id
+        )));
+    }
+
+    // Deserialize the encrypted data and wrap each of its bits.
+    let batched_cts = inputs
+        .values()
+        .map(|bytes| phantom_server.deserialize_batched_ct(bytes))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid encrypted input: {}", e)))?;
+    let wrapped_inputs = batched_cts
+        .iter()
+        .map(|cts| phantom_server.wrap_batched_ct(cts))
+        .collect::<Vec<_>>();
+
+    // An input of the wrong width can never be computed.
+    let input_type = session_entity.settings.input_type;
+    let input_len = session_entity.settings.input_len;
+    if let Some((client_id, bits)) = inputs
+        .keys()
+        .zip(&wrapped_inputs)
+        .find(|(_, bits)| bits.len() != input_len * input_type.bits())
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Input of client {} has {} bits, expected {} for {} values of type {}",
+            client_id,
+            bits.len(),
+            input_len * input_type.bits(),
+            input_len,
+            input_type
+        )));
+    }
+
+    // Compute the function chosen for the session.
+    let ct_out = function
+        .evaluate(input_type, input_len, wrapped_inputs)
+        .ok_or_else(|| {
+            AppError::UnexpectedError(format!(
+                "Inputs of session {} do not match computation '{}'",
+                session_id, function.name
+            ))
+        })?;
+
+    // Pack the bits of the result, so that clients decrypt them with one share per ciphertext.
+    let (encrypted_result, result_format) = if phantom_server.can_pack() {
+        let ring_size = phantom_server.ring_packing_param().ring_size;
+        let encrypted_result = ct_out
+            .chunks(ring_size)
+            .map(|bits| {
+                let packed_ct = phantom_server.pack(bits.iter().map(|bit| bit.ct()));
+                phantom_server.serialize_rp_ct(&packed_ct)
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>();
+        (encrypted_result, ResultFormat::Packed)
+    } else {
+        warn!(
+            "Session {} has no ring-packing key, leaving its result unpacked",
+            session_id
+        );
+        let encrypted_result = ct_out
+            .iter()
+            .map(|bit| phantom_server.serialize_ct(bit.ct()))
+            .collect::<Result<Vec<_>, _>>();
+        (encrypted_result, ResultFormat::Unpacked)
+    };
+    let encrypted_result = encrypted_result
+        .map_err(|e| AppError::UnexpectedError(format!("Failed to serialize the result: {}", e)))?;
+    Ok((encrypted_result, result_format))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::sync::RwLock;
+
+    use frog_adapter::in_memory::session::SessionInMemoryRepository;
+    use frog_adapter::in_memory::state::InMemoryState;
+    use frog_core::entities::computation::ComputationEntity;
+    use frog_core::entities::session::SessionSettings;
+    use phantom::crs::Crs;
+    use phantom::function::{ValueType, DEFAULT_FUNCTION};
+    use phantom::param::{ParamPreset, DEFAULT_PRESET};
+
+    use super::*;
 
-        // Compute the function on the first two ciphertexts (example use-case).
-        let ct_out = fhe_function(
-            cts.first()
-                .ok_or_else(|| AppError::UnexpectedError("Missing first ciphertext".into()))?,
-            cts.get(1)
-                .ok_or_else(|| AppError::UnexpectedError("Missing second ciphertext".into()))?,
+    fn settings() -> SessionSettings {
+        SessionSettings {
+            participant_number: 2,
+            computation: DEFAULT_FUNCTION.to_string(),
+            input_type: ValueType::U64,
+            input_len: 1,
+            labels: Default::default(),
+            ttl_secs: 60,
+            phase_timeout_secs: 60,
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_the_cached_server_until_its_keys_change() {
+        let state = Arc::new(RwLock::new(InMemoryState::default()));
+        let session = Arc::new(SessionInMemoryRepository::new(state.clone()));
+        let service = SessionService::new(
+            session.clone(),
+            PhantomServerCache::new(NonZeroUsize::new(4).unwrap()),
         );
+        let param_preset = ParamPreset::by_name(DEFAULT_PRESET).unwrap();
+        let session_id = session
+            .create(SessionEntity::new(
+                SessionId::generate(),
+                settings(),
+                param_preset,
+                Crs::from_entropy(),
+            ))
+            .await
+            .unwrap();
 
-        // Serialize the computed result back into bytes.
-        let ct_out = ct_out
-            .cts()
-            .into_iter()
-            .map(|t| phantom_server.serialize_ct(t).unwrap())
-            .collect::<Vec<_>>();
+        let first = service.phantom_server(&session_id).await.unwrap();
 
-        // Update the session entity with the result and mark it as done.
-        session_entity.encrypted_result = ct_out;
-        session_entity.status = SessionStatus::Done;
+        // A second computation hits the cache, even after an update that left the server alone.
+        session
+            .set_computation(
+                session_id.clone(),
+                0,
+                ComputationEntity::new(ComputationId(2)),
+            )
+            .await
+            .unwrap();
+        let second = service.phantom_server(&session_id).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
 
-        // Save the updated session entity.
-        self.session.update(session_id, session_entity).await?;
-        Ok(())
+        // A server with other keys is loaded again.
+        let other_server =
+            PhantomServer::new(param_preset.param, Crs::from_entropy(), None, None, None).unwrap();
+        state
+            .write()
+            .unwrap()
+            .sessions
+            .get_mut(&session_id)
+            .unwrap()
+            .phantom_server = Some(other_server);
+        let third = service.phantom_server(&session_id).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
     }
 }
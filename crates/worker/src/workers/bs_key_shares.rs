@@ -6,6 +6,7 @@ use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::app_state::AppState;
+use crate::workers::fail_on_last_attempt;
 
 /// Worker responsible for aggregating bootstrapping key shares.
 #[derive(Deserialize, Serialize, Debug)]
@@ -38,9 +39,16 @@ impl TaskHandler for BsKeySharesWorker {
             .get::<AppState>()
             .expect("AppState must be added to the worker context");
 
-        state
+        let session_id = self.0.data;
+        let result = state
             .session_service
-            .aggregate_bs_key_shares(self.0.data)
-            .await
+            .aggregate_bs_key_shares(session_id.clone())
+            .await;
+
+        // Record the failure once the job runs out of retries.
+        if let Err(e) = &result {
+            fail_on_last_attempt(&ctx, state, session_id, e).await;
+        }
+        result
     }
 }
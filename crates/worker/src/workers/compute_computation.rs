@@ -0,0 +1,56 @@
+use frog_common::workers::{WorkerPayload, COMPUTE_COMPUTATION_WORKER_IDENTIFIER};
+use frog_core::entities::computation::ComputationId;
+use frog_core::entities::session::SessionId;
+use graphile_worker::{IntoTaskHandlerResult, TaskHandler, WorkerContext};
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::app_state::AppState;
+use crate::workers::fail_computation_on_last_attempt;
+
+/// Worker responsible for executing an additional computation of a session on encrypted data.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ComputeComputationWorker(WorkerPayload<(SessionId, ComputationId)>);
+
+impl TaskHandler for ComputeComputationWorker {
+    /// Unique identifier for the worker, used for task scheduling and execution.
+    const IDENTIFIER: &'static str = COMPUTE_COMPUTATION_WORKER_IDENTIFIER;
+
+    /// Executes the task to compute an additional computation on encrypted data.
+    ///
+    /// # Arguments
+    /// - `ctx`: The worker context, providing access to extensions and other runtime parameters.
+    ///
+    /// # Returns
+    /// - `impl IntoTaskHandlerResult`: Result of the task execution, adhering to the worker interface.
+    async fn run(self, ctx: WorkerContext) -> impl IntoTaskHandlerResult {
+        // Extract the current tracing span to ensure tracing context is carried across task boundaries.
+        let span = Span::current();
+        let parent_cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&self.0.tracing)
+        });
+
+        // Set the parent span for proper telemetry tracking.
+        span.set_parent(parent_cx);
+
+        // Access shared application state via the worker's context.
+        let state = ctx
+            .extensions()
+            .get::<AppState>()
+            .expect("AppState must be added to the worker context");
+
+        // Delegate the task to the session service and handle errors if any.
+        let (session_id, computation_id) = self.0.data;
+        let result = state
+            .session_service
+            .compute_computation(session_id.clone(), computation_id)
+            .await;
+
+        // Record the failure of the computation once the job runs out of retries.
+        if let Err(e) = &result {
+            fail_computation_on_last_attempt(&ctx, state, session_id, computation_id, e).await;
+        }
+        result
+    }
+}
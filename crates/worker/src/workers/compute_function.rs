@@ -6,6 +6,7 @@ use tracing::Span;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::app_state::AppState;
+use crate::workers::fail_on_last_attempt;
 
 /// Worker responsible for executing a computation function on encrypted data.
 #[derive(Deserialize, Serialize, Debug)]
@@ -39,6 +40,16 @@ impl TaskHandler for ComputeFunctionWorker {
             .expect("AppState must be added to the worker context");
 
         // Delegate the task to the session service and handle errors if any.
-        state.session_service.compute_function(self.0.data).await
+        let session_id = self.0.data;
+        let result = state
+            .session_service
+            .compute_function(session_id.clone())
+            .await;
+
+        // Record the failure once the job runs out of retries.
+        if let Err(e) = &result {
+            fail_on_last_attempt(&ctx, state, session_id, e).await;
+        }
+        result
     }
 }
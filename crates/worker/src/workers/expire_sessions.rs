@@ -0,0 +1,33 @@
+use frog_common::workers::EXPIRE_SESSIONS_WORKER_IDENTIFIER;
+use graphile_worker::{IntoTaskHandlerResult, TaskHandler, WorkerContext};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::AppState;
+
+/// Worker responsible for expiring sessions that outlived their deadlines.
+///
+/// This job is scheduled by the worker crontab, so it does not carry any session or tracing data.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ExpireSessionsWorker {}
+
+impl TaskHandler for ExpireSessionsWorker {
+    /// Unique identifier for the worker, used for task scheduling and execution.
+    const IDENTIFIER: &'static str = EXPIRE_SESSIONS_WORKER_IDENTIFIER;
+
+    /// Expires all overdue sessions.
+    ///
+    /// # Arguments
+    /// - `ctx`: The worker context, providing access to extensions and other runtime parameters.
+    ///
+    /// # Returns
+    /// - `impl IntoTaskHandlerResult`: Result of the task execution, adhering to the worker interface.
+    async fn run(self, ctx: WorkerContext) -> impl IntoTaskHandlerResult {
+        // Access shared application state via the worker's context.
+        let state = ctx
+            .extensions()
+            .get::<AppState>()
+            .expect("AppState must be added to the worker context");
+
+        state.session_service.expire_sessions().await
+    }
+}
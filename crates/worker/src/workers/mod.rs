@@ -1,2 +1,71 @@
+use frog_core::entities::computation::ComputationId;
+use frog_core::entities::session::SessionId;
+use graphile_worker::WorkerContext;
+use tracing::error;
+
+use crate::app_state::AppState;
+use crate::errors::AppError;
+
 pub mod bs_key_shares;
+pub mod compute_computation;
 pub mod compute_function;
+pub mod expire_sessions;
+
+/// Marks the session as failed if the job errored on its last attempt.
+///
+/// Earlier attempts are left to the worker retry policy.
+///
+/// # Arguments
+/// - `ctx`: The worker context of the failed job.
+/// - `state`: The shared application state.
+/// - `session_id`: The ID of the session processed by the job.
+/// - `err`: The error returned by the job.
+pub async fn fail_on_last_attempt(
+    ctx: &WorkerContext,
+    state: &AppState,
+    session_id: SessionId,
+    err: &AppError,
+) {
+    if ctx.job().attempts() < ctx.job().max_attempts() {
+        return;
+    }
+
+    if let Err(e) = state
+        .session_service
+        .fail(session_id, err.to_string())
+        .await
+    {
+        error!("Failed to record the session failure: {}", e);
+    }
+}
+
+/// Marks a computation of a session as failed if the job errored on its last attempt.
+///
+/// Earlier attempts are left to the worker retry policy, and the session itself is left
+/// untouched.
+///
+/// # Arguments
+/// - `ctx`: The worker context of the failed job.
+/// - `state`: The shared application state.
+/// - `session_id`: The ID of the session the computation belongs to.
+/// - `computation_id`: The ID of the computation processed by the job.
+/// - `err`: The error returned by the job.
+pub async fn fail_computation_on_last_attempt(
+    ctx: &WorkerContext,
+    state: &AppState,
+    session_id: SessionId,
+    computation_id: ComputationId,
+    err: &AppError,
+) {
+    if ctx.job().attempts() < ctx.job().max_attempts() {
+        return;
+    }
+
+    if let Err(e) = state
+        .session_service
+        .fail_computation(session_id, computation_id, err.to_string())
+        .await
+    {
+        error!("Failed to record the computation failure: {}", e);
+    }
+}
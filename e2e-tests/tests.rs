@@ -24,11 +24,19 @@ impl Setup {
         );
         info!("✅ PostgresDB setup completed; URL: {}", &database_url);
 
+        // The server and the worker share the blobs through a local directory
+        let blob_store_root = std::env::temp_dir()
+            .join(format!("frog-blobs-{}", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
         Self {
             postgres_instance,
             envs: vec![
                 ("PG__URL".to_string(), database_url),
                 ("PG__MAX_SIZE".to_string(), "10".to_string()),
+                ("BLOB_STORE__KIND".to_string(), "fs".to_string()),
+                ("BLOB_STORE__ROOT".to_string(), blob_store_root),
             ],
         }
     }
@@ -36,10 +44,12 @@ impl Setup {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::time::Duration;
 
     use e2e_tests::utils::get_free_port;
     use reqwest::Client;
+    use serde_json::json;
     use test_log::test;
     use tokio::time::sleep;
 
@@ -49,18 +59,10 @@ mod tests {
     async fn test_full_flow() {
         let setup_config = Setup::new().await;
 
-        let crs_seed = "crs_seed_32_bytes_123456789_123456789_123456789".to_string();
-        let session_id = "f8e774bd-2f9d-4502-92ca-ac8b9c25868e".to_string();
-
         // Run server
         let mut server_envs = setup_config.envs.clone();
         server_envs.append(&mut vec![
             ("SERVICE_NAME".to_string(), "server".to_string()),
-            ("PHANTOM_SERVER__CRS_SEED".to_string(), crs_seed.clone()),
-            (
-                "PHANTOM_SERVER__PARTICIPANT_NUMBER".to_string(),
-                "2".to_string(),
-            ),
             ("WORKER__SCHEMA".to_string(), "worker".to_string()),
             (
                 "EXPORTER_ENDPOINT".to_string(),
@@ -72,6 +74,23 @@ mod tests {
 
         let server_endpoint = format!("http://{}:{}", server.url, server.port);
 
+        // Create a new session for the clients to join
+        let client = Client::new();
+        let session_id: String = client
+            .post(format!("{}/v1/sessions", server_endpoint))
+            .json(&json!({
+                "participant_number": 2,
+                "computation": "sum",
+                "labels": { "test": "full_flow" },
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        info!("✅ Session created; ID: {}", &session_id);
+
         // Run worker
         let mut worker_envs = setup_config.envs.clone();
         worker_envs.append(&mut vec![
@@ -93,14 +112,14 @@ mod tests {
         }
         let mut clients_info = vec![];
         for i in 0..2 {
-            let mut client_ports = client_ports.clone();
-            let port = client_ports.remove(i);
+            let port = client_ports[i];
             let mut client_endpoints = client_ports
-                .into_iter()
+                .iter()
                 .enumerate()
-                .map(|(i, e)| {
+                .filter(|(peer_id, _)| *peer_id != i)
+                .map(|(peer_id, e)| {
                     (
-                        format!("CLIENT__PEER_ENDPOINTS__{}", i),
+                        format!("CLIENT__PEER_ENDPOINTS__{}", peer_id),
                         format!("http://127.0.0.1:{}", e),
                     )
                 })
@@ -114,9 +133,9 @@ mod tests {
                     server_endpoint.clone(),
                 ),
                 ("CLIENT__CLIENT_ID".to_string(), i.to_string()),
-                ("CLIENT__CRS_SEED".to_string(), crs_seed.clone()),
                 ("CLIENT__SESSION_ID".to_string(), session_id.clone()),
                 ("CLIENT__CLIENT_SEED".to_string(), format!("client_{}", i)),
+                ("CLIENT__INPUT".to_string(), "6".to_string()),
                 (
                     "EXPORTER_ENDPOINT".to_string(),
                     "127.0.0.1:3000".to_string(),
@@ -133,7 +152,6 @@ mod tests {
         sleep(Duration::from_secs(30)).await;
 
         let max_tries = 400;
-        let client = Client::new();
         for _ in 0..max_tries {
             let mut success_count = 0;
             for client_port in client_ports.clone() {
@@ -142,8 +160,8 @@ mod tests {
                     .send()
                     .await
                     .unwrap();
-                let result: Option<u64> = response.json().await.unwrap();
-                if result.is_some_and(|r| r == 12) {
+                let result: Option<HashMap<String, Vec<i64>>> = response.json().await.unwrap();
+                if result.is_some_and(|r| r.get("sum").is_some_and(|sum| *sum == [12])) {
                     success_count += 1;
                 }
             }